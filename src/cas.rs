@@ -1,4 +1,30 @@
+pub(crate) mod assume;
+pub(crate) mod bits;
+pub(crate) mod constant;
+pub(crate) mod diff;
 pub(crate) mod env;
 pub(crate) mod expr;
+pub(crate) mod gradient;
+pub(crate) mod infinity;
+pub(crate) mod integrate;
+pub(crate) mod interval;
 pub(crate) mod lexer;
+pub(crate) mod limit;
+pub(crate) mod log;
+pub(crate) mod nintegrate;
+pub(crate) mod nsolve;
+pub(crate) mod ntheory;
+pub(crate) mod ode;
+pub(crate) mod optimize;
 pub(crate) mod parser;
+pub(crate) mod poly;
+pub(crate) mod rule;
+pub(crate) mod series;
+pub(crate) mod simplify;
+pub(crate) mod special;
+pub(crate) mod stats;
+pub(crate) mod sum;
+pub(crate) mod transform;
+pub(crate) mod trig;
+pub(crate) mod uncertain;
+pub(crate) mod unit;
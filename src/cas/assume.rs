@@ -0,0 +1,494 @@
+use {
+    super::{
+        env::{Assumption, Env},
+        expr::{
+            atom::{number::Number, symbol::Symbol, Atom},
+            call::{op::Op, Call},
+            Expr, TypeErr,
+        },
+    },
+    std::convert::TryFrom,
+};
+
+/// Values `lower .. upper` an expression can take, an end is excluded if it is open.
+#[derive(Clone, Copy)]
+struct Range {
+    lower: f64,
+    lower_open: bool,
+    upper: f64,
+    upper_open: bool,
+}
+
+/// What is known about symbols, so that rules like `abs(a) -> a if a >= 0` can decide.
+/// Everything unknown is never assumed, a symbol could also be complex.
+impl Expr {
+    /// `assume(x > 0, ...)` bounds a symbol, `assume(n, integer)` gives it a property.
+    /// The properties are `integer`, `real`, `positive`, `negative`, `nonnegative`,
+    /// `nonpositive`, `nonzero` and `none`, which forgets everything about it.
+    pub(crate) fn assume(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg {
+                Expr::Call(Call { op, args: sides }) if sides.len() == 2 => {
+                    let (x, y) = (sides[0].eval(env)?, sides[1].eval(env)?);
+
+                    // the symbol may be on either side
+                    let (symbol, op, bound) = match (&x, &y) {
+                        (Expr::Atom(Atom::Symbol(symbol)), _) => (symbol, op.clone(), &y),
+                        (_, Expr::Atom(Atom::Symbol(symbol))) => (symbol, flip(op), &x),
+                        _ => return Err(TypeErr(format!("can not assume `{}`", arg))),
+                    };
+                    let bound = bound.float().ok_or_else(|| {
+                        TypeErr(format!("bound `{}` of `{}` is not a number", bound, symbol))
+                    })?;
+
+                    let mut assumption = env.assumption(symbol).copied().unwrap_or_default();
+                    assumption.real = true;
+                    match op {
+                        Op::More | Op::MoreEq => {
+                            assumption.lower =
+                                tighter(assumption.lower, (bound, op == Op::More), true)
+                        }
+                        Op::Less | Op::LessEq => {
+                            assumption.upper =
+                                tighter(assumption.upper, (bound, op == Op::Less), false)
+                        }
+                        Op::Neq if bound == 0.0 => assumption.nonzero = true,
+                        _ => return Err(TypeErr(format!("can not assume `{}`", arg))),
+                    }
+                    satisfiable(&assumption, symbol, &arg.to_string())?;
+                    env.assume(symbol.clone(), Some(assumption));
+                }
+                Expr::Atom(Atom::Symbol(symbol)) => {
+                    let property = match args.next() {
+                        Some(Expr::Atom(Atom::Symbol(Symbol(property)))) => property,
+                        _ => {
+                            return Err(TypeErr(format!(
+                                "expected property of `{}` like `integer`",
+                                symbol
+                            )))
+                        }
+                    };
+
+                    let mut assumption = env.assumption(symbol).copied().unwrap_or_default();
+                    assumption.real = true;
+                    match &property[..] {
+                        "integer" => assumption.integer = true,
+                        "real" => {}
+                        "positive" => {
+                            assumption.lower = tighter(assumption.lower, (0.0, true), true)
+                        }
+                        "negative" => {
+                            assumption.upper = tighter(assumption.upper, (0.0, true), false)
+                        }
+                        "nonnegative" => {
+                            assumption.lower = tighter(assumption.lower, (0.0, false), true)
+                        }
+                        "nonpositive" => {
+                            assumption.upper = tighter(assumption.upper, (0.0, false), false)
+                        }
+                        "nonzero" => assumption.nonzero = true,
+                        "none" => {
+                            env.assume(symbol.clone(), None);
+                            continue;
+                        }
+                        _ => return Err(TypeErr(format!("unknown property `{}`", property))),
+                    }
+                    satisfiable(&assumption, symbol, &format!("{} {}", symbol, property))?;
+                    env.assume(symbol.clone(), Some(assumption));
+                }
+                _ => return Err(TypeErr(format!("can not assume `{}`", arg))),
+            }
+        }
+
+        Ok(Expr::Atom(Atom::Symbol(Symbol("Def".to_string()))))
+    }
+
+    /// `refine(expr)` simplifies again, now under the current assumptions.
+    pub(crate) fn refine(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        match args {
+            [expr] => expr.eval(env)?.eval(env),
+            _ => Err(TypeErr(
+                "call `refine` undefined on (expr, ...), expected (expr)".to_string(),
+            )),
+        }
+    }
+
+    /// `is(expr, property)` is `true` if the property is known to hold, otherwise `false`.
+    pub(crate) fn is(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (expr, property) = match args {
+            [expr, Expr::Atom(Atom::Symbol(Symbol(property)))] => (expr.eval(env)?, property),
+            _ => {
+                return Err(TypeErr(
+                    "call `is` undefined on (expr, ...), expected (expr, property)".to_string(),
+                ))
+            }
+        };
+
+        let holds = match &property[..] {
+            "integer" => expr.is_integer(env),
+            "real" => expr.is_real(env),
+            "positive" => expr.is_positive(env),
+            "negative" => Expr::neg(expr).is_positive(env),
+            "nonnegative" => expr.range(env).is_some_and(|range| range.lower >= 0.0),
+            "nonpositive" => expr.range(env).is_some_and(|range| range.upper <= 0.0),
+            "nonzero" => expr.is_nonzero(env),
+            _ => return Err(TypeErr(format!("unknown property `{}`", property))),
+        };
+
+        Ok(Expr::Atom(Atom::Symbol(Symbol(holds.to_string()))))
+    }
+
+    /// Decide a comparison, if the assumptions are enough for it.
+    pub(crate) fn holds(op: &Op, x: &Expr, y: &Expr, env: &Env) -> Option<bool> {
+        let range = Expr::sub(x.clone(), y.clone()).range(env)?;
+
+        match op {
+            Op::More => {
+                if range.lower > 0.0 || (range.lower == 0.0 && range.lower_open) {
+                    Some(true)
+                } else if range.upper <= 0.0 {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            Op::MoreEq => {
+                if range.lower >= 0.0 {
+                    Some(true)
+                } else if range.upper < 0.0 || (range.upper == 0.0 && range.upper_open) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            Op::Less | Op::LessEq => Expr::holds(&flip(op), y, x, env),
+            Op::Neq => {
+                if range.lower == 0.0 && range.upper == 0.0 {
+                    Some(false)
+                } else if Expr::sub(x.clone(), y.clone()).is_nonzero(env) {
+                    Some(true)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether an expression is known to be positive.
+    pub(crate) fn is_positive(&self, env: &Env) -> bool {
+        self.range(env)
+            .is_some_and(|range| range.lower > 0.0 || (range.lower == 0.0 && range.lower_open))
+    }
+
+    fn is_nonzero(&self, env: &Env) -> bool {
+        match self {
+            Expr::Atom(Atom::Symbol(symbol))
+                if env
+                    .assumption(symbol)
+                    .is_some_and(|assumption| assumption.nonzero) =>
+            {
+                true
+            }
+            Expr::Call(Call { op: Op::Mul, args }) => args.iter().all(|arg| arg.is_nonzero(env)),
+            _ => self.is_positive(env) || Expr::neg(self.clone()).is_positive(env),
+        }
+    }
+
+    fn is_integer(&self, env: &Env) -> bool {
+        match self {
+            Expr::Atom(Atom::Number(number)) => number.as_int().is_some(),
+            Expr::Atom(Atom::Symbol(symbol)) => env
+                .assumption(symbol)
+                .is_some_and(|assumption| assumption.integer),
+            Expr::Call(Call {
+                op: Op::Add | Op::Mul,
+                args,
+            }) => args.iter().all(|arg| arg.is_integer(env)),
+            Expr::Call(Call { op: Op::Pow, args }) => {
+                args[0].is_integer(env)
+                    && args[1].is_integer(env)
+                    && args[1].range(env).is_some_and(|range| range.lower >= 0.0)
+            }
+            _ => false,
+        }
+    }
+
+    fn is_real(&self, env: &Env) -> bool {
+        match self {
+            Expr::Atom(Atom::Symbol(symbol)) if env.assumption(symbol).is_some() => true,
+            Expr::Call(Call {
+                op: Op::Add | Op::Mul,
+                args,
+            }) => args.iter().all(|arg| arg.is_real(env)),
+            _ => self.range(env).is_some(),
+        }
+    }
+
+    /// Range of the values, if all symbols in it are real.
+    fn range(&self, env: &Env) -> Option<Range> {
+        if let Expr::Atom(Atom::Number(Number::Interval(lower, upper))) = self {
+            return Some(Range {
+                lower: *lower,
+                lower_open: false,
+                upper: *upper,
+                upper_open: false,
+            });
+        }
+        if let Some(value) = self.float() {
+            return if value.is_nan() {
+                None
+            } else {
+                Some(Range::point(value))
+            };
+        }
+
+        match self {
+            Expr::Atom(Atom::Symbol(symbol)) => {
+                let assumption = env.assumption(symbol)?;
+                let (lower, lower_open) = assumption.lower.unwrap_or((f64::NEG_INFINITY, true));
+                let (upper, upper_open) = assumption.upper.unwrap_or((f64::INFINITY, true));
+
+                Some(Range {
+                    lower,
+                    lower_open,
+                    upper,
+                    upper_open,
+                })
+            }
+            Expr::Call(Call { op: Op::Add, args }) => args
+                .iter()
+                .map(|arg| arg.range(env))
+                .reduce(|a, b| Some(a?.add(b?)))?,
+            Expr::Call(Call { op: Op::Mul, args }) => args
+                .iter()
+                .map(|arg| arg.range(env))
+                .reduce(|a, b| a?.mul(b?))?,
+            Expr::Call(Call { op: Op::Pow, args }) => {
+                let base = args[0].range(env)?;
+                match args[1].as_number() {
+                    Some(exp) => match exp.as_int() {
+                        Some(exp) => base.powi(exp),
+                        // roots of nonnegative numbers grow
+                        None if base.lower >= 0.0 => {
+                            base.monotone(|x| x.powf(exp.as_f64()), exp.is_negative())
+                        }
+                        None => None,
+                    },
+                    None if base.lower > 0.0 && args[1].range(env).is_some() => {
+                        Some(Range::above(0.0, true))
+                    }
+                    None => None,
+                }
+            }
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) if args.len() == 1 => {
+                let x = args[0].range(env)?;
+                match &call.0[..] {
+                    "exp" => x.monotone(f64::exp, false),
+                    "ln" if x.lower >= 0.0 => x.monotone(f64::ln, false),
+                    "atan" => x.monotone(f64::atan, false),
+                    "abs" if x.lower >= 0.0 => Some(x),
+                    "abs" if x.upper <= 0.0 => x.mul(Range::point(-1.0)),
+                    "abs" => Some(Range::above(0.0, false)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Range {
+    fn point(value: f64) -> Range {
+        Range {
+            lower: value,
+            lower_open: false,
+            upper: value,
+            upper_open: false,
+        }
+    }
+
+    fn above(lower: f64, lower_open: bool) -> Range {
+        Range {
+            lower,
+            lower_open,
+            upper: f64::INFINITY,
+            upper_open: true,
+        }
+    }
+
+    fn add(self, other: Range) -> Range {
+        Range {
+            lower: self.lower + other.lower,
+            lower_open: self.lower_open || other.lower_open,
+            upper: self.upper + other.upper,
+            upper_open: self.upper_open || other.upper_open,
+        }
+    }
+
+    fn mul(self, other: Range) -> Option<Range> {
+        let ends = |range: Range| {
+            [
+                (range.lower, range.lower_open),
+                (range.upper, range.upper_open),
+            ]
+        };
+
+        let mut corners = Vec::with_capacity(4);
+        for (x, x_open) in ends(self) {
+            for (y, y_open) in ends(other) {
+                let value = x * y;
+                if value.is_nan() {
+                    return None;
+                }
+                // a closed zero is reached, no matter the other factor
+                let reached = |end: f64, open: bool| end == 0.0 && !open;
+                let open = (x_open || y_open) && !reached(x, x_open) && !reached(y, y_open);
+                corners.push((value, open));
+            }
+        }
+
+        let lower = corners
+            .iter()
+            .map(|corner| corner.0)
+            .fold(f64::INFINITY, f64::min);
+        let upper = corners
+            .iter()
+            .map(|corner| corner.0)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        // an end is only open, if every corner there is
+        let open = |end: f64| {
+            corners
+                .iter()
+                .filter(|corner| corner.0 == end)
+                .all(|corner| corner.1)
+        };
+
+        Some(Range {
+            lower,
+            lower_open: open(lower),
+            upper,
+            upper_open: open(upper),
+        })
+    }
+
+    /// Integer power of a range.
+    fn powi(self, exp: i128) -> Option<Range> {
+        let exp = i32::try_from(exp).ok()?;
+
+        if exp < 0 {
+            // only without the pole at zero
+            let positive = self.lower > 0.0 || (self.lower == 0.0 && self.lower_open);
+            let negative = self.upper < 0.0 || (self.upper == 0.0 && self.upper_open);
+
+            return if positive || negative {
+                self.monotone(|x| x.powi(exp), positive || exp % 2 != 0)
+            } else {
+                None
+            };
+        }
+
+        if exp % 2 == 1 || self.lower >= 0.0 {
+            self.monotone(|x| x.powi(exp), false)
+        } else if self.upper <= 0.0 {
+            self.monotone(|x| x.powi(exp), true)
+        } else {
+            let (upper, upper_open) = if -self.lower > self.upper {
+                (self.lower, self.lower_open)
+            } else {
+                (self.upper, self.upper_open)
+            };
+
+            Some(Range {
+                lower: 0.0,
+                lower_open: false,
+                upper: upper.powi(exp),
+                upper_open,
+            })
+        }
+    }
+
+    /// Image under a monotone function, which swaps the ends if it is decreasing.
+    fn monotone(self, function: impl Fn(f64) -> f64, decreasing: bool) -> Option<Range> {
+        let (lower, upper) = (function(self.lower), function(self.upper));
+        if lower.is_nan() || upper.is_nan() {
+            return None;
+        }
+
+        Some(if decreasing {
+            Range {
+                lower: upper,
+                lower_open: self.upper_open,
+                upper: lower,
+                upper_open: self.lower_open,
+            }
+        } else {
+            Range {
+                lower,
+                lower_open: self.lower_open,
+                upper,
+                upper_open: self.upper_open,
+            }
+        })
+    }
+}
+
+/// The same comparison with both sides swapped.
+fn flip(op: &Op) -> Op {
+    match op {
+        Op::Less => Op::More,
+        Op::More => Op::Less,
+        Op::LessEq => Op::MoreEq,
+        Op::MoreEq => Op::LessEq,
+        op => op.clone(),
+    }
+}
+
+/// The tighter one of two lower or upper bounds.
+/// Is there any value left for the symbol, e.g. not after `x > 0` and `x < 0`?
+fn satisfiable(assumption: &Assumption, symbol: &Symbol, new: &str) -> Result<(), TypeErr> {
+    let (mut lower, lower_open) = assumption.lower.unwrap_or((f64::NEG_INFINITY, false));
+    let (mut upper, upper_open) = assumption.upper.unwrap_or((f64::INFINITY, false));
+
+    let empty = if assumption.integer {
+        // the integers in the range
+        lower = if lower_open {
+            lower.floor() + 1.0
+        } else {
+            lower.ceil()
+        };
+        upper = if upper_open {
+            upper.ceil() - 1.0
+        } else {
+            upper.floor()
+        };
+        lower > upper || (assumption.nonzero && lower == 0.0 && upper == 0.0)
+    } else {
+        lower > upper
+            || (lower == upper
+                && (lower_open || upper_open || (assumption.nonzero && lower == 0.0)))
+    };
+
+    if empty {
+        Err(TypeErr(format!(
+            "assumption `{}` contradicts what is known about `{}`, `assume({}, none)` forgets it",
+            new, symbol, symbol
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+fn tighter(old: Option<(f64, bool)>, new: (f64, bool), lower: bool) -> Option<(f64, bool)> {
+    Some(match old {
+        Some(old) if (old.0 > new.0) == lower && old.0 != new.0 => old,
+        Some(old) if old.0 == new.0 => (old.0, old.1 || new.1),
+        _ => new,
+    })
+}
@@ -0,0 +1,267 @@
+use {
+    super::{
+        env::Env,
+        expr::{
+            atom::{
+                number::{Number, Width},
+                symbol::Symbol,
+                Atom,
+            },
+            call::op::Op,
+            Expr, TypeErr,
+        },
+        ntheory::{integer_list, integers},
+    },
+    std::convert::TryFrom,
+};
+
+/// Digits of all bases up to 36.
+const DIGITS: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Integers in other bases, bitwise operations on them in two's complement,
+/// integers of fixed width and the bits of floats.
+impl Expr {
+    /// `base(n, b)` is the symbol of the digits, like `0xFF` or `12₃`.
+    pub(crate) fn base(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (args, width) = Expr::widths(args, env)?;
+        let [n, base] = integers::<2>("base", &args, env)?;
+        if !(2..=36).contains(&base) {
+            return Err(TypeErr(format!(
+                "call `base` expected a base from 2 to 36, found `{}`",
+                base
+            )));
+        }
+
+        Ok(digits(n, base as u128, width))
+    }
+
+    pub(crate) fn hex(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (args, width) = Expr::widths(args, env)?;
+        let [n] = integers::<1>("hex", &args, env)?;
+        Ok(digits(n, 16, width))
+    }
+
+    pub(crate) fn oct(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (args, width) = Expr::widths(args, env)?;
+        let [n] = integers::<1>("oct", &args, env)?;
+        Ok(digits(n, 8, width))
+    }
+
+    pub(crate) fn bin(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (args, width) = Expr::widths(args, env)?;
+        let [n] = integers::<1>("bin", &args, env)?;
+        Ok(digits(n, 2, width))
+    }
+
+    pub(crate) fn and(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (args, width) = Expr::widths(args, env)?;
+        let numbers = integer_list("and", &args, env)?;
+        Ok(fixed(numbers.into_iter().fold(-1, |a, b| a & b), width))
+    }
+
+    pub(crate) fn or(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (args, width) = Expr::widths(args, env)?;
+        let numbers = integer_list("or", &args, env)?;
+        Ok(fixed(numbers.into_iter().fold(0, |a, b| a | b), width))
+    }
+
+    pub(crate) fn xor(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (args, width) = Expr::widths(args, env)?;
+        let numbers = integer_list("xor", &args, env)?;
+        Ok(fixed(numbers.into_iter().fold(0, |a, b| a ^ b), width))
+    }
+
+    /// `shl(n, k)` is `n 2^k`, as long as it fits.
+    pub(crate) fn shl(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (args, width) = Expr::widths(args, env)?;
+        let [n, k] = integers::<2>("shl", &args, env)?;
+        shift(n, k, width)
+    }
+
+    /// `shr(n, k)` is `floor(n / 2^k)`, so the sign is kept.
+    pub(crate) fn shr(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (args, width) = Expr::widths(args, env)?;
+        let [n, k] = integers::<2>("shr", &args, env)?;
+        shift(n, k.checked_neg().unwrap_or(i128::MAX), width)
+    }
+
+    /// `u8(x)` up to `i64(x)` wrap integers around like a cast,
+    /// and other numbers are rounded towards zero and saturated.
+    pub(crate) fn fixed(call: &str, args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let width = Width::parse(call).expect("called with a width");
+
+        match args {
+            [x] => match x.eval(env)?.as_number() {
+                Some(number) => Ok(Expr::num(match number.as_int() {
+                    Some(n) => width.fit(n),
+                    None if number.as_f64().is_nan() => width.fit(0),
+                    // `i64::MAX` is no float, so clamp after the cast, which saturates at `i128`
+                    None => Number::Fixed(
+                        (number.as_f64() as i128).clamp(width.min(), width.max()),
+                        width,
+                    ),
+                })),
+                None => Err(TypeErr(format!(
+                    "call `{}` expected number, found `{}`",
+                    call, x
+                ))),
+            },
+            _ => Err(TypeErr(format!(
+                "call `{}` undefined on (expr, ...), expected (number)",
+                call
+            ))),
+        }
+    }
+
+    /// `saturating(x)` and `wrapping(x)` change what happens on overflow.
+    pub(crate) fn overflow(call: &str, args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        match args {
+            [x] => match x.eval(env)?.as_number() {
+                Some(Number::Fixed(n, width)) => Ok(Expr::num(Number::Fixed(
+                    n,
+                    Width {
+                        saturating: call == "saturating",
+                        ..width
+                    },
+                ))),
+                _ => Err(TypeErr(format!(
+                    "call `{}` expected integer of fixed width like `u8(x)`, found `{}`",
+                    call, x
+                ))),
+            },
+            _ => Err(TypeErr(format!(
+                "call `{}` undefined on (expr, ...), expected (integer)",
+                call
+            ))),
+        }
+    }
+
+    /// `a / b` or `a % b` of integers of fixed width, which are undefined for `b = 0`.
+    pub(crate) fn fixed_quotient(op: &Op, a: Number, b: Number) -> Result<Expr, TypeErr> {
+        if b.is_zero() {
+            return Err(TypeErr(format!(
+                "op `{}` undefined on `{}` and zero",
+                op,
+                Expr::num(a)
+            )));
+        }
+
+        Ok(Expr::num(match op {
+            Op::Mod => a.modulus(b),
+            _ => a / b,
+        }))
+    }
+
+    /// `ieee(x)` are the bits of sign, exponent and mantissa of the float.
+    pub(crate) fn ieee(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let bits = match args {
+            [x] => x.number(env)?.as_f64().to_bits(),
+            _ => {
+                return Err(TypeErr(
+                    "call `ieee` undefined on (expr, ...), expected (number)".to_string(),
+                ))
+            }
+        };
+
+        Ok(Expr::Atom(Atom::Symbol(Symbol(format!(
+            "{} {:011b} {:052b}",
+            bits >> 63,
+            (bits >> 52) & 0x7FF,
+            bits & ((1 << 52) - 1)
+        )))))
+    }
+
+    /// Evaluated arguments and the width all integers with one are promoted to.
+    fn widths(args: &[Expr], env: &mut Env) -> Result<(Vec<Expr>, Option<Width>), TypeErr> {
+        let mut values = Vec::with_capacity(args.len());
+        for arg in args {
+            values.push(arg.eval(env)?);
+        }
+
+        let width = values
+            .iter()
+            .filter_map(|value| match value.as_number() {
+                Some(Number::Fixed(_, width)) => Some(width),
+                _ => None,
+            })
+            .reduce(Width::promote);
+
+        Ok((values, width))
+    }
+}
+
+/// The integer in the width, if there is one.
+fn fixed(n: i128, width: Option<Width>) -> Expr {
+    match width {
+        Some(width) => Expr::num(width.fit(n)),
+        None => Expr::int(n),
+    }
+}
+
+/// Shift to the left by `k` bits, or to the right for negative `k`.
+fn shift(n: i128, k: i128, width: Option<Width>) -> Result<Expr, TypeErr> {
+    if k < 0 {
+        return Ok(fixed(n >> k.unsigned_abs().min(127), width));
+    }
+    if let Some(width) = width {
+        return Ok(Expr::num(width.shl(n, k as u128)));
+    }
+
+    u32::try_from(k)
+        .ok()
+        .filter(|k| *k < 127)
+        .and_then(|k| n.checked_mul(1 << k))
+        .map(Expr::int)
+        .ok_or_else(|| {
+            TypeErr(format!(
+                "`{}` shifted by `{}` does not fit into an integer",
+                n, k
+            ))
+        })
+}
+
+/// Digits with the prefix of the base, or the base as a subscript.
+/// Integers of fixed width have all digits of their two's complement.
+fn digits(n: i128, base: u128, width: Option<Width>) -> Expr {
+    let (mut rest, sign, length) = match width {
+        Some(width) => {
+            let bits = (n as u128) & ((1 << width.bits) - 1);
+            let per_digit = 128 - (base - 1).leading_zeros();
+            let length = if base.is_power_of_two() {
+                width.bits.div_ceil(per_digit)
+            } else {
+                1
+            };
+
+            (bits, "", length as usize)
+        }
+        None => (n.unsigned_abs(), if n < 0 { "-" } else { "" }, 1),
+    };
+
+    let mut digits = Vec::new();
+    while rest > 0 || digits.len() < length {
+        digits.push(DIGITS[(rest % base) as usize] as char);
+        rest /= base;
+    }
+
+    let digits: String = digits.into_iter().rev().collect();
+
+    Expr::Atom(Atom::Symbol(Symbol(match base {
+        2 => format!("{}0b{}", sign, digits),
+        8 => format!("{}0o{}", sign, digits),
+        16 => format!("{}0x{}", sign, digits),
+        _ => {
+            let subscript: String = base
+                .to_string()
+                .chars()
+                .map(|digit| {
+                    "₀₁₂₃₄₅₆₇₈₉"
+                        .chars()
+                        .nth(digit as usize - '0' as usize)
+                        .unwrap_or(digit)
+                })
+                .collect();
+            format!("{}{}{}", sign, digits, subscript)
+        }
+    })))
+}
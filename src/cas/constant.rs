@@ -0,0 +1,159 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::{number::Number, symbol::Symbol, Atom},
+        call::{op::Op, Call},
+        Expr, TypeErr,
+    },
+};
+
+/// Most significant digits of an `f64`.
+const MAX_DIGITS: i128 = 17;
+
+/// The exact constants `e` and `i`, `π` lives with the trigonometric functions.
+/// `e^x` is always written as `exp x`, so there is only one form of it.
+impl Expr {
+    pub(crate) fn e() -> Expr {
+        Expr::Atom(Atom::Symbol(Symbol("e".to_string())))
+    }
+
+    pub(crate) fn i() -> Expr {
+        Expr::Atom(Atom::Symbol(Symbol("i".to_string())))
+    }
+
+    /// `N(expr, digits)` is the number of an expression, rounded to significant digits.
+    pub(crate) fn numeric(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (expr, digits) = match args {
+            [expr] => (expr, MAX_DIGITS),
+            [expr, digits] => (
+                expr,
+                digits
+                    .eval(env)?
+                    .as_number()
+                    .and_then(|digits| digits.as_int())
+                    .filter(|digits| (1..=MAX_DIGITS).contains(digits))
+                    .ok_or_else(|| {
+                        TypeErr(format!(
+                            "digits `{}` are no integer from 1 to {}",
+                            digits, MAX_DIGITS
+                        ))
+                    })?,
+            ),
+            _ => {
+                return Err(TypeErr(
+                    "call `N` undefined on (expr, ...), expected (expr, integer)".to_string(),
+                ))
+            }
+        };
+
+        let value = expr.number(env)?.as_f64();
+        let rounded = format!("{:.*e}", digits as usize - 1, value)
+            .parse()
+            .unwrap_or(value);
+
+        Ok(Expr::num(Number::Float(rounded)))
+    }
+
+    /// Powers of `e`, `exp` and `i`, and square roots of negative numbers.
+    pub(crate) fn constant_pow(base: &Expr, exp: &Expr) -> Option<Expr> {
+        if *base == Expr::e() {
+            return Some(Expr::function(Symbol("exp".to_string()), exp.clone()));
+        }
+
+        let exp = exp.as_number()?;
+
+        // exp(a)^b = exp(a b)
+        if let Expr::Call(Call {
+            op: Op::Call(call),
+            args,
+        }) = base
+        {
+            if call.0 == "exp" {
+                return Some(Expr::function(
+                    call.clone(),
+                    Expr::mul(vec![args[0].clone(), Expr::num(exp)]),
+                ));
+            }
+        }
+
+        if *base == Expr::i() {
+            return Some(match exp.as_int()?.rem_euclid(4) {
+                0 => Expr::int(1),
+                1 => Expr::i(),
+                2 => Expr::int(-1),
+                _ => Expr::neg(Expr::i()),
+            });
+        }
+
+        // sqrt(-x) = i sqrt(x)
+        match base.as_number() {
+            Some(base) if base.is_exact() && base.is_negative() && exp == Number::ratio(1, 2) => {
+                Some(Expr::mul(vec![
+                    Expr::i(),
+                    Expr::pow(Expr::num(-base), Expr::num(exp)),
+                ]))
+            }
+            _ => None,
+        }
+    }
+
+    /// Exponential, logarithm and Gamma function, where they are exact.
+    pub(crate) fn exact_constant(call: &str, x: &Expr) -> Option<Expr> {
+        match (call, x) {
+            ("gamma", _) => half_gamma(x.as_number()?),
+            ("fact", _) => half_gamma(x.as_number()? + Number::int(1)),
+            ("exp", _) if *x == Expr::int(1) => Some(Expr::e()),
+            ("ln", _) if *x == Expr::e() => Some(Expr::int(1)),
+            // only real exponents can be taken back
+            (
+                "ln",
+                Expr::Call(Call {
+                    op: Op::Call(call),
+                    args,
+                }),
+            ) if call.0 == "exp" && args[0].float().is_some() => Some(args[0].clone()),
+            ("exp", Expr::Call(Call { op: Op::Mul, args })) => {
+                // Euler's formula, exact at multiples of `π`
+                let position = args.iter().position(|arg| *arg == Expr::i())?;
+                let mut angle = args.clone();
+                angle.remove(position);
+                let angle = Expr::mul(angle);
+                angle.float()?;
+
+                let (cos, sin) = (
+                    Expr::function(Symbol("cos".to_string()), angle.clone()),
+                    Expr::function(Symbol("sin".to_string()), angle),
+                );
+                if cos.calls("cos") || sin.calls("sin") {
+                    return None;
+                }
+
+                Some(Expr::add(vec![cos, Expr::mul(vec![Expr::i(), sin])]))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// `Γ(n + 1/2) = (2n)! / (4^n n!) sqrt π`, also for negative n.
+fn half_gamma(x: Number) -> Option<Expr> {
+    let n = match x {
+        Number::Ratio(num, 2) => (num - 1) / 2,
+        _ => return None,
+    };
+
+    let (m, four) = (Number::int(n.abs()), Number::int(4));
+    let coef = if n >= 0 {
+        (Number::int(2) * m.clone()).fact() / (four.pow(m.clone()) * m.fact())
+    } else {
+        (-four).pow(m.clone()) * m.clone().fact() / (Number::int(2) * m).fact()
+    };
+    if !coef.is_exact() {
+        return None;
+    }
+
+    Some(Expr::mul(vec![
+        Expr::num(coef),
+        Expr::pow(Expr::pi(), Expr::num(Number::ratio(1, 2))),
+    ]))
+}
@@ -0,0 +1,291 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::{number::Number, symbol::Symbol, Atom},
+        call::{op::Op, Call},
+        Expr, TypeErr,
+    },
+};
+
+impl Expr {
+    /// Symbolic derivative of a canonical expression by a variable.
+    pub(crate) fn derive(&self, var: &Symbol) -> Result<Expr, TypeErr> {
+        if !self.contains(var) {
+            return Ok(Expr::int(0));
+        }
+
+        Ok(match self {
+            Expr::Atom(_) => Expr::int(1),
+            Expr::Call(Call { op: Op::Add, args }) => Expr::add(
+                args.iter()
+                    .map(|arg| arg.derive(var))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Expr::Call(Call { op: Op::Mul, args }) => {
+                // product rule, every factor is derived once
+                let mut terms = Vec::with_capacity(args.len());
+
+                for (i, arg) in args.iter().enumerate() {
+                    let mut factors = args.clone();
+                    factors[i] = arg.derive(var)?;
+                    terms.push(Expr::mul(factors));
+                }
+
+                Expr::add(terms)
+            }
+            Expr::Call(Call { op: Op::Pow, args }) => {
+                let (base, exp) = (&args[0], &args[1]);
+
+                if exp.contains(var) {
+                    // (b^e)' = b^e (e' ln b + e b' / b)
+                    Expr::mul(vec![
+                        self.clone(),
+                        Expr::add(vec![
+                            Expr::mul(vec![
+                                exp.derive(var)?,
+                                Expr::function(Symbol("ln".to_string()), base.clone()),
+                            ]),
+                            Expr::mul(vec![
+                                exp.clone(),
+                                base.derive(var)?,
+                                Expr::pow(base.clone(), Expr::int(-1)),
+                            ]),
+                        ]),
+                    ])
+                } else {
+                    Expr::mul(vec![
+                        exp.clone(),
+                        Expr::pow(base.clone(), Expr::sub(exp.clone(), Expr::int(1))),
+                        base.derive(var)?,
+                    ])
+                }
+            }
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) => match &args[..] {
+                // chain rule
+                [x] => Expr::mul(vec![Expr::derivative(call, x)?, x.derive(var)?]),
+                [x, base] if call.0 == "log" => {
+                    let ln = |x: &Expr| Expr::function(Symbol("ln".to_string()), x.clone());
+                    Expr::div(ln(x), ln(base)).derive(var)?
+                }
+                // atan2: (x y' - y x') / (x^2 + y^2)
+                [y, x] if call.0 == "angle" => Expr::div(
+                    Expr::sub(
+                        Expr::mul(vec![x.clone(), y.derive(var)?]),
+                        Expr::mul(vec![y.clone(), x.derive(var)?]),
+                    ),
+                    Expr::add(vec![
+                        Expr::pow(x.clone(), Expr::int(2)),
+                        Expr::pow(y.clone(), Expr::int(2)),
+                    ]),
+                ),
+                // incomplete elliptic integrals by their amplitude
+                [x, second]
+                    if matches!(&call.0[..], "ellipf" | "ellipe") && !second.contains(var) =>
+                {
+                    let amplitude = Expr::sub(
+                        Expr::int(1),
+                        Expr::mul(vec![
+                            second.clone(),
+                            Expr::pow(
+                                Expr::function(Symbol("sin".to_string()), x.clone()),
+                                Expr::int(2),
+                            ),
+                        ]),
+                    );
+                    let exp = if call.0 == "ellipf" { -1 } else { 1 };
+                    Expr::mul(vec![
+                        Expr::pow(amplitude, Expr::num(Number::ratio(exp, 2))),
+                        x.derive(var)?,
+                    ])
+                }
+                // only by the second argument, the order or parameter stays constant
+                [first, x] if !first.contains(var) => Expr::mul(vec![
+                    Expr::derivative_two(call, first, x)
+                        .ok_or_else(|| TypeErr(format!("derivative of `{}` is undefined", self)))?,
+                    x.derive(var)?,
+                ]),
+                _ => return Err(TypeErr(format!("derivative of `{}` is undefined", self))),
+            },
+            _ => return Err(TypeErr(format!("derivative of `{}` is undefined", self))),
+        })
+    }
+
+    /// Partial derivatives of a built-in function at some values, by central differences
+    /// where the derivative is not known in closed form, like for `zeta`.
+    pub(crate) fn slopes(call: &Symbol, at: &[f64]) -> Vec<f64> {
+        let vars: Vec<_> = (0..at.len()).map(|i| Symbol(format!("x_{}", i))).collect();
+        let f = Expr::rebuild(
+            &Op::Call(call.clone()),
+            vars.iter()
+                .map(|var| Expr::Atom(Atom::Symbol(var.clone())))
+                .collect(),
+        );
+        let values: Vec<_> = vars.iter().zip(at.iter().copied()).collect();
+
+        vars.iter()
+            .enumerate()
+            .map(|(i, var)| {
+                f.derive(var)
+                    .ok()
+                    .and_then(|slope| slope.float_with(&values))
+                    .unwrap_or_else(|| {
+                        // about the cube root of the machine epsilon
+                        let step = 6e-6 * at[i].abs().max(1.0);
+                        let value = |shift: f64| {
+                            let mut shifted = values.clone();
+                            shifted[i].1 += shift;
+                            f.float_with(&shifted).unwrap_or(f64::NAN)
+                        };
+                        (value(step) - value(-step)) / (2.0 * step)
+                    })
+            })
+            .collect()
+    }
+
+    /// Derivative of a function of one argument at `x`.
+    fn derivative(call: &Symbol, x: &Expr) -> Result<Expr, TypeErr> {
+        let f = |name: &str, x: Expr| Expr::function(Symbol(name.to_string()), x);
+        let square = Expr::pow(x.clone(), Expr::int(2));
+        let inv_sqrt = |x: Expr| Expr::pow(x, Expr::num(Number::ratio(-1, 2)));
+        let inv = |x: Expr| Expr::pow(x, Expr::int(-1));
+        // erf' = 2 / sqrt π exp(-x^2)
+        let erf_derivative = || {
+            Expr::mul(vec![
+                Expr::int(2),
+                Expr::pow(Expr::pi(), Expr::num(Number::ratio(-1, 2))),
+                f("exp", Expr::neg(square.clone())),
+            ])
+        };
+
+        Ok(match &call.0[..] {
+            "exp" => f("exp", x.clone()),
+            "ln" => inv(x.clone()),
+            "lg" => inv(Expr::mul(vec![x.clone(), f("ln", Expr::int(10))])),
+            "cbrt" => inv(Expr::mul(vec![
+                Expr::int(3),
+                Expr::pow(f("cbrt", x.clone()), Expr::int(2)),
+            ])),
+
+            "sin" => f("cos", x.clone()),
+            "cos" => Expr::neg(f("sin", x.clone())),
+            "tan" => Expr::add(vec![
+                Expr::int(1),
+                Expr::pow(f("tan", x.clone()), Expr::int(2)),
+            ]),
+            "asin" => inv_sqrt(Expr::sub(Expr::int(1), square)),
+            "acos" => Expr::neg(inv_sqrt(Expr::sub(Expr::int(1), square))),
+            "atan" => inv(Expr::add(vec![Expr::int(1), square])),
+
+            "sinh" => f("cosh", x.clone()),
+            "cosh" => f("sinh", x.clone()),
+            "tanh" => Expr::sub(Expr::int(1), Expr::pow(f("tanh", x.clone()), Expr::int(2))),
+            "asinh" => inv_sqrt(Expr::add(vec![square, Expr::int(1)])),
+            "acosh" => inv_sqrt(Expr::sub(square, Expr::int(1))),
+            "atanh" => inv(Expr::sub(Expr::int(1), square)),
+
+            "erf" => erf_derivative(),
+            "erfc" => Expr::neg(erf_derivative()),
+            "gamma" => Expr::mul(vec![f("gamma", x.clone()), f("digamma", x.clone())]),
+            "lgamma" => f("digamma", x.clone()),
+            "fact" => Expr::mul(vec![
+                f("fact", x.clone()),
+                f("digamma", Expr::add(vec![x.clone(), Expr::int(1)])),
+            ]),
+            // W' = W / (x (1 + W))
+            "lambertw" => Expr::div(
+                f("lambertw", x.clone()),
+                Expr::mul(vec![
+                    x.clone(),
+                    Expr::add(vec![Expr::int(1), f("lambertw", x.clone())]),
+                ]),
+            ),
+            "airyai" => f("airyaiprime", x.clone()),
+            "airybi" => f("airybiprime", x.clone()),
+            "airyaiprime" => Expr::mul(vec![x.clone(), f("airyai", x.clone())]),
+            "airybiprime" => Expr::mul(vec![x.clone(), f("airybi", x.clone())]),
+            // K' = (E - (1 - m) K) / (2m (1 - m)), E' = (E - K) / 2m
+            "ellipk" => Expr::div(
+                Expr::sub(
+                    f("ellipe", x.clone()),
+                    Expr::mul(vec![
+                        Expr::sub(Expr::int(1), x.clone()),
+                        f("ellipk", x.clone()),
+                    ]),
+                ),
+                Expr::mul(vec![
+                    Expr::int(2),
+                    x.clone(),
+                    Expr::sub(Expr::int(1), x.clone()),
+                ]),
+            ),
+            "ellipe" => Expr::div(
+                Expr::sub(f("ellipe", x.clone()), f("ellipk", x.clone())),
+                Expr::mul(vec![Expr::int(2), x.clone()]),
+            ),
+
+            "abs" => f("signum", x.clone()),
+            // piecewise constant, apart from the jumps
+            "signum" | "ceil" | "floor" | "round" | "trunc" => Expr::int(0),
+            "fract" => Expr::int(1),
+
+            _ => {
+                return Err(TypeErr(format!(
+                    "derivative of `{}` is undefined",
+                    Expr::Call(Call::new(Op::Call(call.clone()), vec![x.clone()]))
+                )))
+            }
+        })
+    }
+
+    /// Derivative of a function of two arguments by the second one.
+    fn derivative_two(call: &Symbol, first: &Expr, x: &Expr) -> Option<Expr> {
+        let f = |first: Expr, x: Expr| Expr::rebuild(&Op::Call(call.clone()), vec![first, x]);
+        let shift = |by: i128| Expr::add(vec![first.clone(), Expr::int(by)]);
+
+        Some(match &call.0[..] {
+            // Z' = (Z(n - 1) - Z(n + 1)) / 2
+            "besselj" | "bessely" => Expr::mul(vec![
+                Expr::num(Number::ratio(1, 2)),
+                Expr::sub(f(shift(-1), x.clone()), f(shift(1), x.clone())),
+            ]),
+            "polylog" => Expr::div(f(shift(-1), x.clone()), x.clone()),
+            _ => return None,
+        })
+    }
+
+    /// `diff(expr, x, n)` is the `n`-th derivative of expr by `x`.
+    pub(crate) fn diff(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (expr, var, n) = match args {
+            [expr, var] => (expr, var, 1),
+            [expr, var, n] => (
+                expr,
+                var,
+                n.eval(env)?
+                    .as_number()
+                    .and_then(|n| n.as_int())
+                    .filter(|n| *n >= 0)
+                    .ok_or_else(|| {
+                        TypeErr(format!("derivative order `{}` is no natural number", n))
+                    })?,
+            ),
+            _ => {
+                return Err(TypeErr(
+                    "call `diff` undefined on (expr, ...), expected (expr, symbol, integer)"
+                        .to_string(),
+                ))
+            }
+        };
+
+        let var = var.symbol()?;
+        let mut expr = expr.eval(env)?;
+
+        for _ in 0..n {
+            expr = expr.derive(&var)?;
+        }
+
+        Ok(expr)
+    }
+}
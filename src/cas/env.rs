@@ -6,9 +6,13 @@ use {
     std::{collections::HashMap, fs, io, io::BufRead},
 };
 
-/// Contains all variables and functions as definitions.
-/// Use Default to initiate with common definitions.
-pub struct Env(pub(crate) HashMap<Symbol, Def>);
+/// Contains all variables and functions as definitions, what is assumed about symbols
+/// and the rewrite rules. Use Default to initiate with common definitions.
+pub struct Env {
+    defs: HashMap<Symbol, Def>,
+    assumptions: HashMap<Symbol, Assumption>,
+    rules: Vec<Expr>,
+}
 
 /// Anything denoted by symbol := value will be a definition
 #[derive(Clone)]
@@ -19,49 +23,103 @@ pub enum Def {
     Call { args: Vec<Symbol>, call: Expr },
     // Call Rust functions
     OSCall,
+    // Constants known to Rust, they stay exact symbols until approximated
+    OSConst,
+    // Base units of measurement, each is a dimension of its own
+    OSUnit,
+}
+
+/// Anything denoted by `assume(x > 0)` or `assume(n, integer)`.
+#[derive(Clone, Copy, Default)]
+pub struct Assumption {
+    /// Lower bound and if it is strict.
+    pub(crate) lower: Option<(f64, bool)>,
+    /// Upper bound and if it is strict.
+    pub(crate) upper: Option<(f64, bool)>,
+    pub(crate) integer: bool,
+    pub(crate) real: bool,
+    pub(crate) nonzero: bool,
 }
 
 impl Env {
     /// Default is recommended instead, because it contains common definitions.
     pub fn new() -> Env {
-        Env(HashMap::new())
+        Env {
+            defs: HashMap::new(),
+            assumptions: HashMap::new(),
+            rules: Vec::new(),
+        }
     }
 
     /// Return a definition by symbol.
     pub fn get(&self, symbol: &Symbol) -> Result<&Def, TypeErr> {
-        self.0
+        self.defs
             .get(symbol)
             .ok_or_else(|| TypeErr(format!("`{}` is undefined", symbol)))
     }
 
     /// Define new definition.
     pub fn insert(&mut self, symbol: Symbol, def: Def) -> Option<Def> {
-        self.0.insert(symbol, def)
+        self.defs.insert(symbol, def)
     }
 
     /// Remove definition by symbol.
     pub fn remove(&mut self, symbol: Symbol) -> Option<Def> {
-        self.0.remove(&symbol)
+        self.defs.remove(&symbol)
+    }
+
+    /// Return what is assumed about a symbol.
+    pub fn assumption(&self, symbol: &Symbol) -> Option<&Assumption> {
+        self.assumptions.get(symbol)
+    }
+
+    /// Assume more about a symbol, or start over for `None`.
+    pub fn assume(&mut self, symbol: Symbol, assumption: Option<Assumption>) {
+        match assumption {
+            Some(assumption) => self.assumptions.insert(symbol, assumption),
+            None => self.assumptions.remove(&symbol),
+        };
+    }
+
+    /// Return the rewrite rules, in the order they are tried.
+    pub fn rules(&self) -> &[Expr] {
+        &self.rules
+    }
+
+    /// Replace the rewrite rules.
+    pub fn set_rules(&mut self, rules: Vec<Expr>) {
+        self.rules = rules;
+    }
+
+    /// Whether base units are defined, as after `load(units)`.
+    pub fn has_units(&self) -> bool {
+        self.defs.values().any(|def| matches!(def, Def::OSUnit))
     }
 
     /// Loads a file of definitions. The crate come with `default_env.txt`.
     /// Did work with WASM, probably because it is an internal file.
     pub fn load(path: &str) -> Result<Env, String> {
         let mut env = Env::new();
+        env.import(path)?;
 
-        let file = fs::File::open(path).map_err(|error| format!("{:?}", error))?;
+        Ok(env)
+    }
+
+    /// Loads a file of definitions into this enviroment, like `physics_env.txt`.
+    pub fn import(&mut self, path: &str) -> Result<(), String> {
+        let file = fs::File::open(path).map_err(|error| format!("{}", error))?;
         for input in io::BufReader::new(file).lines() {
-            let expr = match Expr::parse(&input.map_err(|error| format!("{:?}", error))?, &env) {
+            let expr = match Expr::parse(&input.map_err(|error| format!("{:?}", error))?, self) {
                 Ok(expr) => expr,
                 Err(ParserErr::Empty) => continue,
                 Err(error) => return Err(format!("{}", error)),
             };
 
             // To save a definition, eval it as an expression in its enviroment
-            expr.eval(&mut env).map_err(|error| format!("{}", error))?;
+            expr.eval(self).map_err(|error| format!("{}", error))?;
         }
 
-        Ok(env)
+        Ok(())
     }
 }
 
@@ -1,8 +1,5 @@
 use {
-    super::{
-        env::{Def, Env},
-        lexer::E_TOKEN_LEN,
-    },
+    super::env::{Def, Env},
     atom::{number::Number, symbol::Symbol, Atom},
     call::{op::Op, Call},
 };
@@ -38,39 +35,65 @@ impl Expr {
         }
     }
 
+    /// Turn a list of expressions back into a list expression.
+    pub(crate) fn from_list(mut list: Vec<Expr>) -> Expr {
+        let mut expr = list.pop().unwrap_or_else(|| Expr::int(0));
+
+        while let Some(head) = list.pop() {
+            expr = Expr::Call(Call::new(Op::List, vec![head, expr]));
+        }
+
+        expr
+    }
+
     /// Try to eval an expression into a number.
     pub fn number(&self, env: &mut Env) -> Result<Number, TypeErr> {
-        match self {
-            Expr::Atom(Atom::Number(number)) => Ok(number.clone()),
-            Expr::Atom(Atom::Symbol(symbol)) => {
-                if let Def::Expr(Expr::Atom(Atom::Number(number))) = env.get(symbol)? {
-                    Ok(number.clone())
-                } else {
-                    Err(TypeErr(format!("`{}` is not a number", self)))
-                }
-            }
-            _ => self.eval(env)?.number(env),
+        // eval exactly first, then approximate whatever could not be resolved exactly
+        match self.eval(env)?.approx(env).eval(env)? {
+            Expr::Atom(Atom::Number(number)) => Ok(number),
+            expr => Err(TypeErr(format!("`{}` is not a number", expr))),
         }
     }
 
     /// Evaluate in an enviroment to another equal and or simpler expression.
+    /// The result is exact, numbers are only approximated by [`Expr::number`].
     pub fn eval(&self, env: &mut Env) -> Result<Expr, TypeErr> {
         match self {
+            Expr::Atom(Atom::Symbol(symbol)) => match env.get(symbol) {
+                Ok(Def::Expr(expr)) => {
+                    // hide the definition while evaluating it, so it can not refer to itself
+                    let expr = expr.clone();
+                    let def = env.remove(symbol.clone());
+                    let result = expr.eval(env);
+
+                    if let Some(def) = def {
+                        env.insert(symbol.clone(), def);
+                    }
+
+                    result
+                }
+                // a prefixed unit like `km`
+                Err(_) => match Expr::prefixed(symbol, env) {
+                    Some(expr) => expr.eval(env),
+                    None => Ok(self.clone()),
+                },
+                _ => Ok(self.clone()),
+            },
             Expr::Call(Call { op, args }) => {
                 if let Op::Call(call) = op {
                     if let Def::Call {
                         args: symbols,
                         call: expr,
-                    } = env.get(&call)?.clone()
+                    } = env.get(call)?.clone()
                     {
                         // define local definitions of a function call
                         let mut vars = Env::new();
 
-                        for (var, symbol) in args.into_iter().zip(symbols.into_iter()) {
+                        for (var, symbol) in args.iter().zip(symbols) {
                             vars.insert(symbol, Def::Expr(var.clone()));
                         }
 
-                        return expr.apply_env(&mut vars);
+                        return expr.apply_env(&mut vars)?.eval(env);
                     }
                 }
 
@@ -78,7 +101,10 @@ impl Expr {
                     [x] => Expr::eval_one_arg(op, x, env),
                     [x, y] => Expr::eval_two_args(op, x, y, env),
                     tuple => Expr::eval_many_args(op, tuple, env),
-                }
+                }?
+                .order_terms()?
+                .apply_rules(env)?
+                .nonempty(self)
             }
             _ => Ok(self.clone()),
         }
@@ -88,46 +114,111 @@ impl Expr {
         Ok(match op {
             Op::Call(call) => Expr::eval_call_one(call, x, env)?,
 
-            Op::Sub => Expr::Atom(Atom::Number(-x.number(env)?)),
-            Op::Add => x.clone(),
-            Op::Fact => Expr::Atom(Atom::Number(x.number(env)?.fact())),
+            Op::Sub => Expr::neg(x.eval(env)?),
+            Op::Add => x.eval(env)?,
+            Op::Fact => {
+                let x = x.eval(env)?;
+                match x.as_number() {
+                    Some(_) => Expr::function(Symbol("fact".to_string()), x),
+                    None => Expr::Call(Call::new(Op::Fact, vec![x])),
+                }
+            }
+            // the derivative `y'` of an unknown function stays as it is
+            Op::Prime => Expr::Call(Call::new(Op::Prime, vec![x.eval(env)?])),
 
             _ => return Err(TypeErr(format!("op `{}` undefined on (expr)", op))),
         })
     }
 
+    /// Built-in functions of one number.
+    pub(crate) fn builtin_one(call: &str) -> Option<fn(Number) -> Number> {
+        Some(match call {
+            "abs" => Number::abs,
+            "signum" => Number::signum,
+            "ceil" => Number::ceil,
+            "floor" => Number::floor,
+            "round" => Number::round,
+            "trunc" => Number::trunc,
+            "fract" => Number::fract,
+
+            "exp" => Number::exp,
+            "fact" => Number::fact,
+            "gamma" => Number::gamma,
+            "lgamma" => Number::lgamma,
+            "fibonacci" => Number::fibonacci,
+            "catalan" => Number::catalan,
+            "bell" => Number::bell,
+
+            "erf" => Number::erf,
+            "erfc" => Number::erfc,
+            "digamma" => Number::digamma,
+            "zeta" => Number::zeta,
+            "lambertw" => Number::lambertw,
+            "airyai" => Number::airyai,
+            "airyaiprime" => Number::airyaiprime,
+            "airybi" => Number::airybi,
+            "airybiprime" => Number::airybiprime,
+            "ellipk" => Number::ellipk,
+            "ellipe" => Number::ellipe,
+
+            "sin" => Number::sin,
+            "asin" => Number::asin,
+            "sinh" => Number::sinh,
+            "asinh" => Number::asinh,
+
+            "cos" => Number::cos,
+            "acos" => Number::acos,
+            "cosh" => Number::cosh,
+            "acosh" => Number::acosh,
+
+            "tan" => Number::tan,
+            "atan" => Number::atan,
+            "tanh" => Number::tanh,
+            "atanh" => Number::atanh,
+
+            "ln" => Number::ln,
+            "lg" => Number::lg,
+
+            "sqrt" => Number::sqrt,
+            "cbrt" => Number::cbrt,
+
+            _ => return None,
+        })
+    }
+
+    /// Built-in functions of two numbers.
+    pub(crate) fn builtin_two(call: &str) -> Option<fn(Number, Number) -> Number> {
+        Some(match call {
+            "log" => Number::log,
+            "angle" => Number::angle,
+            "beta" => Number::beta,
+            "binomial" => Number::binomial,
+            "perm" => Number::perm,
+            "stirling1" => Number::stirling1,
+            "stirling2" => Number::stirling2,
+
+            "besselj" => Number::besselj,
+            "bessely" => Number::bessely,
+            "polylog" => Number::polylog,
+            "ellipf" => Number::ellipf,
+            "ellipe" => Number::ellipe_incomplete,
+            _ => return None,
+        })
+    }
+
     fn eval_call_one(call: &Symbol, x: &Expr, env: &mut Env) -> Result<Expr, TypeErr> {
         Ok(match &call.0[..] {
-            "abs" => Expr::Atom(Atom::Number(x.number(env)?.abs())),
-            "signum" => Expr::Atom(Atom::Number(x.number(env)?.signum())),
-            "ceil" => Expr::Atom(Atom::Number(x.number(env)?.ceil())),
-            "floor" => Expr::Atom(Atom::Number(x.number(env)?.floor())),
-            "round" => Expr::Atom(Atom::Number(x.number(env)?.round())),
-            "trunc" => Expr::Atom(Atom::Number(x.number(env)?.trunc())),
-            "fract" => Expr::Atom(Atom::Number(x.number(env)?.fract())),
-
-            "sin" => Expr::Atom(Atom::Number(x.number(env)?.sin())),
-            "asin" => Expr::Atom(Atom::Number(x.number(env)?.asin())),
-            "sinh" => Expr::Atom(Atom::Number(x.number(env)?.sinh())),
-            "asinh" => Expr::Atom(Atom::Number(x.number(env)?.asinh())),
-
-            "cos" => Expr::Atom(Atom::Number(x.number(env)?.cos())),
-            "acos" => Expr::Atom(Atom::Number(x.number(env)?.acos())),
-            "cosh" => Expr::Atom(Atom::Number(x.number(env)?.cosh())),
-            "acosh" => Expr::Atom(Atom::Number(x.number(env)?.acosh())),
-
-            "tan" => Expr::Atom(Atom::Number(x.number(env)?.tan())),
-            "atan" => Expr::Atom(Atom::Number(x.number(env)?.atan())),
-            "tanh" => Expr::Atom(Atom::Number(x.number(env)?.tanh())),
-            "atanh" => Expr::Atom(Atom::Number(x.number(env)?.atanh())),
-
-            "ln" => Expr::Atom(Atom::Number(x.number(env)?.ln())),
-            "lg" => Expr::Atom(Atom::Number(x.number(env)?.lg())),
-
-            "sqrt" => Expr::Atom(Atom::Number(x.number(env)?.sqrt())),
-            "cbrt" => Expr::Atom(Atom::Number(x.number(env)?.cbrt())),
-
-            _ => return Err(TypeErr(format!("call `{}` undefined on (expr)", call))),
+            // an order term like `O(x^4)` stays as it is
+            "O" => Expr::Call(Call::new(
+                Op::Call(call.clone()),
+                vec![x.eval(env)?.order_arg()],
+            )),
+
+            name if Expr::builtin_one(name).is_some() => {
+                Expr::function(call.clone(), Expr::dimensionless(call, x.eval(env)?, env)?)
+            }
+
+            _ => Expr::eval_many_args(&Op::Call(call.clone()), std::slice::from_ref(x), env)?,
         })
     }
 
@@ -139,14 +230,57 @@ impl Expr {
 
             Op::Child => Expr::child(x, y, env)?,
 
-            Op::Add => Expr::Atom(Atom::Number(x.number(env)? + y.number(env)?)),
-            Op::Sub => Expr::Atom(Atom::Number(x.number(env)? - y.number(env)?)),
-            Op::Mul => Expr::Atom(Atom::Number(x.number(env)? * y.number(env)?)),
-            Op::Div => Expr::Atom(Atom::Number(x.number(env)? / y.number(env)?)),
-            Op::Pow => Expr::Atom(Atom::Number(x.number(env)?.pow(y.number(env)?))),
-            Op::Mod => Expr::Atom(Atom::Number(x.number(env)?.modulus(y.number(env)?))),
+            Op::Add => Expr::add(Expr::commensurable(vec![x.eval(env)?, y.eval(env)?], env)?),
+            Op::Sub => {
+                let (x, y) = (x.eval(env)?, y.eval(env)?);
+                match (x.as_number(), y.as_number()) {
+                    // saturating integers can not subtract by adding the negation
+                    (Some(a), Some(b)) if a.is_fixed() || b.is_fixed() => Expr::num(a - b),
+                    _ => Expr::add(Expr::commensurable(vec![x, Expr::neg(y)], env)?),
+                }
+            }
+            Op::Mul => Expr::mul(vec![x.eval(env)?, y.eval(env)?]),
+            Op::Div => {
+                let (x, y) = (x.eval(env)?, y.eval(env)?);
+                match (x.as_number(), y.as_number()) {
+                    // integers of fixed width round towards zero
+                    (Some(a), Some(b)) if a.is_fixed() || b.is_fixed() => {
+                        Expr::fixed_quotient(op, a, b)?
+                    }
+                    _ => Expr::div(x, y),
+                }
+            }
+            Op::Pow => Expr::pow(x.eval(env)?, y.eval(env)?),
+            Op::Mod => {
+                let (x, y) = (x.eval(env)?, y.eval(env)?);
+                match (x.as_number(), y.as_number()) {
+                    (Some(a), Some(b)) if a.is_fixed() || b.is_fixed() => {
+                        Expr::fixed_quotient(op, a, b)?
+                    }
+                    _ => Expr::fold_two(Op::Mod, x, y, Number::modulus),
+                }
+            }
 
-            Op::Eq => Expr::Atom(Atom::Symbol(x.number(env)?.equal(y.number(env)?))),
+            Op::Eq => {
+                let (x, y) = (x.eval(env)?, y.eval(env)?);
+                match (x.as_number(), y.as_number()) {
+                    (Some(x), Some(y)) => Expr::Atom(Atom::Symbol(x.equal(y))),
+                    _ => Expr::Call(Call::new(Op::Eq, vec![x, y])),
+                }
+            }
+            Op::Neq | Op::Less | Op::More | Op::LessEq | Op::MoreEq => {
+                let (x, y) = (x.eval(env)?, y.eval(env)?);
+                match Expr::holds(op, &x, &y, env) {
+                    Some(holds) => Expr::Atom(Atom::Symbol(Symbol(holds.to_string()))),
+                    None => Expr::Call(Call::new(op.clone(), vec![x, y])),
+                }
+            }
+
+            Op::List => Expr::Call(Call::new(Op::List, vec![x.eval(env)?, y.eval(env)?])),
+            Op::Interval => Expr::hull(x.eval(env)?, y.eval(env)?)?,
+            Op::PlusMinus => Expr::plus_minus(x.eval(env)?, y.eval(env)?),
+            Op::Rule | Op::If => Expr::make_rule(op, x, y)?,
+            Op::To => Expr::convert(x, y, env)?,
 
             _ => return Err(TypeErr(format!("op `{}` undefined on (expr, expr)", op))),
         })
@@ -167,8 +301,8 @@ impl Expr {
         let parent = parent.expr(env)?.list();
 
         Ok(parent
-            .get(index.0 as usize)
-            .ok_or(TypeErr(format!("op `{}` index out of bounds", Op::Child)))?
+            .get(index.as_f64() as usize)
+            .ok_or_else(|| TypeErr(format!("op `{}` index out of bounds", Op::Child)))?
             .clone())
     }
 
@@ -191,7 +325,7 @@ impl Expr {
                         },
                     );
 
-                    Ok(Expr::Atom(Atom::Symbol(Symbol(format!("Def")))))
+                    Ok(Expr::Atom(Atom::Symbol(Symbol("Def".to_string()))))
                 } else {
                     Err(TypeErr(format!(
                         "op `{}` is undefined on (symbol / symbol(symbols, ...), expr)",
@@ -201,15 +335,17 @@ impl Expr {
             }
 
             Expr::Atom(Atom::Symbol(symbol)) => {
-                env.insert(
-                    symbol.clone(),
-                    match y {
-                        Expr::Atom(Atom::Symbol(Symbol(call))) if call == "OSCall" => Def::OSCall,
-                        _ => Def::Expr(y.clone()),
-                    },
-                );
-
-                Ok(Expr::Atom(Atom::Symbol(Symbol(format!("Def")))))
+                let def = match y {
+                    Expr::Atom(Atom::Symbol(Symbol(call))) if call == "OSCall" => Def::OSCall,
+                    Expr::Atom(Atom::Symbol(Symbol(call))) if call == "OSConst" => Def::OSConst,
+                    Expr::Atom(Atom::Symbol(Symbol(unit))) if unit == "OSUnit" => Def::OSUnit,
+                    // a measurement is taken once, so it is correlated with itself
+                    _ if y.measures() => Def::Expr(y.eval(env)?),
+                    _ => Def::Expr(y.clone()),
+                };
+                env.insert(symbol.clone(), def);
+
+                Ok(Expr::Atom(Atom::Symbol(Symbol("Def".to_string()))))
             }
 
             _ => Err(TypeErr(format!(
@@ -221,35 +357,197 @@ impl Expr {
 
     fn eval_call_two(call: &Symbol, x: &Expr, y: &Expr, env: &mut Env) -> Result<Expr, TypeErr> {
         Ok(match &call.0[..] {
-            "root" => Expr::Atom(Atom::Number(x.number(env)?.root(y.number(env)?))),
-            "log" => Expr::Atom(Atom::Number(x.number(env)?.log(y.number(env)?))),
-            "angle" => Expr::Atom(Atom::Number(x.number(env)?.log(y.number(env)?))),
+            name if name == "root" || Expr::builtin_two(name).is_some() => {
+                Expr::rebuild(&Op::Call(call.clone()), vec![x.eval(env)?, y.eval(env)?])
+            }
 
-            _ => return Err(TypeErr(format!("op `{}` undefined on (expr, expr)", call))),
+            _ => Expr::eval_many_args(&Op::Call(call.clone()), &[x.clone(), y.clone()], env)?,
         })
     }
 
     fn eval_many_args(op: &Op, tuple: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        if let Op::Call(call) = op {
+            if let Some(result) = Expr::shadow(call, tuple, env)? {
+                return Ok(result);
+            }
+        }
+
         Ok(match op {
             Op::Call(call) => match &call.0[..] {
+                // `sum(expr, k, a, b)` has an index, otherwise the arguments and lists are summed up
+                "sum" if Expr::is_indexed(tuple) => Expr::sum(tuple, env)?,
+                "prod" if Expr::is_indexed(tuple) => Expr::prod(tuple, env)?,
                 "sum" => {
-                    let mut nums = Vec::with_capacity(E_TOKEN_LEN);
+                    let args = Expr::values(tuple, env)?;
+
+                    match args.iter().map(Expr::as_number).collect::<Option<Vec<_>>>() {
+                        Some(nums) => Expr::num(Number::sum(nums)),
+                        None => Expr::add(args),
+                    }
+                }
+
+                "prod" => Expr::mul(Expr::values(tuple, env)?),
+
+                "multinomial" => {
+                    let mut args = Vec::with_capacity(tuple.len());
 
                     for arg in tuple {
-                        nums.push(arg.number(env)?);
+                        args.push(arg.eval(env)?);
                     }
 
-                    Expr::Atom(Atom::Number(Number::sum(nums)))
+                    match args.iter().map(Expr::as_number).collect::<Option<Vec<_>>>() {
+                        Some(numbers) if numbers.iter().all(Number::is_exact) => {
+                            Expr::num(Number::multinomial(numbers))
+                        }
+                        _ => Expr::Call(Call::new(op.clone(), args)),
+                    }
                 }
 
+                "N" => Expr::numeric(tuple, env)?,
+                "series" => Expr::series(tuple, env)?,
+                "rule" => Expr::rule(tuple, env)?,
+                "unrule" => Expr::unrule(tuple, env)?,
+                "replace" => Expr::replace_rules(tuple, env)?,
+                "subs" => Expr::subs(tuple, env)?,
+                "trigexpand" => Expr::trigexpand(tuple, env)?,
+                "trigreduce" => Expr::trigreduce(tuple, env)?,
+                "trigsimp" => Expr::trigsimp(tuple, env)?,
+                "expandlog" => Expr::expandlog(tuple, env)?,
+                "combinelog" => Expr::combinelog(tuple, env)?,
+                "assume" => Expr::assume(tuple, env)?,
+                "refine" => Expr::refine(tuple, env)?,
+                "is" => Expr::is(tuple, env)?,
+                "isprime" => Expr::isprime(tuple, env)?,
+                "nextprime" => Expr::nextprime(tuple, env)?,
+                "factorint" => Expr::factorint(tuple, env)?,
+                "gcd" => Expr::gcd(tuple, env)?,
+                "lcm" => Expr::lcm(tuple, env)?,
+                "egcd" => Expr::egcd(tuple, env)?,
+                "powmod" => Expr::powmod(tuple, env)?,
+                "modinv" => Expr::modinv(tuple, env)?,
+                "totient" => Expr::totient(tuple, env)?,
+                "divisors" => Expr::divisors(tuple, env)?,
+                "crt" => Expr::crt(tuple, env)?,
+                "mean" => Expr::mean(tuple, env)?,
+                "median" => Expr::median(tuple, env)?,
+                "mode" => Expr::mode(tuple, env)?,
+                "var" => Expr::var(tuple, env)?,
+                "std" => Expr::std(tuple, env)?,
+                "quantile" => Expr::quantile(tuple, env)?,
+                "cov" => Expr::cov(tuple, env)?,
+                "corr" => Expr::corr(tuple, env)?,
+                "linreg" => Expr::linreg(tuple, env)?,
+                name if Expr::is_distribution(name) => Expr::distribution(name, tuple, env)?,
+                "diff" => Expr::diff(tuple, env)?,
+                "limit" => Expr::limit(tuple, env)?,
+                "nsolve" => Expr::nsolve(tuple, env)?,
+                "gradient" => Expr::gradient(tuple, env)?,
+                "minimize" => Expr::minimize(tuple, env)?,
+                "maximize" => Expr::maximize(tuple, env)?,
+                "nintegrate" => Expr::nintegrate(tuple, env)?,
+                "dsolve" => Expr::dsolve(tuple, env)?,
+                "odesolve" => Expr::odesolve(tuple, env)?,
+                "laplace" => Expr::laplace(tuple, env)?,
+                "ilaplace" => Expr::ilaplace(tuple, env)?,
+                "fourier" => Expr::fourier(tuple, env)?,
+                "ifourier" => Expr::ifourier(tuple, env)?,
+                "ztransform" => Expr::ztransform(tuple, env)?,
+                "interval" => Expr::interval(tuple, env)?,
+                "load" => Expr::load(tuple, env)?,
+                "base" => Expr::base(tuple, env)?,
+                "hex" => Expr::hex(tuple, env)?,
+                "oct" => Expr::oct(tuple, env)?,
+                "bin" => Expr::bin(tuple, env)?,
+                "and" => Expr::and(tuple, env)?,
+                "or" => Expr::or(tuple, env)?,
+                "xor" => Expr::xor(tuple, env)?,
+                "shl" => Expr::shl(tuple, env)?,
+                "shr" => Expr::shr(tuple, env)?,
+                "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" => {
+                    Expr::fixed(&call.0, tuple, env)?
+                }
+                "saturating" | "wrapping" => Expr::overflow(&call.0, tuple, env)?,
+                "ieee" => Expr::ieee(tuple, env)?,
+
                 _ => return Err(TypeErr(format!("call `{}` undefined on (expr, ...)", call))),
             },
 
+            // the canonical form has sums and products of any length
+            Op::Add | Op::Mul => {
+                let mut args = Vec::with_capacity(tuple.len());
+
+                for arg in tuple {
+                    args.push(arg.eval(env)?);
+                }
+
+                Expr::rebuild(op, args)
+            }
+
+            // a rule with a condition
+            Op::Rule if tuple.len() == 3 => Expr::Call(Call::new(Op::Rule, tuple.to_vec())),
+
             _ => return Err(TypeErr(format!("op `{}` undefined on (expr, ...)", op))),
         })
     }
 
-    fn symbol(&self) -> Result<Symbol, TypeErr> {
+    /// Bound variables like `i` of `sum(i^2, i, 1, n)` shadow constants and definitions,
+    /// so they are renamed to fresh symbols in all arguments and back in the result.
+    fn shadow(call: &Symbol, tuple: &[Expr], env: &mut Env) -> Result<Option<Expr>, TypeErr> {
+        let bound: &[usize] = match &call.0[..] {
+            "sum" | "prod" if Expr::is_indexed(tuple) => &[1],
+            "diff" | "limit" | "series" | "nsolve" | "nintegrate" | "gradient" | "minimize"
+            | "maximize" => &[1],
+            "odesolve" => &[1, 3],
+            "laplace" | "ilaplace" | "fourier" | "ifourier" | "ztransform" => &[1, 2],
+            _ => return Ok(None),
+        };
+
+        let mut pairs: Vec<(Expr, Expr)> = Vec::new();
+        for var in bound
+            .iter()
+            .filter_map(|index| tuple.get(*index))
+            .flat_map(|vars| vars.clone().list())
+        {
+            let symbol = match &var {
+                Expr::Atom(Atom::Symbol(symbol)) => symbol,
+                _ => continue,
+            };
+            if matches!(env.get(symbol), Err(_) | Ok(Def::OSCall | Def::Call { .. }))
+                || pairs.iter().any(|(from, _)| *from == var)
+            {
+                continue;
+            }
+
+            let fresh = (1..)
+                .map(|k| Symbol(format!("{}{}", symbol, k)))
+                .find(|fresh| {
+                    env.get(fresh).is_err()
+                        && !tuple.iter().any(|arg| arg.contains(fresh))
+                        && pairs
+                            .iter()
+                            .all(|(_, to)| *to != Expr::Atom(Atom::Symbol(fresh.clone())))
+                })
+                .expect("there is always a fresh symbol");
+            pairs.push((var.clone(), Expr::Atom(Atom::Symbol(fresh))));
+        }
+
+        if pairs.is_empty() {
+            return Ok(None);
+        }
+
+        let tuple: Vec<Expr> = tuple.iter().map(|arg| arg.substitute_all(&pairs)).collect();
+        let back: Vec<(Expr, Expr)> = pairs.into_iter().map(|(from, to)| (to, from)).collect();
+
+        Ok(Some(
+            Expr::eval_many_args(&Op::Call(call.clone()), &tuple, env)?.substitute_all(&back),
+        ))
+    }
+
+    fn is_indexed(tuple: &[Expr]) -> bool {
+        tuple.len() == 4 && matches!(tuple[1], Expr::Atom(Atom::Symbol(_)))
+    }
+
+    pub(crate) fn symbol(&self) -> Result<Symbol, TypeErr> {
         match self {
             Expr::Atom(Atom::Symbol(symbol)) => Ok(symbol.clone()),
             // Compare number to all symbols?
@@ -257,10 +555,10 @@ impl Expr {
         }
     }
 
-    fn apply_env(&self, env: &mut Env) -> Result<Expr, TypeErr> {
+    pub(crate) fn apply_env(&self, env: &mut Env) -> Result<Expr, TypeErr> {
         Ok(match self {
             Expr::Atom(Atom::Symbol(symbol)) => {
-                if let Ok(Def::Expr(expr)) = env.get(&symbol) {
+                if let Ok(Def::Expr(expr)) = env.get(symbol) {
                     expr.clone()
                 } else {
                     self.clone()
@@ -277,10 +575,4 @@ impl Expr {
             _ => self.clone(),
         })
     }
-
-    // Sort an expression by precedence and associativity
-    fn order(&mut self) {}
-
-    // Sum up etc.
-    fn simplify(&mut self) {}
 }
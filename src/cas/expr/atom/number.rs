@@ -1,36 +1,56 @@
-use super::super::super::lexer::{
-    Lexer, LexerErr, BIN_DIGITS, DEC_DIGITS, E_TOKEN_LEN, HEX_DIGITS,
+use {
+    super::super::super::lexer::{
+        Lexer, LexerErr, BIN_DIGITS, DEC_DIGITS, E_TOKEN_LEN, HEX_DIGITS, OCT_DIGITS,
+    },
+    std::{convert::TryFrom, rc::Rc},
 };
 
+mod combinatorics;
+mod dual;
+mod fixed;
+mod interval;
 mod ops;
+mod special;
+mod uncertain;
 
-/// Wrapper of a f64. Maybe implement arbitrary precicion in the future.
-#[derive(Clone, Copy, PartialEq)]
-pub struct Number(pub(crate) f64);
+pub use fixed::Width;
+
+/// Either an exact fraction, an inexact float, an interval around a number,
+/// a number with a standard uncertainty, an integer of fixed width
+/// or a dual number with a derivative.
+/// Maybe implement arbitrary precicion in the future.
+#[derive(Clone, PartialEq)]
+pub enum Number {
+    /// Numerator and denominator, always reduced and with a positive denominator.
+    Ratio(i128, i128),
+    Float(f64),
+    /// Lower and upper bound, which are rounded outward.
+    Interval(f64, f64),
+    /// Value and its linear dependence on independent errors, as pairs of the error
+    /// and its coefficient.
+    Uncertain(f64, Rc<[(usize, f64)]>),
+    /// Value, which is always in the range of the width.
+    Fixed(i128, Width),
+    /// Value and derivative, which is the coefficient of `ε` with `ε^2 = 0`.
+    Dual(f64, f64),
+}
 
 impl Number {
     pub(crate) fn parse(lexer: &mut Lexer) -> Result<Number, LexerErr> {
-        /* let first = lexer
-            .chars
-            .peek()
-            .expect("Lexer.chars.peek() None, but not before")
-            .1;
-
-        if first == '0' {
-            lexer.chars.next();
-
-            match lexer.chars.peek() {
-                Some((_, 'x')) => {
-                    lexer.chars.next();
-                    return Number::parse_hex(lexer);
-                }
-                Some((_, 'b')) => {
-                    lexer.chars.next();
-                    return Number::parse_bin(lexer);
-                }
-                _ => {}
-            }
-        } */
+        // exact integers with the prefix of their base, like `0xFF`
+        if let Some((_, '0')) = lexer.chars.peek() {
+            let mut ahead = lexer.chars.clone().skip(1).map(|(_, pot)| pot);
+
+            // `0o9` is a bad octal literal, not `0 * o9`
+            let parse = match (ahead.next(), ahead.next()) {
+                (Some('x'), Some(pot)) if is_hex_digit(&pot) => Number::parse_hex,
+                (Some('o'), Some(pot)) if pot.is_ascii_digit() => Number::parse_oct,
+                (Some('b'), Some(pot)) if pot.is_ascii_digit() => Number::parse_bin,
+                _ => Number::parse_dec,
+            };
+
+            return parse(lexer);
+        }
 
         Number::parse_dec(lexer)
     }
@@ -39,58 +59,191 @@ impl Number {
         let mut string = String::with_capacity(E_TOKEN_LEN);
         let start = lexer.start();
 
-        //string.push_str(&lexer.eat(|pot| "+-".contains(*pot)));
         string.push_str(&lexer.eat(is_dec_digit));
 
-        if let Ok((_, pot)) = lexer.expect(vec!['.']) {
-            string.push(pot);
-            string.push_str(&lexer.eat(is_dec_digit));
+        // integers are exact, everything with a point or an exponent is a float
+        let mut exact = true;
+
+        // the point of `1..2` belongs to the interval
+        if !lexer.ahead("..") {
+            if let Ok((_, pot)) = lexer.expect(vec!['.']) {
+                exact = false;
+                string.push(pot);
+                string.push_str(&lexer.eat(is_dec_digit));
+            }
         }
 
         if let Ok((_, pot)) = lexer.expect(vec!['e', 'E']) {
+            exact = false;
             string.push(pot);
             string.push_str(&lexer.eat(|pot| "+-".contains(*pot)));
             string.push_str(&lexer.eat(is_dec_digit));
         }
 
+        if exact {
+            if let Ok(number) = string.parse::<i128>() {
+                return Ok(Number::int(number));
+            }
+        }
+
         string
             .parse::<f64>()
-            .map_err(|_| LexerErr::panic(format!("could not parse decimal"), start))
-            .map(|number| Number(number))
+            .map_err(|_| LexerErr::panic("could not parse decimal".to_string(), start))
+            .map(Number::Float)
     }
 
     fn parse_hex(lexer: &mut Lexer) -> Result<Number, LexerErr> {
-        let mut string = String::with_capacity(E_TOKEN_LEN);
+        Number::parse_radix(lexer, 16, is_hex_digit, "hexadecimal")
+    }
+
+    fn parse_oct(lexer: &mut Lexer) -> Result<Number, LexerErr> {
+        Number::parse_radix(lexer, 8, is_oct_digit, "octal")
+    }
+
+    fn parse_bin(lexer: &mut Lexer) -> Result<Number, LexerErr> {
+        Number::parse_radix(lexer, 2, is_bin_digit, "binary")
+    }
+
+    /// Digits after a prefix of two chars, like `0x`.
+    fn parse_radix(
+        lexer: &mut Lexer,
+        radix: u32,
+        is_digit: fn(&char) -> bool,
+        name: &str,
+    ) -> Result<Number, LexerErr> {
         let start = lexer.start();
+        lexer.chars.nth(1);
+        let digits = lexer.eat(is_digit);
 
-        string.push_str(&lexer.eat(is_hex_digit));
+        // `0b102` is not `0b10 * 2`
+        if let Some(&(at, pot)) = lexer.chars.peek() {
+            if pot.is_ascii_alphanumeric() || pot == '_' {
+                return Err(LexerErr::panic(
+                    format!("invalid digit `{}` in {} literal", pot, name),
+                    at,
+                ));
+            }
+        }
 
-        if let Ok((_, pot)) = lexer.expect(vec!['.']) {
-            string.push(pot);
-            string.push_str(&lexer.eat(is_hex_digit));
+        i128::from_str_radix(&digits, radix)
+            .map(Number::int)
+            .map_err(|_| LexerErr::panic(format!("could not parse {}", name), start))
+    }
+
+    pub(crate) fn int(number: i128) -> Number {
+        Number::Ratio(number, 1)
+    }
+
+    /// Reduced fraction, falls back to a float on division by zero.
+    pub(crate) fn ratio(num: i128, den: i128) -> Number {
+        if den == 0 {
+            return Number::Float(num as f64 / 0.0);
         }
 
-        string
-            .parse::<f64>()
-            .map_err(|_| LexerErr::panic(format!("could not parse hexadecimal"), start))
-            .map(|number| Number(number))
+        let gcd = match i128::try_from(gcd(num, den)) {
+            Ok(gcd) => gcd,
+            // both are `i128::MIN` or 0
+            Err(_) => return Number::int((num == den) as i128),
+        };
+        let (num, den) = (num / gcd, den / gcd);
+
+        if den < 0 {
+            match (num.checked_neg(), den.checked_neg()) {
+                (Some(num), Some(den)) => Number::Ratio(num, den),
+                _ => Number::Float(num as f64 / den as f64),
+            }
+        } else {
+            Number::Ratio(num, den)
+        }
     }
 
-    fn parse_bin(lexer: &mut Lexer) -> Result<Number, LexerErr> {
-        let mut string = String::with_capacity(E_TOKEN_LEN);
-        let start = lexer.start();
+    pub(crate) fn is_exact(&self) -> bool {
+        matches!(self, Number::Ratio(_, _))
+    }
 
-        string.push_str(&lexer.eat(is_bin_digit));
+    pub(crate) fn is_zero(&self) -> bool {
+        match self {
+            Number::Ratio(num, _) => *num == 0,
+            Number::Float(float) => *float == 0.0,
+            Number::Interval(lower, upper) => *lower == 0.0 && *upper == 0.0,
+            Number::Uncertain(_, _) => false,
+            Number::Fixed(n, _) => *n == 0,
+            Number::Dual(value, tangent) => *value == 0.0 && *tangent == 0.0,
+        }
+    }
 
-        if let Ok((_, pot)) = lexer.expect(vec!['.']) {
-            string.push(pot);
-            string.push_str(&lexer.eat(is_bin_digit));
+    pub(crate) fn is_one(&self) -> bool {
+        match self {
+            Number::Ratio(num, den) => *num == 1 && *den == 1,
+            Number::Float(float) => *float == 1.0,
+            Number::Interval(lower, upper) => *lower == 1.0 && *upper == 1.0,
+            Number::Uncertain(_, _) => false,
+            Number::Fixed(n, _) => *n == 1,
+            Number::Dual(value, tangent) => *value == 1.0 && *tangent == 0.0,
         }
+    }
 
-        string
-            .parse::<f64>()
-            .map_err(|_| LexerErr::panic(format!("could not parse binary"), start))
-            .map(|number| Number(number))
+    pub(crate) fn is_negative(&self) -> bool {
+        self.as_f64() < 0.0
+    }
+
+    /// The exact integer, if this is one.
+    pub(crate) fn as_int(&self) -> Option<i128> {
+        match self {
+            Number::Ratio(num, 1) | Number::Fixed(num, _) => Some(*num),
+            _ => None,
+        }
+    }
+
+    /// Value of the number, the midpoint of an interval.
+    pub(crate) fn as_f64(&self) -> f64 {
+        match self {
+            Number::Ratio(num, den) => *num as f64 / *den as f64,
+            Number::Float(float) => *float,
+            Number::Interval(lower, upper) => lower / 2.0 + upper / 2.0,
+            Number::Uncertain(value, _) | Number::Dual(value, _) => *value,
+            Number::Fixed(n, _) => *n as f64,
+        }
+    }
+
+    /// Value of a constant, that is defined as `OSConst`.
+    pub(crate) fn constant(name: &str) -> Option<Number> {
+        Some(Number::Float(match name {
+            "inf" => f64::INFINITY,
+            "nan" => f64::NAN,
+            "π" => std::f64::consts::PI,
+            "e" => std::f64::consts::E,
+            _ => return None,
+        }))
+    }
+
+    /// Inexact version of the number, intervals, uncertainties, fixed-width integers
+    /// and dual numbers stay as they are.
+    pub(crate) fn approx(&self) -> Number {
+        match self {
+            Number::Interval(_, _)
+            | Number::Uncertain(_, _)
+            | Number::Fixed(_, _)
+            | Number::Dual(_, _) => self.clone(),
+            _ => Number::Float(self.as_f64()),
+        }
+    }
+}
+
+/// Positive greatest common divisor, where `gcd(0, 0)` is 1 so that it always divides.
+/// It is unsigned, because `gcd(i128::MIN, 0)` is `2^127`.
+pub(crate) fn gcd(a: i128, b: i128) -> u128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        let rem = a % b;
+        a = b;
+        b = rem;
+    }
+
+    if a == 0 {
+        1
+    } else {
+        a
     }
 }
 
@@ -102,6 +255,10 @@ fn is_hex_digit(pot: &char) -> bool {
     HEX_DIGITS.contains(*pot)
 }
 
+fn is_oct_digit(pot: &char) -> bool {
+    OCT_DIGITS.contains(*pot)
+}
+
 fn is_bin_digit(pot: &char) -> bool {
     BIN_DIGITS.contains(*pot)
 }
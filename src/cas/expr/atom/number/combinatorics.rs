@@ -0,0 +1,392 @@
+use {
+    super::{gcd, Number},
+    std::f64::consts::PI,
+};
+
+/// Coefficients of the Lanczos approximation with `g = 7`, good to 15 digits.
+const LANCZOS: [f64; 9] = [
+    0.999_999_999_999_809_9,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_572e-6,
+    1.505_632_735_149_311_6e-7,
+];
+
+/// Longest product, that is still multiplied out instead of using the Gamma function.
+const MAX_PRODUCT: i128 = 100_000;
+
+/// Most steps of a Stirling triangle, beyond them an asymptotic form is used.
+const MAX_TRIANGLE: i128 = 1_000_000;
+
+/// Above this Γ overflows a float.
+const MAX_GAMMA: f64 = 171.624_376_956_302_7;
+
+/// Combinatorial functions are exact on integers, as long as they fit, and
+/// continue to real arguments through the Gamma function where that is possible.
+/// Floats without a fraction count as integers, but the result stays a float.
+impl Number {
+    /// `n!`, which is `Γ(n + 1)` for non-integers and has poles at negative integers.
+    pub(crate) fn fact(self) -> Number {
+        match self.as_int() {
+            Some(n) if n < 0 => Number::ratio(1, 0),
+            Some(n) => {
+                let mut fact: i128 = 1;
+                for k in 2..=n {
+                    match fact.checked_mul(k) {
+                        Some(product) => fact = product,
+                        None => return Number::Float(gamma(n as f64 + 1.0)),
+                    }
+                }
+                Number::int(fact)
+            }
+            None => Number::Float(gamma(self.as_f64() + 1.0)),
+        }
+    }
+
+    pub(crate) fn gamma(self) -> Number {
+        match self.as_int() {
+            Some(n) if n <= 0 => Number::ratio(1, 0),
+            Some(n) => Number::int(n - 1).fact(),
+            None => Number::Float(gamma(self.as_f64())),
+        }
+    }
+
+    /// Logarithm of `|Γ(x)|`, which does not overflow.
+    pub(crate) fn lgamma(self) -> Number {
+        match self.as_int() {
+            Some(1) | Some(2) => Number::int(0),
+            Some(n) if n <= 0 => Number::ratio(1, 0),
+            _ => Number::Float(ln_gamma(self.as_f64())),
+        }
+    }
+
+    /// `B(a, b) = Γ(a) Γ(b) / Γ(a + b)`.
+    pub(crate) fn beta(self, rhs: Number) -> Number {
+        if let (Some(a), Some(b)) = (self.as_int(), rhs.as_int()) {
+            if a > 0 && b > 0 {
+                let one = Number::int(1);
+                return (self.clone() - one.clone()).fact() * (rhs.clone() - one.clone()).fact()
+                    / (self + rhs - one).fact();
+            }
+        }
+
+        let (a, b) = (self.as_f64(), rhs.as_f64());
+        Number::Float(gamma_ratio(&[a, b], &[a + b]))
+    }
+
+    /// `binomial(n, k) = n! / (k! (n - k)!)`, for any `n` if `k` is an integer.
+    pub(crate) fn binomial(self, rhs: Number) -> Number {
+        let exact = self.is_exact() && rhs.is_exact();
+        let binomial = match (integral(&self), integral(&rhs)) {
+            (_, Some(k)) if k < 0 => Number::int(0),
+            (Some(n), Some(k)) if n >= 0 && k > n => Number::int(0),
+            (Some(n), Some(k)) if n >= 0 => binomial(n, k.min(n - k)),
+            // binomial(-n, k) = (-1)^k binomial(n + k - 1, k)
+            (Some(n), Some(k)) => match n.checked_neg().and_then(|n| (n - 1).checked_add(k)) {
+                Some(m) if k % 2 == 0 => binomial(m, k.min(m - k)),
+                Some(m) => -binomial(m, k.min(m - k)),
+                None => Number::Float(f64::NAN),
+            },
+            (None, Some(k)) if k <= MAX_PRODUCT => falling(self, k) / Number::int(k).fact(),
+            _ => {
+                let (n, k) = (self.as_f64(), rhs.as_f64());
+                Number::Float(gamma_ratio(&[n + 1.0], &[k + 1.0, n - k + 1.0]))
+            }
+        };
+
+        inexact(binomial, exact)
+    }
+
+    /// Permutations `perm(n, k) = n! / (n - k)!` of k out of n.
+    pub(crate) fn perm(self, rhs: Number) -> Number {
+        let exact = self.is_exact() && rhs.is_exact();
+        let perm = match (integral(&self), integral(&rhs)) {
+            (Some(n), Some(k)) if n >= 0 && k > n => Number::int(0),
+            (_, Some(k)) if (0..=MAX_PRODUCT).contains(&k) => falling(self, k),
+            _ => {
+                let (n, k) = (self.as_f64(), rhs.as_f64());
+                Number::Float(gamma_ratio(&[n + 1.0], &[n - k + 1.0]))
+            }
+        };
+
+        inexact(perm, exact)
+    }
+
+    /// `n! / (k1! k2! ...)` with `n = k1 + k2 + ...`.
+    pub(crate) fn multinomial(args: Vec<Number>) -> Number {
+        if args.iter().all(|k| k.as_int().is_some_and(|k| k >= 0)) {
+            let mut sum = Number::int(0);
+            let mut product = Number::int(1);
+
+            for k in args {
+                sum = sum + k.clone();
+                product = product * sum.clone().binomial(k);
+            }
+
+            return product;
+        }
+
+        let sum: f64 = args.iter().map(Number::as_f64).sum();
+        let den: Vec<f64> = args.iter().map(|k| k.as_f64() + 1.0).collect();
+        Number::Float(gamma_ratio(&[sum + 1.0], &den))
+    }
+
+    /// Unsigned Stirling numbers of the first kind, permutations of n with k cycles.
+    /// There are `(n - 1)!` with a single cycle.
+    pub(crate) fn stirling1(self, rhs: Number) -> Number {
+        triangle(
+            self,
+            rhs,
+            |n, _| Number::int(n),
+            |n| Number::int(n - 1).fact(),
+        )
+    }
+
+    /// Stirling numbers of the second kind, partitions of n into k non-empty sets.
+    pub(crate) fn stirling2(self, rhs: Number) -> Number {
+        triangle(self, rhs, |_, k| Number::int(k), |_| Number::int(1))
+    }
+
+    /// Bell numbers, all partitions of n.
+    pub(crate) fn bell(self) -> Number {
+        let n = match integral(&self) {
+            Some(n) if n >= 0 => n,
+            _ => return Number::Float(f64::NAN),
+        };
+        // a longer triangle overflows anyway
+        if n * n > MAX_TRIANGLE {
+            return inexact(Number::Float(f64::INFINITY), self.is_exact());
+        }
+
+        // Bell triangle, every row starts with the end of the last one
+        let mut row = vec![Number::int(1)];
+        for _ in 0..n {
+            let mut next = vec![row.last().expect("rows are not empty").clone()];
+            for value in &row {
+                let last = next.last().expect("rows are not empty").clone();
+                next.push(last + value.clone());
+            }
+            row = next;
+        }
+
+        inexact(row.swap_remove(0), self.is_exact())
+    }
+
+    /// Catalan numbers `binomial(2n, n) / (n + 1)`.
+    pub(crate) fn catalan(self) -> Number {
+        match self.as_int() {
+            Some(n) if n >= 0 => {
+                (Number::int(2) * self.clone()).binomial(self.clone()) / (self + Number::int(1))
+            }
+            _ => {
+                let n = self.as_f64();
+                Number::Float(gamma_ratio(&[2.0 * n + 1.0], &[n + 2.0, n + 1.0]))
+            }
+        }
+    }
+
+    /// Fibonacci numbers, for real n by Binet's formula.
+    pub(crate) fn fibonacci(self) -> Number {
+        match self.as_int() {
+            // F(-n) = (-1)^(n + 1) F(n)
+            Some(n) if n < 0 && n % 2 == 0 => -fibonacci(n.unsigned_abs()).0,
+            Some(n) => fibonacci(n.unsigned_abs()).0,
+            None => {
+                let (n, phi) = (self.as_f64(), (1.0 + 5_f64.sqrt()) / 2.0);
+                Number::Float((phi.powf(n) - (PI * n).cos() * phi.powf(-n)) / 5_f64.sqrt())
+            }
+        }
+    }
+}
+
+/// `binomial(n, k)` of integers, without an overflowing intermediate product.
+fn binomial(n: i128, k: i128) -> Number {
+    let mut binomial: i128 = 1;
+
+    for i in 0..k {
+        // binomial * (n - i) is divisible by i + 1
+        let divisor = gcd(binomial, i + 1) as i128;
+        match (binomial / divisor).checked_mul((n - i) / ((i + 1) / divisor)) {
+            Some(product) => binomial = product,
+            // the ratio of Gamma functions cancels for large n and small k
+            None if k <= MAX_PRODUCT => {
+                return Number::Float((i..k).fold(binomial as f64, |product, i| {
+                    product * (n - i) as f64 / (i + 1) as f64
+                }))
+            }
+            None => {
+                let (n, k) = (n as f64, k as f64);
+                return Number::Float(gamma_ratio(&[n + 1.0], &[k + 1.0, n - k + 1.0]));
+            }
+        }
+    }
+
+    Number::int(binomial)
+}
+
+/// Falling factorial `x (x - 1) ... (x - k + 1)`.
+fn falling(x: Number, k: i128) -> Number {
+    (0..k).fold(Number::int(1), |product, i| {
+        product * (x.clone() - Number::int(i))
+    })
+}
+
+/// Entry of a Stirling triangle `T(n + 1, k) = factor(n, k) T(n, k) + T(n, k - 1)`,
+/// where `first(n)` is `T(n, 1)`.
+fn triangle(
+    n: Number,
+    k: Number,
+    factor: fn(i128, i128) -> Number,
+    first: fn(i128) -> Number,
+) -> Number {
+    let exact = n.is_exact() && k.is_exact();
+    let (n, k) = match (integral(&n), integral(&k)) {
+        (Some(n), Some(k)) if n >= 0 && k >= 0 => (n, k),
+        _ => return Number::Float(f64::NAN),
+    };
+    if k > n {
+        return Number::int(0);
+    }
+
+    // walk along the columns up to k, or along the diagonals up to `n - k`
+    let diagonal = n - k;
+    if n.saturating_mul(k.min(diagonal) + 1) > MAX_TRIANGLE {
+        return inexact(asymptotic(n, k, first), exact);
+    }
+
+    let mut row = vec![Number::int(0); k.min(diagonal) as usize + 1];
+    row[0] = Number::int(1);
+    for m in 0..n {
+        if k <= diagonal {
+            for j in (1..=k.min(m + 1) as usize).rev() {
+                row[j] = factor(m, j as i128) * row[j].clone() + row[j - 1].clone();
+            }
+            row[0] = Number::int(0);
+        } else {
+            // `row[j]` is `T(m, m - j)`
+            for j in (1..=diagonal.min(m + 1) as usize).rev() {
+                row[j] = factor(m, m + 1 - j as i128) * row[j - 1].clone() + row[j].clone();
+            }
+        }
+    }
+
+    inexact(row.swap_remove(k.min(diagonal) as usize), exact)
+}
+
+/// Stirling numbers, that are too far from the edges of the triangle to walk there.
+/// Far from the diagonal, both kinds are at least `k^(n - k)`, which overflows for
+/// `k > 1`, and near it they approach `binomial(n, 2)^j / j!` for `j = n - k`.
+fn asymptotic(n: i128, k: i128, first: fn(i128) -> Number) -> Number {
+    let diagonal = n - k;
+
+    match k {
+        0 => Number::int(0),
+        1 => first(n),
+        _ if k <= diagonal => Number::Float(f64::INFINITY),
+        _ => {
+            let (n, j) = (n as f64, diagonal as f64);
+            Number::Float((j * (n * (n - 1.0) / 2.0).ln() - ln_gamma(j + 1.0)).exp())
+        }
+    }
+}
+
+/// The integer of an exact integer or of a float without a fraction.
+fn integral(x: &Number) -> Option<i128> {
+    match x {
+        Number::Float(float) if float.fract() == 0.0 && float.abs() < i128::MAX as f64 => {
+            Some(*float as i128)
+        }
+        _ => x.as_int(),
+    }
+}
+
+/// The result as a float, unless all arguments were exact.
+fn inexact(result: Number, exact: bool) -> Number {
+    if exact {
+        result
+    } else {
+        result.approx()
+    }
+}
+
+/// `(F(n), F(n + 1))` by doubling.
+fn fibonacci(n: u128) -> (Number, Number) {
+    if n == 0 {
+        return (Number::int(0), Number::int(1));
+    }
+
+    let (a, b) = fibonacci(n / 2);
+    // F(2k) = F(k) (2 F(k + 1) - F(k)), F(2k + 1) = F(k)^2 + F(k + 1)^2
+    let (even, odd) = (
+        a.clone() * (Number::int(2) * b.clone() - a.clone()),
+        a.clone() * a + b.clone() * b,
+    );
+
+    if n.is_multiple_of(2) {
+        (even, odd)
+    } else {
+        (odd.clone(), even + odd)
+    }
+}
+
+/// Γ(x) by the Lanczos approximation, reflected for `x < 1/2`.
+pub(super) fn gamma(x: f64) -> f64 {
+    if x < 0.5 {
+        return PI / ((PI * x).sin() * gamma(1.0 - x));
+    }
+    if x > MAX_GAMMA {
+        return f64::INFINITY;
+    }
+
+    // the power alone overflows before Γ does
+    let (t, sum) = lanczos(x);
+    let power = t.powf((x - 0.5) / 2.0);
+    (2.0 * PI).sqrt() * power * (-t).exp() * power * sum
+}
+
+/// `Γ(a1) Γ(a2) ... / (Γ(b1) Γ(b2) ...)`, by the logarithms where the Gamma functions
+/// overflow but their ratio does not, like in `binomial(200, 100)`.
+fn gamma_ratio(num: &[f64], den: &[f64]) -> f64 {
+    let product = |xs: &[f64]| xs.iter().map(|x| gamma(*x)).product::<f64>();
+    let ratio = product(num) / product(den);
+    if ratio.is_finite() && ratio != 0.0 {
+        return ratio;
+    }
+
+    let ln = |xs: &[f64]| xs.iter().map(|x| ln_gamma(*x)).sum::<f64>();
+    let sign: f64 = num.iter().chain(den).map(|x| gamma_sign(*x)).product();
+    sign * (ln(num) - ln(den)).exp()
+}
+
+/// Sign of Γ(x), which alternates between the poles at negative integers.
+fn gamma_sign(x: f64) -> f64 {
+    if x > 0.0 || x.floor() % 2.0 == 0.0 {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+/// `ln |Γ(x)|`.
+pub(super) fn ln_gamma(x: f64) -> f64 {
+    if x < 0.5 {
+        return (PI / (PI * x).sin().abs()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let (t, sum) = lanczos(x);
+    0.5 * (2.0 * PI).ln() + (x - 0.5) * t.ln() - t + sum.ln()
+}
+
+fn lanczos(x: f64) -> (f64, f64) {
+    let x = x - 1.0;
+    let sum = LANCZOS[1..]
+        .iter()
+        .enumerate()
+        .fold(LANCZOS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.0));
+
+    (x + 7.5, sum)
+}
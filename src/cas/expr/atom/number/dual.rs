@@ -0,0 +1,90 @@
+use super::Number;
+
+/// Dual numbers `a + b ε` with `ε^2 = 0`, which carry a derivative along with their value.
+/// Every operation applies the chain rule, so derivatives are exact up to rounding.
+impl Number {
+    pub(crate) fn is_dual(&self) -> bool {
+        matches!(self, Number::Dual(_, _))
+    }
+
+    /// Derivative, which is 0 for all other numbers.
+    pub(crate) fn tangent(&self) -> f64 {
+        match *self {
+            Number::Dual(_, tangent) => tangent,
+            _ => 0.0,
+        }
+    }
+
+    /// Result of a function with a known slope at the value.
+    pub(crate) fn chain(self, value: Number, slope: f64) -> Number {
+        Number::Dual(value.as_f64(), scale(slope, self.tangent()))
+    }
+
+    /// Result of a function of two numbers with known partial slopes.
+    pub(crate) fn chain_two(self, rhs: Number, value: Number, slopes: (f64, f64)) -> Number {
+        Number::Dual(
+            value.as_f64(),
+            scale(slopes.0, self.tangent()) + scale(slopes.1, rhs.tangent()),
+        )
+    }
+
+    pub(super) fn dual_add(self, rhs: Number) -> Number {
+        Number::Dual(self.as_f64() + rhs.as_f64(), self.tangent() + rhs.tangent())
+    }
+
+    pub(super) fn dual_mul(self, rhs: Number) -> Number {
+        let (a, b) = (self.as_f64(), rhs.as_f64());
+        self.chain_two(rhs, Number::Float(a * b), (b, a))
+    }
+
+    pub(super) fn dual_div(self, rhs: Number) -> Number {
+        let (a, b) = (self.as_f64(), rhs.as_f64());
+        self.chain_two(rhs, Number::Float(a / b), (1.0 / b, -a / (b * b)))
+    }
+
+    pub(super) fn dual_pow(self, rhs: Number) -> Number {
+        let (a, b) = (self.as_f64(), rhs.as_f64());
+        let value = a.powf(b);
+
+        self.chain_two(
+            rhs,
+            Number::Float(value),
+            (b * a.powf(b - 1.0), value * a.ln()),
+        )
+    }
+
+    pub(super) fn dual_modulus(self, rhs: Number) -> Number {
+        let (a, b) = (self.as_f64(), rhs.as_f64());
+        let floor = (a / b).floor();
+
+        self.chain_two(rhs, Number::Float(a - b * floor), (1.0, -floor))
+    }
+
+    /// `log(x, b) = ln x / ln b`.
+    pub(super) fn dual_log(self, rhs: Number) -> Number {
+        let (a, b) = (self.as_f64(), rhs.as_f64());
+        let ln = b.ln();
+
+        self.chain_two(
+            rhs,
+            Number::Float(a.log(b)),
+            (1.0 / (a * ln), -a.ln() / (b * ln * ln)),
+        )
+    }
+
+    pub(super) fn dual_angle(self, rhs: Number) -> Number {
+        let (y, x) = (self.as_f64(), rhs.as_f64());
+        let square = x * x + y * y;
+
+        self.chain_two(rhs, Number::Float(y.atan2(x)), (x / square, -y / square))
+    }
+}
+
+/// Slope times tangent, where a constant stays constant even at a pole.
+fn scale(slope: f64, tangent: f64) -> f64 {
+    if tangent == 0.0 {
+        0.0
+    } else {
+        slope * tangent
+    }
+}
@@ -0,0 +1,172 @@
+use {
+    super::Number,
+    std::{cmp::Ordering, convert::TryFrom},
+};
+
+/// Bits of a fixed-width integer, if it has a sign and if it saturates instead of wrapping around.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Width {
+    pub(crate) bits: u32,
+    pub(crate) signed: bool,
+    pub(crate) saturating: bool,
+}
+
+impl Width {
+    /// Types like `u8` or `i64`, which wrap around.
+    pub(crate) fn parse(name: &str) -> Option<Width> {
+        let (signed, bits) = match name.split_at(1) {
+            ("u", bits) => (false, bits),
+            ("i", bits) => (true, bits),
+            _ => return None,
+        };
+
+        match bits.parse() {
+            Ok(bits @ (8 | 16 | 32 | 64)) => Some(Width {
+                bits,
+                signed,
+                saturating: false,
+            }),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn min(&self) -> i128 {
+        if self.signed {
+            -(1 << (self.bits - 1))
+        } else {
+            0
+        }
+    }
+
+    pub(crate) fn max(&self) -> i128 {
+        if self.signed {
+            (1 << (self.bits - 1)) - 1
+        } else {
+            (1 << self.bits) - 1
+        }
+    }
+
+    /// An integer in range, by wrapping around or saturating.
+    pub(crate) fn fit(&self, n: i128) -> Number {
+        if self.saturating {
+            return Number::Fixed(n.clamp(self.min(), self.max()), *self);
+        }
+
+        let n = n.rem_euclid(1 << self.bits);
+        Number::Fixed(
+            if n > self.max() {
+                n - (1 << self.bits)
+            } else {
+                n
+            },
+            *self,
+        )
+    }
+
+    /// Result of an operation, which might not even fit into an `i128`,
+    /// so it is wrapped around `2^128` first, or saturated towards its sign.
+    fn fit_overflow(&self, exact: Option<i128>, wrapped: i128, positive: bool) -> Number {
+        match exact {
+            Some(n) => self.fit(n),
+            None if !self.saturating => self.fit(wrapped),
+            None if positive => self.fit(i128::MAX),
+            None => self.fit(i128::MIN),
+        }
+    }
+
+    /// Width of mixed integers, which is the wider one and signed for equal bits,
+    /// if either is signed. It saturates, if either does.
+    pub(crate) fn promote(self, other: Width) -> Width {
+        let signed = match self.bits.cmp(&other.bits) {
+            Ordering::Greater => self.signed,
+            Ordering::Less => other.signed,
+            Ordering::Equal => self.signed || other.signed,
+        };
+
+        Width {
+            bits: self.bits.max(other.bits),
+            signed,
+            saturating: self.saturating || other.saturating,
+        }
+    }
+
+    /// Shift to the left by `k` bits, which is `n 2^k` wrapped or saturated.
+    pub(crate) fn shl(&self, n: i128, k: u128) -> Number {
+        if !self.saturating {
+            let wrapped = u32::try_from(k)
+                .ok()
+                .and_then(|k| (n as u128).checked_shl(k));
+            return self.fit(wrapped.unwrap_or(0) as i128);
+        }
+
+        match k {
+            _ if n == 0 => self.fit(0),
+            // at most 64 bits shifted by 63 still fit
+            k if k < self.bits as u128 => self.fit(n << k),
+            _ => self.fit_overflow(None, 0, n > 0),
+        }
+    }
+}
+
+/// Integers of a fixed width, which either wrap around or saturate.
+/// Arithmetic with another integer keeps its width, mixed widths are promoted.
+impl Number {
+    pub(crate) fn is_fixed(&self) -> bool {
+        matches!(self, Number::Fixed(_, _))
+    }
+
+    /// Promoted width of the fixed-width integers and both values, if both are integers.
+    fn fixed_pair(&self, rhs: &Number) -> Option<(Width, i128, i128)> {
+        let width = match (self, rhs) {
+            (Number::Fixed(_, left), Number::Fixed(_, right)) => left.promote(*right),
+            (Number::Fixed(_, width), _) | (_, Number::Fixed(_, width)) => *width,
+            _ => return None,
+        };
+
+        Some((width, self.as_int()?, rhs.as_int()?))
+    }
+
+    pub(super) fn fixed_add(&self, rhs: &Number) -> Option<Number> {
+        let (width, a, b) = self.fixed_pair(rhs)?;
+        Some(width.fit(a + b))
+    }
+
+    pub(super) fn fixed_sub(&self, rhs: &Number) -> Option<Number> {
+        let (width, a, b) = self.fixed_pair(rhs)?;
+        Some(width.fit(a - b))
+    }
+
+    pub(super) fn fixed_mul(&self, rhs: &Number) -> Option<Number> {
+        let (width, a, b) = self.fixed_pair(rhs)?;
+        Some(width.fit_overflow(a.checked_mul(b), a.wrapping_mul(b), (a < 0) == (b < 0)))
+    }
+
+    /// Division rounds towards zero, evaluation rejects a zero divisor before.
+    pub(super) fn fixed_div(&self, rhs: &Number) -> Option<Number> {
+        let (width, a, b) = self.fixed_pair(rhs)?;
+        Some(match a.checked_div(b) {
+            Some(n) => width.fit(n),
+            None => Number::Float(a as f64 / 0.0),
+        })
+    }
+
+    pub(super) fn fixed_modulus(&self, rhs: &Number) -> Option<Number> {
+        let (width, a, b) = self.fixed_pair(rhs)?;
+        match Number::int(a).modulus(Number::int(b)) {
+            Number::Ratio(n, 1) => Some(width.fit(n)),
+            nan => Some(nan),
+        }
+    }
+
+    /// Only powers with a natural exponent stay integers.
+    pub(super) fn fixed_pow(&self, rhs: &Number) -> Option<Number> {
+        let (width, a, b) = self.fixed_pair(rhs)?;
+        let exp = u32::try_from(b).ok()?;
+
+        Some(width.fit_overflow(
+            a.checked_pow(exp),
+            a.wrapping_pow(exp),
+            a >= 0 || exp % 2 == 0,
+        ))
+    }
+}
@@ -0,0 +1,347 @@
+use {
+    super::{combinatorics::gamma, special::erf, Number},
+    std::f64::consts::{FRAC_PI_2, PI, TAU},
+};
+
+/// Rounding error of the float functions of the standard library, in units of the last place.
+const LIBRARY_ULPS: u32 = 4;
+
+/// Rounding error of the approximations of special functions, which are good to 15 digits.
+const APPROXIMATION_ULPS: u32 = 64;
+
+/// Minimum of `Γ` on the positive numbers, where it turns from falling to rising.
+const GAMMA_MIN_AT: f64 = 1.461_632_144_968_362_2;
+const GAMMA_MIN: f64 = 0.885_603_194_410_888_6;
+
+/// Intervals `[lower .. upper]`, which certainly enclose a real number. Every bound is
+/// rounded outward, fractions are enclosed by their neighbouring floats, and floats
+/// count as exact. Functions without a known enclosure give all numbers `[-inf .. inf]`.
+impl Number {
+    /// Smallest interval around two numbers, in any order.
+    pub(crate) fn hull(self, other: Number) -> Number {
+        let ((a, b), (c, d)) = (self.bounds(), other.bounds());
+
+        // min and max would just drop a NaN
+        if [a, b, c, d].iter().any(|bound| bound.is_nan()) {
+            return Number::Interval(f64::NAN, f64::NAN);
+        }
+
+        Number::Interval(a.min(c), b.max(d))
+    }
+
+    pub(crate) fn is_interval(&self) -> bool {
+        matches!(self, Number::Interval(_, _))
+    }
+
+    /// Lower and upper bound of the number.
+    pub(crate) fn bounds(&self) -> (f64, f64) {
+        match *self {
+            Number::Ratio(num, den) => {
+                let value = num as f64 / den as f64;
+
+                // only integers up to 2^53 have an exact float
+                if den == 1 && value as i128 == num {
+                    (value, value)
+                } else {
+                    (value.next_down(), value.next_up())
+                }
+            }
+            Number::Float(float) | Number::Dual(float, _) => (float, float),
+            Number::Interval(lower, upper) => (lower, upper),
+            Number::Fixed(n, _) => Number::int(n).bounds(),
+            // one standard deviation
+            Number::Uncertain(value, _) => {
+                let deviation = self.deviation();
+                (down(value - deviation, 1), up(value + deviation, 1))
+            }
+        }
+    }
+
+    pub(super) fn interval_add(self, rhs: Number) -> Number {
+        let ((a, b), (c, d)) = (self.bounds(), rhs.bounds());
+        Number::Interval(add(a, c, false), add(b, d, true))
+    }
+
+    pub(super) fn interval_mul(self, rhs: Number) -> Number {
+        let ((a, b), (c, d)) = (self.bounds(), rhs.bounds());
+        let products = |up| [mul(a, c, up), mul(a, d, up), mul(b, c, up), mul(b, d, up)];
+
+        Number::Interval(min(&products(false)), max(&products(true)))
+    }
+
+    pub(super) fn interval_div(self, rhs: Number) -> Number {
+        let ((a, b), (c, d)) = (self.bounds(), rhs.bounds());
+
+        // the divisor is zero or the quotient is unbounded
+        if c == 0.0 && d == 0.0 {
+            return Number::Interval(f64::NAN, f64::NAN);
+        }
+        if c <= 0.0 && d >= 0.0 {
+            return Number::Interval(f64::NEG_INFINITY, f64::INFINITY);
+        }
+
+        let quotients = |up| [div(a, c, up), div(a, d, up), div(b, c, up), div(b, d, up)];
+        Number::Interval(min(&quotients(false)), max(&quotients(true)))
+    }
+
+    pub(super) fn interval_pow(self, rhs: Number) -> Number {
+        let (a, b) = self.bounds();
+
+        match rhs {
+            Number::Ratio(1, 2) if b < 0.0 => Number::Interval(f64::NAN, f64::NAN),
+            Number::Ratio(1, 2) => Number::Interval(sqrt(a.max(0.0), false), sqrt(b, true)),
+            Number::Ratio(n, 1) if n < 0 => Number::int(1).interval_div(self.interval_pow(-rhs)),
+            Number::Ratio(n, 1) => {
+                let odd = n % 2 == 1;
+
+                if a >= 0.0 {
+                    Number::Interval(power(a, n, false), power(b, n, true))
+                } else if b <= 0.0 && odd {
+                    Number::Interval(-power(-a, n, true), -power(-b, n, false))
+                } else if b <= 0.0 {
+                    Number::Interval(power(-b, n, false), power(-a, n, true))
+                } else if odd {
+                    Number::Interval(-power(-a, n, true), power(b, n, true))
+                } else {
+                    Number::Interval(0.0, power(-a, n, true).max(power(b, n, true)))
+                }
+            }
+            // odd roots are odd functions, `x^(1/q) = -(-x)^(1/q)`
+            Number::Ratio(p, q) if q % 2 == 1 && a < 0.0 => {
+                let root = |lower: f64, upper: f64| {
+                    Number::Interval(lower, upper).interval_pow(Number::ratio(1, q))
+                };
+
+                let negative = -root((-b).max(0.0), -a);
+                let root = if b <= 0.0 {
+                    negative
+                } else {
+                    negative.hull(root(0.0, b))
+                };
+                root.interval_pow(Number::int(p))
+            }
+            // x^y = exp(y ln x) of positive x
+            _ => self.enclose_one("ln").interval_mul(rhs).enclose_one("exp"),
+        }
+    }
+
+    /// `a % m = a - m floor(a / m)`, which is in between `0` and `m` where the floor jumps.
+    pub(super) fn interval_modulus(self, rhs: Number) -> Number {
+        let (lower, upper) = self.clone().interval_div(rhs.clone()).bounds();
+        let (floor, ceil) = (lower.floor(), upper.floor());
+
+        if floor == ceil && floor.is_finite() {
+            self.interval_add(-rhs.interval_mul(Number::Float(floor)))
+        } else {
+            Number::int(0).hull(rhs)
+        }
+    }
+
+    /// Enclosure of a built-in function of one interval.
+    pub(crate) fn enclose_one(self, call: &str) -> Number {
+        let (a, b) = self.bounds();
+        let all = (f64::NEG_INFINITY, f64::INFINITY);
+
+        match call {
+            "exp" => increasing(a, b, f64::exp, all, LIBRARY_ULPS),
+            "ln" => increasing(a, b, f64::ln, (0.0, f64::INFINITY), LIBRARY_ULPS),
+            "lg" => increasing(a, b, f64::log10, (0.0, f64::INFINITY), LIBRARY_ULPS),
+            "sqrt" => self.interval_pow(Number::ratio(1, 2)),
+            "cbrt" => increasing(a, b, f64::cbrt, all, LIBRARY_ULPS),
+
+            "sinh" => increasing(a, b, f64::sinh, all, LIBRARY_ULPS),
+            "asinh" => increasing(a, b, f64::asinh, all, LIBRARY_ULPS),
+            "tanh" => increasing(a, b, f64::tanh, all, LIBRARY_ULPS),
+            "atanh" => increasing(a, b, f64::atanh, (-1.0, 1.0), LIBRARY_ULPS),
+            "acosh" => increasing(a, b, f64::acosh, (1.0, f64::INFINITY), LIBRARY_ULPS),
+            "asin" => increasing(a, b, f64::asin, (-1.0, 1.0), LIBRARY_ULPS),
+            "atan" => increasing(a, b, f64::atan, all, LIBRARY_ULPS),
+            "acos" => increasing(-b, -a, |x| f64::acos(-x), (-1.0, 1.0), LIBRARY_ULPS),
+            "erf" => increasing(a, b, erf, all, APPROXIMATION_ULPS),
+            "erfc" => increasing(-b, -a, |x| 1.0 + erf(x), all, APPROXIMATION_ULPS),
+
+            // steps are exact
+            "floor" => Number::Interval(a.floor(), b.floor()),
+            "ceil" => Number::Interval(a.ceil(), b.ceil()),
+            "round" => Number::Interval(a.round(), b.round()),
+            "trunc" => Number::Interval(a.trunc(), b.trunc()),
+            "signum" => Number::Interval(a.signum(), b.signum()),
+            "fract" if a.trunc() == b.trunc() => {
+                Number::Interval(add(a, -a.trunc(), false), add(b, -b.trunc(), true))
+            }
+            "fract" if a >= 0.0 => Number::Interval(0.0, 1.0),
+            "fract" => Number::Interval(-1.0, 1.0),
+            "abs" if a >= 0.0 => Number::Interval(a, b),
+            "abs" if b <= 0.0 => Number::Interval(-b, -a),
+            "abs" => Number::Interval(0.0, b.max(-a)),
+
+            "cosh" if a >= 0.0 => increasing(a, b, f64::cosh, all, LIBRARY_ULPS),
+            "cosh" if b <= 0.0 => increasing(-b, -a, f64::cosh, all, LIBRARY_ULPS),
+            "cosh" => outward(1.0, a.cosh().max(b.cosh()), LIBRARY_ULPS),
+
+            // extrema of `sin` at π/2 + 2 k π and -π/2 + 2 k π, of `cos` at 2 k π and π + 2 k π
+            "sin" => periodic(a, b, f64::sin, FRAC_PI_2),
+            "cos" => periodic(a, b, f64::cos, 0.0),
+            "tan" if passes(a, b, FRAC_PI_2, PI) => Number::Interval(all.0, all.1),
+            "tan" => increasing(a, b, f64::tan, all, LIBRARY_ULPS),
+
+            "gamma" | "fact" if a > 0.0 || call == "fact" && a > -1.0 => {
+                let shift = if call == "fact" { 1.0 } else { 0.0 };
+                let (a, b) = (down(a + shift, 1), up(b + shift, 1));
+
+                if a >= GAMMA_MIN_AT {
+                    increasing(a, b, gamma, all, APPROXIMATION_ULPS)
+                } else if b <= GAMMA_MIN_AT {
+                    increasing(-b, -a, |x| gamma(-x), all, APPROXIMATION_ULPS)
+                } else {
+                    outward(GAMMA_MIN, gamma(a).max(gamma(b)), APPROXIMATION_ULPS)
+                }
+            }
+
+            _ => Number::Interval(all.0, all.1),
+        }
+    }
+
+    /// Enclosure of a built-in function of two numbers, of which one is an interval.
+    pub(crate) fn enclose_two(self, call: &str, rhs: Number) -> Number {
+        match call {
+            "log" => self.enclose_one("ln").interval_div(rhs.enclose_one("ln")),
+            "angle" => outward(-PI, PI, 1),
+            _ => Number::Interval(f64::NEG_INFINITY, f64::INFINITY),
+        }
+    }
+}
+
+/// Interval of the bounds, each moved outward by some units in the last place.
+fn outward(lower: f64, upper: f64, ulps: u32) -> Number {
+    Number::Interval(down(lower, ulps), up(upper, ulps))
+}
+
+fn down(x: f64, ulps: u32) -> f64 {
+    (0..ulps).fold(x, |x, _| x.next_down())
+}
+
+fn up(x: f64, ulps: u32) -> f64 {
+    (0..ulps).fold(x, |x, _| x.next_up())
+}
+
+/// Image of an increasing function, on the part of the interval in its domain.
+fn increasing(a: f64, b: f64, call: impl Fn(f64) -> f64, domain: (f64, f64), ulps: u32) -> Number {
+    let (a, b) = (a.max(domain.0), b.min(domain.1));
+
+    if a > b || a.is_nan() || b.is_nan() {
+        return Number::Interval(f64::NAN, f64::NAN);
+    }
+
+    outward(call(a), call(b), ulps)
+}
+
+/// Image of `sin` or `cos`, with the maximum at `phase + 2 k π` and the minimum half a period later.
+fn periodic(a: f64, b: f64, call: fn(f64) -> f64, phase: f64) -> Number {
+    if (b - a).is_nan() || b - a >= TAU {
+        return Number::Interval(-1.0, 1.0);
+    }
+
+    let upper = if passes(a, b, phase, TAU) {
+        1.0
+    } else {
+        up(call(a).max(call(b)), LIBRARY_ULPS).min(1.0)
+    };
+    let lower = if passes(a, b, phase + PI, TAU) {
+        -1.0
+    } else {
+        down(call(a).min(call(b)), LIBRARY_ULPS).max(-1.0)
+    };
+
+    Number::Interval(lower, upper)
+}
+
+/// Whether `phase + k period` may be in between `a` and `b`, where close calls count as inside.
+fn passes(a: f64, b: f64, phase: f64, period: f64) -> bool {
+    let slack = 4.0 * f64::EPSILON * a.abs().max(b.abs()).max(1.0);
+    let k = ((a - slack - phase) / period).ceil();
+
+    phase + k * period <= b + slack
+}
+
+/// Sum rounded down or up, by the exact error of the rounded sum.
+fn add(x: f64, y: f64, up: bool) -> f64 {
+    let sum = x + y;
+    if !sum.is_finite() {
+        return sum;
+    }
+
+    let virtual_y = sum - x;
+    let error = (x - (sum - virtual_y)) + (y - virtual_y);
+    rounded(sum, error, up)
+}
+
+/// Product rounded down or up, where `0 inf` is 0 as a bound.
+fn mul(x: f64, y: f64, up: bool) -> f64 {
+    if x == 0.0 || y == 0.0 {
+        return 0.0;
+    }
+
+    let product = x * y;
+    if !product.is_finite() {
+        return product;
+    }
+
+    rounded(product, x.mul_add(y, -product), up)
+}
+
+/// Quotient rounded down or up, by the exact remainder `x - q y`.
+fn div(x: f64, y: f64, up: bool) -> f64 {
+    let quotient = x / y;
+    if !quotient.is_finite() || quotient == 0.0 && x != 0.0 {
+        return if up {
+            quotient.next_up()
+        } else {
+            quotient.next_down()
+        };
+    }
+
+    rounded(quotient, (-quotient).mul_add(y, x) * y.signum(), up)
+}
+
+/// Square root rounded down or up, by the exact remainder `x - r^2`.
+fn sqrt(x: f64, up: bool) -> f64 {
+    let root = x.sqrt();
+    if !root.is_finite() {
+        return root;
+    }
+
+    rounded(root, (-root).mul_add(root, x), up)
+}
+
+/// Non-negative power `x^n` rounded down or up, by squaring.
+fn power(x: f64, n: i128, up: bool) -> f64 {
+    let (mut base, mut n, mut power) = (x, n, 1.0);
+
+    while n > 0 {
+        if n % 2 == 1 {
+            power = mul(power, base, up);
+        }
+        base = mul(base, base, up);
+        n /= 2;
+    }
+
+    power
+}
+
+/// Move a rounded value to the next float, if the exact value is on that side of it.
+fn rounded(value: f64, error: f64, up: bool) -> f64 {
+    match (up, error) {
+        (true, error) if error > 0.0 => value.next_up(),
+        (false, error) if error < 0.0 => value.next_down(),
+        _ => value,
+    }
+}
+
+fn min(values: &[f64]) -> f64 {
+    values.iter().copied().fold(f64::INFINITY, f64::min)
+}
+
+fn max(values: &[f64]) -> f64 {
+    values.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+}
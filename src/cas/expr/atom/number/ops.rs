@@ -1,13 +1,38 @@
 use {
     super::{super::symbol::Symbol, Number},
-    std::ops,
+    std::{convert::TryFrom, ops},
 };
 
 impl ops::Add for Number {
     type Output = Number;
 
     fn add(self, rhs: Number) -> Self::Output {
-        Number(self.0 + rhs.0)
+        if self.is_interval() || rhs.is_interval() {
+            return self.interval_add(rhs);
+        }
+        if self.is_uncertain() || rhs.is_uncertain() {
+            return self.uncertain_add(rhs);
+        }
+        if self.is_dual() || rhs.is_dual() {
+            return self.dual_add(rhs);
+        }
+        if let Some(number) = self.fixed_add(&rhs) {
+            return number;
+        }
+
+        if let (&Number::Ratio(a, b), &Number::Ratio(c, d)) = (&self, &rhs) {
+            // a/b + c/d = (ad + cb) / bd
+            if let Some(number) = (|| {
+                Some(Number::ratio(
+                    a.checked_mul(d)?.checked_add(c.checked_mul(b)?)?,
+                    b.checked_mul(d)?,
+                ))
+            })() {
+                return number;
+            }
+        }
+
+        Number::Float(self.as_f64() + rhs.as_f64())
     }
 }
 
@@ -15,7 +40,12 @@ impl ops::Sub for Number {
     type Output = Number;
 
     fn sub(self, rhs: Number) -> Self::Output {
-        Number(self.0 - rhs.0)
+        // saturating integers can not subtract by adding the negation
+        if let Some(number) = self.fixed_sub(&rhs) {
+            return number;
+        }
+
+        self + -rhs
     }
 }
 
@@ -23,7 +53,26 @@ impl ops::Mul for Number {
     type Output = Number;
 
     fn mul(self, rhs: Number) -> Self::Output {
-        Number(self.0 * rhs.0)
+        if self.is_interval() || rhs.is_interval() {
+            return self.interval_mul(rhs);
+        }
+        if self.is_uncertain() || rhs.is_uncertain() {
+            return self.uncertain_mul(rhs);
+        }
+        if self.is_dual() || rhs.is_dual() {
+            return self.dual_mul(rhs);
+        }
+        if let Some(number) = self.fixed_mul(&rhs) {
+            return number;
+        }
+
+        if let (&Number::Ratio(a, b), &Number::Ratio(c, d)) = (&self, &rhs) {
+            if let (Some(num), Some(den)) = (a.checked_mul(c), b.checked_mul(d)) {
+                return Number::ratio(num, den);
+            }
+        }
+
+        Number::Float(self.as_f64() * rhs.as_f64())
     }
 }
 
@@ -31,7 +80,26 @@ impl ops::Div for Number {
     type Output = Number;
 
     fn div(self, rhs: Number) -> Self::Output {
-        Number(self.0 / rhs.0)
+        if self.is_interval() || rhs.is_interval() {
+            return self.interval_div(rhs);
+        }
+        if self.is_uncertain() || rhs.is_uncertain() {
+            return self.uncertain_div(rhs);
+        }
+        if self.is_dual() || rhs.is_dual() {
+            return self.dual_div(rhs);
+        }
+        if let Some(number) = self.fixed_div(&rhs) {
+            return number;
+        }
+
+        if let (&Number::Ratio(a, b), &Number::Ratio(c, d)) = (&self, &rhs) {
+            if let (Some(num), Some(den)) = (a.checked_mul(d), b.checked_mul(c)) {
+                return Number::ratio(num, den);
+            }
+        }
+
+        Number::Float(self.as_f64() / rhs.as_f64())
     }
 }
 
@@ -39,154 +107,345 @@ impl ops::Neg for Number {
     type Output = Number;
 
     fn neg(self) -> Self::Output {
-        Number(-self.0)
+        match self {
+            Number::Ratio(num, den) => match num.checked_neg() {
+                Some(num) => Number::Ratio(num, den),
+                None => Number::Float(-self.as_f64()),
+            },
+            Number::Float(float) => Number::Float(-float),
+            Number::Interval(lower, upper) => Number::Interval(-upper, -lower),
+            Number::Uncertain(value, _) => self.propagate(Number::Float(-value), -1.0),
+            Number::Fixed(n, width) => width.fit(-n),
+            Number::Dual(value, tangent) => Number::Dual(-value, -tangent),
+        }
     }
 }
 
 impl Number {
+    /// Apply a float function, but stay exact on the one well known value.
+    pub(super) fn special(self, at: i128, value: i128, call: fn(f64) -> f64) -> Number {
+        if self == Number::int(at) {
+            Number::int(value)
+        } else {
+            Number::Float(call(self.as_f64()))
+        }
+    }
+
     pub(crate) fn abs(self) -> Number {
-        Number(self.0.abs())
+        if self.is_interval() {
+            self.enclose_one("abs")
+        } else if let Number::Uncertain(value, _) = self {
+            self.propagate(Number::Float(value.abs()), value.signum())
+        } else if let Number::Dual(value, _) = self {
+            self.chain(Number::Float(value.abs()), value.signum())
+        } else if self.is_negative() {
+            -self
+        } else {
+            self
+        }
     }
 
     pub(crate) fn ceil(self) -> Number {
-        Number(self.0.ceil())
+        match self {
+            Number::Ratio(_, _) => -(-self).floor(),
+            Number::Float(float) => Number::Float(float.ceil()),
+            Number::Interval(_, _) => self.enclose_one("ceil"),
+            Number::Uncertain(value, _) => self.propagate(Number::Float(value.ceil()), 0.0),
+            Number::Fixed(_, _) => self,
+            Number::Dual(value, _) => self.chain(Number::Float(value.ceil()), 0.0),
+        }
     }
 
     pub(crate) fn floor(self) -> Number {
-        Number(self.0.floor())
+        match self {
+            Number::Ratio(num, den) => Number::int(num.div_euclid(den)),
+            Number::Float(float) => Number::Float(float.floor()),
+            Number::Interval(_, _) => self.enclose_one("floor"),
+            Number::Uncertain(value, _) => self.propagate(Number::Float(value.floor()), 0.0),
+            Number::Fixed(_, _) => self,
+            Number::Dual(value, _) => self.chain(Number::Float(value.floor()), 0.0),
+        }
     }
 
     pub(crate) fn round(self) -> Number {
-        Number(self.0.round())
+        match self {
+            // half away from zero, like f64::round
+            Number::Ratio(_, _) if self.is_negative() => -(-self).round(),
+            Number::Ratio(num, den) => match num.checked_mul(2).and_then(|n| n.checked_add(den)) {
+                Some(twice) => Number::int(twice / (2 * den)),
+                None => Number::Float(self.as_f64().round()),
+            },
+            Number::Float(float) => Number::Float(float.round()),
+            Number::Interval(_, _) => self.enclose_one("round"),
+            Number::Uncertain(value, _) => self.propagate(Number::Float(value.round()), 0.0),
+            Number::Fixed(_, _) => self,
+            Number::Dual(value, _) => self.chain(Number::Float(value.round()), 0.0),
+        }
     }
 
     pub(crate) fn trunc(self) -> Number {
-        Number(self.0.trunc())
+        match self {
+            Number::Ratio(num, den) => Number::int(num / den),
+            Number::Float(float) => Number::Float(float.trunc()),
+            Number::Interval(_, _) => self.enclose_one("trunc"),
+            Number::Uncertain(value, _) => self.propagate(Number::Float(value.trunc()), 0.0),
+            Number::Fixed(_, _) => self,
+            Number::Dual(value, _) => self.chain(Number::Float(value.trunc()), 0.0),
+        }
     }
 
     pub(crate) fn fract(self) -> Number {
-        Number(self.0.fract())
+        if self.is_interval() {
+            return self.enclose_one("fract");
+        }
+
+        self.clone() - self.trunc()
     }
 
     pub(crate) fn signum(self) -> Number {
-        Number(self.0.signum())
+        match self {
+            Number::Ratio(num, _) => Number::int(num.signum()),
+            Number::Float(float) => Number::Float(float.signum()),
+            Number::Interval(_, _) => self.enclose_one("signum"),
+            Number::Uncertain(value, _) => self.propagate(Number::Float(value.signum()), 0.0),
+            Number::Fixed(n, width) => width.fit(n.signum()),
+            Number::Dual(value, _) => self.chain(Number::Float(value.signum()), 0.0),
+        }
     }
 
     pub(crate) fn exp(self) -> Number {
-        Number(self.0.exp())
-    }
-
-    pub(crate) fn fact(mut self) -> Number {
-        if self.0 <= 0.0 || self.0 == 1.0 {
-            return Number(1.0);
-        }
-
-        self * (self - Number(1.0)).fact()
+        self.special(0, 1, f64::exp)
     }
 
     pub(crate) fn ln(self) -> Number {
-        Number(self.0.ln())
+        self.special(1, 0, f64::ln)
     }
 
     pub(crate) fn lg(self) -> Number {
-        Number(self.0.log10())
+        match self.clone().log(Number::int(10)) {
+            exact if exact.is_exact() => exact,
+            _ => Number::Float(self.as_f64().log10()),
+        }
     }
 
     pub(crate) fn sqrt(self) -> Number {
-        Number(self.0.sqrt())
+        self.pow(Number::ratio(1, 2))
     }
 
     pub(crate) fn cbrt(self) -> Number {
-        Number(self.0.cbrt())
+        match self.clone().pow(Number::ratio(1, 3)) {
+            root if root.is_exact() => root,
+            _ => Number::Float(self.as_f64().cbrt()),
+        }
     }
 
     pub(crate) fn sin(self) -> Number {
-        Number(self.0.sin())
+        self.special(0, 0, f64::sin)
     }
 
     pub(crate) fn asin(self) -> Number {
-        Number(self.0.asin())
+        self.special(0, 0, f64::asin)
     }
 
     pub(crate) fn sinh(self) -> Number {
-        Number(self.0.sinh())
+        self.special(0, 0, f64::sinh)
     }
 
     pub(crate) fn asinh(self) -> Number {
-        Number(self.0.asinh())
+        self.special(0, 0, f64::asinh)
     }
 
     pub(crate) fn cos(self) -> Number {
-        Number(self.0.cos())
+        self.special(0, 1, f64::cos)
     }
 
     pub(crate) fn acos(self) -> Number {
-        Number(self.0.acos())
+        self.special(1, 0, f64::acos)
     }
 
     pub(crate) fn cosh(self) -> Number {
-        Number(self.0.cosh())
+        self.special(0, 1, f64::cosh)
     }
 
     pub(crate) fn acosh(self) -> Number {
-        Number(self.0.acosh())
+        self.special(1, 0, f64::acosh)
     }
 
     pub(crate) fn tan(self) -> Number {
-        Number(self.0.tan())
+        self.special(0, 0, f64::tan)
     }
 
     pub(crate) fn atan(self) -> Number {
-        Number(self.0.atan())
+        self.special(0, 0, f64::atan)
     }
 
     pub(crate) fn tanh(self) -> Number {
-        Number(self.0.tanh())
+        self.special(0, 0, f64::tanh)
     }
 
     pub(crate) fn atanh(self) -> Number {
-        Number(self.0.atanh())
+        self.special(0, 0, f64::atanh)
     }
 
     // 2 args
 
     pub(crate) fn modulus(self, rhs: Number) -> Number {
-        Number(self.0 % rhs.0)
+        if self.is_interval() || rhs.is_interval() {
+            return self.interval_modulus(rhs);
+        }
+        if self.is_uncertain() || rhs.is_uncertain() {
+            return self.uncertain_modulus(rhs);
+        }
+        if self.is_dual() || rhs.is_dual() {
+            return self.dual_modulus(rhs);
+        }
+        if let Some(number) = self.fixed_modulus(&rhs) {
+            return number;
+        }
+        if rhs.is_zero() {
+            return Number::Float(f64::NAN);
+        }
+
+        // same sign as the divisor, so `-7 % 3 = 2`
+        if let (Some(a), Some(m)) = (self.as_int(), rhs.as_int()) {
+            // only `i128::MIN % -1` overflows, which is 0
+            let rem = a.checked_rem_euclid(m).unwrap_or(0);
+            return Number::int(if m < 0 && rem != 0 { rem + m } else { rem });
+        }
+
+        match (&self, &rhs) {
+            (Number::Float(a), _) | (_, Number::Float(a)) if a.is_nan() => Number::Float(f64::NAN),
+            (Number::Ratio(_, _), Number::Ratio(_, _)) => {
+                self.clone() - rhs.clone() * (self / rhs).floor()
+            }
+            _ => {
+                let (a, m) = (self.as_f64(), rhs.as_f64());
+                Number::Float(a - m * (a / m).floor())
+            }
+        }
     }
 
     pub(crate) fn pow(self, rhs: Number) -> Number {
-        Number(self.0.powf(rhs.0))
+        if self.is_interval() || rhs.is_interval() {
+            return self.interval_pow(rhs);
+        }
+        if self.is_uncertain() || rhs.is_uncertain() {
+            return self.uncertain_pow(rhs);
+        }
+        if self.is_dual() || rhs.is_dual() {
+            return self.dual_pow(rhs);
+        }
+        if let Some(number) = self.fixed_pow(&rhs) {
+            return number;
+        }
+        if let (&Number::Ratio(a, b), &Number::Ratio(p, q)) = (&self, &rhs) {
+            if let Some(number) = exact_pow(a, b, p, q) {
+                return number;
+            }
+        }
+
+        Number::Float(self.as_f64().powf(rhs.as_f64()))
     }
 
     pub(crate) fn equal(self, rhs: Number) -> Symbol {
-        if self.0 == rhs.0 {
-            Symbol(format!("true"))
+        let equal = if self.is_exact() && rhs.is_exact() {
+            self == rhs
+        } else if self.is_interval() || rhs.is_interval() {
+            self.bounds() == rhs.bounds()
         } else {
-            Symbol(format!("false"))
-        }
-    }
+            self.as_f64() == rhs.as_f64()
+        };
 
-    pub(crate) fn root(self, rhs: Number) -> Number {
-        Number(self.0.powf(1.0 / rhs.0))
+        if equal {
+            Symbol("true".to_string())
+        } else {
+            Symbol("false".to_string())
+        }
     }
 
     pub(crate) fn log(self, rhs: Number) -> Number {
-        Number(self.0.log(rhs.0))
+        if self.is_interval() || rhs.is_interval() {
+            return self.enclose_two("log", rhs);
+        }
+        if self.is_uncertain() || rhs.is_uncertain() {
+            return self.propagate_two(rhs, Number::log);
+        }
+        if self.is_dual() || rhs.is_dual() {
+            return self.dual_log(rhs);
+        }
+
+        let value = self.as_f64().log(rhs.as_f64());
+
+        // exact for integer powers of the base
+        if self.is_exact() && rhs.is_exact() && value.is_finite() {
+            let exp = Number::int(value.round() as i128);
+            if rhs.pow(exp.clone()) == self {
+                return exp;
+            }
+        }
+
+        Number::Float(value)
     }
 
     pub(crate) fn angle(self, rhs: Number) -> Number {
-        Number(self.0.atan2(rhs.0))
+        if self.is_uncertain() || rhs.is_uncertain() {
+            return self.propagate_two(rhs, Number::angle);
+        }
+        if self.is_dual() || rhs.is_dual() {
+            return self.dual_angle(rhs);
+        }
+
+        Number::Float(self.as_f64().atan2(rhs.as_f64()))
     }
 
     // any number of args
 
     pub(crate) fn sum(args: Vec<Number>) -> Number {
-        let mut sum = 0.0;
+        let mut sum = Number::int(0);
 
         for num in args {
-            sum = sum + num.0;
+            sum = sum + num;
         }
 
-        Number(sum)
+        sum
     }
 }
+
+/// `(a/b)^(p/q)`, if the result is a fraction again.
+fn exact_pow(a: i128, b: i128, p: i128, q: i128) -> Option<Number> {
+    // even roots of negative numbers are not real
+    if a < 0 && q % 2 == 0 {
+        return None;
+    }
+
+    let (a, b) = (iroot(a, q)?, iroot(b, q)?);
+    let exp = u32::try_from(p.abs()).ok()?;
+    let (num, den) = (a.checked_pow(exp)?, b.checked_pow(exp)?);
+
+    Some(if p < 0 {
+        if num == 0 {
+            return None;
+        }
+        Number::ratio(den, num)
+    } else {
+        Number::Ratio(num, den)
+    })
+}
+
+/// Exact integer `n`-th root.
+fn iroot(a: i128, n: i128) -> Option<i128> {
+    if n == 1 {
+        return Some(a);
+    }
+
+    let exp = u32::try_from(n).ok()?;
+    let guess = (a.abs() as f64).powf(1.0 / n as f64).round() as i128;
+
+    for root in guess.saturating_sub(1)..=guess.saturating_add(1) {
+        if root >= 0 && root.checked_pow(exp) == Some(a.abs()) {
+            return Some(if a < 0 { -root } else { root });
+        }
+    }
+
+    None
+}
@@ -0,0 +1,710 @@
+use {
+    super::{
+        combinatorics::{gamma, ln_gamma},
+        Number,
+    },
+    std::f64::consts::{LN_2, PI},
+};
+
+/// Terms of the alternating series of Borwein for `ζ`, good to 15 digits.
+const ZETA_TERMS: usize = 30;
+
+/// Depth of the continued fraction of `erfc`.
+const ERFC_DEPTH: usize = 250;
+
+/// Below this, Airy functions of negative arguments are asymptotic.
+const AIRY_ASYMPTOTIC: f64 = -8.0;
+
+/// Euler-Mascheroni constant.
+const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+
+/// `Ai(0)` and `-Ai'(0)`.
+const AIRY_AI: f64 = 0.355_028_053_887_817_2;
+const AIRY_AI_PRIME: f64 = 0.258_819_403_792_806_8;
+
+/// Special functions of real numbers, approximated to `f64` precision.
+/// They stay exact only on their well known rational values.
+impl Number {
+    pub(crate) fn erf(self) -> Number {
+        self.special(0, 0, erf)
+    }
+
+    pub(crate) fn erfc(self) -> Number {
+        self.special(0, 1, erfc)
+    }
+
+    pub(crate) fn digamma(self) -> Number {
+        match self.as_int() {
+            Some(n) if n <= 0 => Number::ratio(1, 0),
+            _ => Number::Float(digamma(self.as_f64())),
+        }
+    }
+
+    /// Riemann zeta function, exact at non-positive integers.
+    pub(crate) fn zeta(self) -> Number {
+        match self.as_int() {
+            Some(1) => Number::ratio(1, 0),
+            // ζ(-n) = -B(n + 1) / (n + 1)
+            Some(n) if n <= 0 => match Number::bernoulli(1 - n) {
+                bernoulli if bernoulli.is_exact() => -bernoulli / Number::int(1 - n),
+                _ => Number::Float(zeta(n as f64)),
+            },
+            _ => Number::Float(zeta(self.as_f64())),
+        }
+    }
+
+    /// Bernoulli numbers with `B(1) = 1/2`, exact while they fit.
+    pub(crate) fn bernoulli(n: i128) -> Number {
+        // Akiyama-Tanigawa
+        let mut row = Vec::with_capacity(n as usize + 1);
+        for m in 0..=n {
+            row.push(Number::ratio(1, m + 1));
+            for j in (1..=m as usize).rev() {
+                row[j - 1] = Number::int(j as i128) * (row[j - 1].clone() - row[j].clone());
+            }
+        }
+
+        row.swap_remove(0)
+    }
+
+    /// `polylog(s, z)` of real `z`, which is complex above 1.
+    pub(crate) fn polylog(self, rhs: Number) -> Number {
+        if rhs.is_zero() {
+            return Number::int(0);
+        }
+
+        Number::Float(polylog(self.as_f64(), rhs.as_f64()))
+    }
+
+    /// Principal branch of the Lambert W function, the inverse of `w exp w`.
+    pub(crate) fn lambertw(self) -> Number {
+        self.special(0, 0, lambert_w)
+    }
+
+    pub(crate) fn besselj(self, rhs: Number) -> Number {
+        match (self.as_int(), rhs.is_zero()) {
+            (Some(0), true) => Number::int(1),
+            (Some(_), true) => Number::int(0),
+            _ => Number::Float(bessel_j(self.as_f64(), rhs.as_f64())),
+        }
+    }
+
+    pub(crate) fn bessely(self, rhs: Number) -> Number {
+        Number::Float(bessel_y(self.as_f64(), rhs.as_f64()))
+    }
+
+    pub(crate) fn airyai(self) -> Number {
+        Number::Float(airy(self.as_f64()).0)
+    }
+
+    pub(crate) fn airyaiprime(self) -> Number {
+        Number::Float(airy(self.as_f64()).1)
+    }
+
+    pub(crate) fn airybi(self) -> Number {
+        Number::Float(airy(self.as_f64()).2)
+    }
+
+    pub(crate) fn airybiprime(self) -> Number {
+        Number::Float(airy(self.as_f64()).3)
+    }
+
+    /// Complete elliptic integral of the first kind of the parameter `m = k^2`.
+    pub(crate) fn ellipk(self) -> Number {
+        Number::Float(ellip_f(PI / 2.0, self.as_f64()))
+    }
+
+    /// Complete elliptic integral of the second kind of the parameter `m = k^2`.
+    pub(crate) fn ellipe(self) -> Number {
+        self.special(1, 1, |m| ellip_e(PI / 2.0, m))
+    }
+
+    /// Incomplete elliptic integral of the first kind `F(φ, m)`.
+    pub(crate) fn ellipf(self, rhs: Number) -> Number {
+        if self.is_zero() {
+            return Number::int(0);
+        }
+
+        Number::Float(ellip_f(self.as_f64(), rhs.as_f64()))
+    }
+
+    /// Incomplete elliptic integral of the second kind `E(φ, m)`.
+    pub(crate) fn ellipe_incomplete(self, rhs: Number) -> Number {
+        if self.is_zero() {
+            return Number::int(0);
+        }
+
+        Number::Float(ellip_e(self.as_f64(), rhs.as_f64()))
+    }
+}
+
+pub(super) fn erf(x: f64) -> f64 {
+    if x < 0.0 {
+        return -erf(-x);
+    }
+    if x >= 2.5 {
+        return 1.0 - erfc(x);
+    }
+
+    // erf x = 2 / sqrt π exp(-x^2) sum 2^k x^(2k + 1) / (1 3 ... (2k + 1)), without cancellation
+    let (mut sum, mut term) = (x, x);
+    let mut k = 0.0;
+    while term > f64::EPSILON * sum / 8.0 {
+        term *= 2.0 * x * x / (2.0 * k + 3.0);
+        sum += term;
+        k += 1.0;
+    }
+
+    2.0 / PI.sqrt() * (-x * x).exp() * sum
+}
+
+fn erfc(x: f64) -> f64 {
+    if x < 0.5 {
+        return 1.0 - erf(x);
+    }
+
+    // continued fraction x + (1/2) / (x + 1 / (x + (3/2) / (x + ...)))
+    let fraction = (1..=ERFC_DEPTH)
+        .rev()
+        .fold(x, |fraction, k| x + k as f64 / 2.0 / fraction);
+
+    (-x * x).exp() / (PI.sqrt() * fraction)
+}
+
+fn digamma(x: f64) -> f64 {
+    if x < 0.5 {
+        return digamma(1.0 - x) - PI / (PI * x).tan();
+    }
+
+    let (mut x, mut value) = (x, 0.0);
+    while x < 10.0 {
+        value -= 1.0 / x;
+        x += 1.0;
+    }
+
+    // ln x - 1 / 2x - sum B(2k) / (2k x^2k)
+    let coefs = [
+        1.0 / 12.0,
+        -1.0 / 120.0,
+        1.0 / 252.0,
+        -1.0 / 240.0,
+        1.0 / 132.0,
+        -691.0 / 32760.0,
+        1.0 / 12.0,
+    ];
+    let square = 1.0 / (x * x);
+    let series = coefs
+        .iter()
+        .rev()
+        .fold(0.0, |series, coef| (series + coef) * square);
+
+    value + x.ln() - 0.5 / x - series
+}
+
+fn zeta(s: f64) -> f64 {
+    if s == 1.0 {
+        return f64::INFINITY;
+    }
+    if s == 0.0 {
+        return -0.5;
+    }
+    // trivial zeros
+    if s < 0.0 && s % 2.0 == 0.0 {
+        return 0.0;
+    }
+    if s < 0.5 {
+        // ζ(s) = 2^s π^(s - 1) sin(π s / 2) Γ(1 - s) ζ(1 - s)
+        return 2_f64.powf(s)
+            * PI.powf(s - 1.0)
+            * (PI * s / 2.0).sin()
+            * gamma(1.0 - s)
+            * zeta(1.0 - s);
+    }
+
+    // d(k) = n sum (n + i - 1)! 4^i / ((n - i)! (2i)!)
+    let n = ZETA_TERMS;
+    let mut weights = Vec::with_capacity(n + 1);
+    let (mut term, mut sum) = (1.0 / n as f64, 0.0);
+    for i in 0..=n {
+        if i > 0 {
+            term *= (n + i - 1) as f64 * (n - i + 1) as f64 * 4.0 / ((2 * i - 1) * 2 * i) as f64;
+        }
+        sum += term;
+        weights.push(n as f64 * sum);
+    }
+
+    let last = weights[n];
+    let eta: f64 = (0..n)
+        .map(|k| {
+            let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+            sign * (weights[k] - last) / ((k + 1) as f64).powf(s)
+        })
+        .sum();
+
+    // η(s) = (1 - 2^(1 - s)) ζ(s)
+    eta / (last * ((1.0 - s) * LN_2).exp_m1())
+}
+
+fn polylog(s: f64, z: f64) -> f64 {
+    if s <= 0.0 && s.fract() == 0.0 {
+        return negative_polylog(-s as u32, z);
+    }
+    if z == 1.0 {
+        return zeta(s);
+    }
+    if z > 1.0 {
+        return f64::NAN;
+    }
+
+    if z.abs() <= 0.5 {
+        let (mut sum, mut power) = (0.0, z);
+        for k in 1.. {
+            let term = power / (k as f64).powf(s);
+            sum += term;
+            if term.abs() <= f64::EPSILON * sum.abs() / 8.0 {
+                break;
+            }
+            power *= z;
+        }
+        return sum;
+    }
+    if z > 0.5 {
+        return polylog_near_one(s, z.ln());
+    }
+    if z >= -1.0 {
+        // Li(s, z) + Li(s, -z) = 2^(1 - s) Li(s, z^2)
+        return 2_f64.powf(1.0 - s) * polylog(s, z * z) - polylog(s, -z);
+    }
+
+    // inversion, only real for integer orders
+    if s.fract() != 0.0 {
+        return f64::NAN;
+    }
+    let (n, ln) = (s as i32, (-z).ln());
+    let mut value = -ln.powi(n) / gamma(s + 1.0);
+    for r in 1..=n / 2 {
+        let eta = -((1 - 2 * r) as f64 * LN_2).exp_m1() * zeta(2.0 * r as f64);
+        value -= 2.0 * ln.powi(n - 2 * r) / gamma((n - 2 * r) as f64 + 1.0) * eta;
+    }
+
+    value - if n % 2 == 0 { 1.0 } else { -1.0 } * polylog(s, 1.0 / z)
+}
+
+/// `Li(-n, z) = sum k! S(n + 1, k + 1) (z / (1 - z))^(k + 1)`, a rational function.
+fn negative_polylog(n: u32, z: f64) -> f64 {
+    let ratio = z / (1.0 - z);
+
+    (0..=n as i128)
+        .map(|k| {
+            Number::int(k).fact().as_f64()
+                * Number::int(n as i128 + 1)
+                    .stirling2(Number::int(k + 1))
+                    .as_f64()
+                * ratio.powi(k as i32 + 1)
+        })
+        .sum()
+}
+
+/// `Li(s, exp μ) = Γ(1 - s) (-μ)^(s - 1) + sum ζ(s - k) μ^k / k!` for small μ.
+fn polylog_near_one(s: f64, mu: f64) -> f64 {
+    let integer = s.fract() == 0.0;
+    let (mut sum, mut power) = (0.0, 1.0);
+
+    for k in 0..200 {
+        let term = if integer && k as f64 == s - 1.0 {
+            // the pole of ζ and Γ cancel to a harmonic number
+            let harmonic: f64 = (1..=k).map(|j| 1.0 / j as f64).sum();
+            power * (harmonic - (-mu).ln())
+        } else {
+            zeta(s - k as f64) * power
+        };
+        sum += term;
+
+        if k as f64 > s && term.abs() <= f64::EPSILON * sum.abs() / 8.0 {
+            break;
+        }
+        power *= mu / (k + 1) as f64;
+    }
+
+    if integer {
+        sum
+    } else {
+        sum + gamma(1.0 - s) * (-mu).powf(s - 1.0)
+    }
+}
+
+fn lambert_w(x: f64) -> f64 {
+    let branch = -(-1_f64).exp();
+    if x < branch || x.is_nan() {
+        return f64::NAN;
+    }
+    if x == branch {
+        return -1.0;
+    }
+    if x.is_infinite() {
+        return x;
+    }
+
+    let mut w = if x < -0.25 {
+        // expansion around the branch point
+        let p = (2.0 * (std::f64::consts::E * x + 1.0)).sqrt();
+        -1.0 + p - p * p / 3.0 + 11.0 / 72.0 * p * p * p
+    } else if x < 3.0 {
+        x.ln_1p()
+    } else {
+        x.ln() - x.ln().ln()
+    };
+
+    // Halley's iteration
+    for _ in 0..64 {
+        let exp = w.exp();
+        let f = w * exp - x;
+        let step = f / (exp * (w + 1.0) - (w + 2.0) * f / (2.0 * w + 2.0));
+        w -= step;
+        if step.abs() <= f64::EPSILON * (1.0 + w.abs()) {
+            break;
+        }
+    }
+
+    w
+}
+
+/// `J(f + k)` for `k = 0 .. n` and fractional order `0 <= f < 1`, by the backward recurrence
+/// of Miller, which is stable, normalized by `(x/2)^f = sum (f + 2k) Γ(f + k) / k! J(f + 2k)`.
+fn bessel_j_orders(f: f64, n: usize, x: f64) -> Vec<f64> {
+    let top = (n as f64).max(x);
+    let mut m = top as usize + 20 + (40.0 * top).sqrt() as usize;
+    m += m % 2;
+
+    let mut values = vec![0.0; m + 2];
+    values[m] = f64::MIN_POSITIVE;
+    for k in (1..=m).rev() {
+        values[k - 1] = 2.0 * (f + k as f64) / x * values[k] - values[k + 1];
+
+        if values[k - 1].abs() > 1e250 {
+            for value in &mut values[k - 1..] {
+                *value *= 1e-250;
+            }
+        }
+    }
+
+    let norm: f64 = (0..=m / 2)
+        .map(|k| {
+            let weight = if k == 0 {
+                gamma(f + 1.0)
+            } else {
+                (f + 2.0 * k as f64) * (ln_gamma(f + k as f64) - ln_gamma(k as f64 + 1.0)).exp()
+            };
+            weight * values[2 * k]
+        })
+        .sum();
+
+    let scale = (x / 2.0).powf(f) / norm;
+    values.truncate(n + 1);
+    values.iter().map(|value| value * scale).collect()
+}
+
+fn bessel_j(order: f64, x: f64) -> f64 {
+    let integer = order.fract() == 0.0;
+    // J(-n, x) = (-1)^n J(n, x) = J(n, -x)
+    let parity = |n: f64| if n % 2.0 == 0.0 { 1.0 } else { -1.0 };
+
+    if x == 0.0 {
+        return if order == 0.0 {
+            1.0
+        } else if order > 0.0 || integer {
+            0.0
+        } else {
+            f64::INFINITY
+        };
+    }
+    if integer && (order < 0.0 || x < 0.0) {
+        let sign = if order < 0.0 { parity(order) } else { 1.0 }
+            * if x < 0.0 { parity(order) } else { 1.0 };
+        return sign * bessel_j(order.abs(), x.abs());
+    }
+    if x < 0.0 {
+        return f64::NAN;
+    }
+
+    let (f, n) = (order - order.floor(), order.floor());
+    if n >= 0.0 {
+        return bessel_j_orders(f, n as usize, x)[n as usize];
+    }
+
+    // the recurrence downwards to negative orders is stable
+    let values = bessel_j_orders(f, 1, x);
+    let (mut current, mut next) = (values[0], values[1]);
+    let mut mu = f;
+    for _ in 0..(-n) as usize {
+        (current, next) = (2.0 * mu / x * current - next, current);
+        mu -= 1.0;
+    }
+
+    current
+}
+
+fn bessel_y(order: f64, x: f64) -> f64 {
+    if x < 0.0 {
+        return f64::NAN;
+    }
+    if x == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+
+    if order.fract() != 0.0 {
+        // Y(ν) = (J(ν) cos νπ - J(-ν)) / sin νπ
+        return (bessel_j(order, x) * (order * PI).cos() - bessel_j(-order, x))
+            / (order * PI).sin();
+    }
+    if order < 0.0 {
+        let sign = if order % 2.0 == 0.0 { 1.0 } else { -1.0 };
+        return sign * bessel_y(-order, x);
+    }
+
+    // Neumann series Y(0) = 2/π (ln(x/2) + γ) J(0) - 4/π sum (-1)^k J(2k) / k, and its derivative
+    let values = bessel_j_orders(0.0, ((x as usize + 20) * 2).max(40), x);
+    let ln = (x / 2.0).ln() + EULER_GAMMA;
+
+    let (mut sum, mut derived) = (0.0, 0.0);
+    for k in 1..values.len() / 2 {
+        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+        sum += sign * values[2 * k] / k as f64;
+        derived += sign * (values[2 * k - 1] - values[2 * k + 1]) / (2.0 * k as f64);
+    }
+
+    let mut y0 = 2.0 / PI * ln * values[0] - 4.0 / PI * sum;
+    let mut y1 = -(2.0 / PI * (values[0] / x - ln * values[1]) - 4.0 / PI * derived);
+    if order == 0.0 {
+        return y0;
+    }
+
+    // the recurrence upwards is stable for Y
+    for k in 1..order as usize {
+        (y0, y1) = (y1, 2.0 * k as f64 / x * y1 - y0);
+    }
+
+    y1
+}
+
+/// `(Ai, Ai', Bi, Bi')`.
+fn airy(x: f64) -> (f64, f64, f64, f64) {
+    if x < AIRY_ASYMPTOTIC {
+        return airy_asymptotic(-x);
+    }
+
+    // Maclaurin series Ai = c1 f - c2 g, Bi = sqrt 3 (c1 f + c2 g)
+    let cube = x * x * x;
+    let (mut f, mut df, mut g, mut dg) = (1.0, 0.0, x, 1.0);
+    let (mut f_term, mut g_term) = (1.0, x);
+    let mut k = 0.0;
+    loop {
+        f_term *= cube / ((3.0 * k + 2.0) * (3.0 * k + 3.0));
+        g_term *= cube / ((3.0 * k + 3.0) * (3.0 * k + 4.0));
+        f += f_term;
+        g += g_term;
+        if x != 0.0 {
+            df += f_term * (3.0 * k + 3.0) / x;
+            dg += g_term * (3.0 * k + 4.0) / x;
+        }
+        k += 1.0;
+
+        if f_term.abs() + g_term.abs() <= f64::EPSILON * (f.abs() + g.abs()) / 8.0 || k > 500.0 {
+            break;
+        }
+    }
+
+    let sqrt3 = 3_f64.sqrt();
+    let (bi, dbi) = (
+        sqrt3 * (AIRY_AI * f + AIRY_AI_PRIME * g),
+        sqrt3 * (AIRY_AI * df + AIRY_AI_PRIME * dg),
+    );
+
+    if x > 1.0 {
+        // the series cancels for the decaying Ai, so use Bessel K instead
+        let zeta = 2.0 / 3.0 * x.powf(1.5);
+        return (
+            (x / 3.0).sqrt() / PI * bessel_k(1.0 / 3.0, zeta),
+            -x / (PI * sqrt3) * bessel_k(2.0 / 3.0, zeta),
+            bi,
+            dbi,
+        );
+    }
+
+    (
+        AIRY_AI * f - AIRY_AI_PRIME * g,
+        AIRY_AI * df - AIRY_AI_PRIME * dg,
+        bi,
+        dbi,
+    )
+}
+
+/// Airy functions at `-z`, by their oscillating asymptotic expansions.
+fn airy_asymptotic(z: f64) -> (f64, f64, f64, f64) {
+    let zeta = 2.0 / 3.0 * z.powf(1.5);
+
+    // the even and odd part of the series, cut at its smallest term
+    let sums = |first_kind: bool| {
+        let (mut even, mut odd, mut coef, mut last) = (0.0, 0.0, 1.0, f64::INFINITY);
+        for k in 0..40 {
+            if k > 0 {
+                let k = k as f64;
+                coef *= (6.0 * k - 5.0) * (6.0 * k - 3.0) * (6.0 * k - 1.0)
+                    / ((2.0 * k - 1.0) * 216.0 * k);
+            }
+            let coef = if first_kind || k == 0 {
+                coef
+            } else {
+                -(6 * k + 1) as f64 / (6 * k - 1) as f64 * coef
+            };
+
+            let term = coef / zeta.powi(k);
+            if term.abs() > last {
+                break;
+            }
+            last = term.abs();
+
+            let sign = if (k / 2) % 2 == 0 { 1.0 } else { -1.0 };
+            if k % 2 == 0 {
+                even += sign * term;
+            } else {
+                odd += sign * term;
+            }
+        }
+        (even, odd)
+    };
+
+    let ((u_even, u_odd), (v_even, v_odd)) = (sums(true), sums(false));
+    let (cos, sin) = ((zeta - PI / 4.0).cos(), (zeta - PI / 4.0).sin());
+    let (a, b) = (1.0 / (PI.sqrt() * z.powf(0.25)), z.powf(0.25) / PI.sqrt());
+
+    (
+        a * (cos * u_even + sin * u_odd),
+        b * (sin * v_even - cos * v_odd),
+        a * (cos * u_odd - sin * u_even),
+        b * (cos * v_even + sin * v_odd),
+    )
+}
+
+/// `K(ν, z) = 1/2 ∫ exp(-z cosh t) cosh(ν t) dt` over all t, where the trapezoidal rule
+/// converges exponentially for this even and analytic integrand.
+fn bessel_k(order: f64, z: f64) -> f64 {
+    let step = 0.05;
+    let mut sum = (-z).exp();
+
+    for k in 1.. {
+        let t = k as f64 * step;
+        let term = (-z * t.cosh()).exp() * (order * t).cosh();
+        sum += 2.0 * term;
+        if term <= f64::EPSILON * sum / 8.0 {
+            break;
+        }
+    }
+
+    sum * step / 2.0
+}
+
+/// `F(φ, m) = sin φ RF(cos^2 φ, 1 - m sin^2 φ, 1)`, quasi periodic in π.
+fn ellip_f(amplitude: f64, m: f64) -> f64 {
+    let (turns, phi) = reduce_amplitude(amplitude);
+    let (sin, cos) = phi.sin_cos();
+    let y = 1.0 - m * sin * sin;
+
+    // like `K(1)` it diverges logarithmically, which the duplication would only approach
+    if y.abs() < f64::EPSILON && cos.abs() < f64::EPSILON {
+        return amplitude.signum() * f64::INFINITY;
+    }
+
+    let value = sin * carlson_rf(cos * cos, y, 1.0);
+    if turns == 0.0 {
+        value
+    } else {
+        value + 2.0 * turns * ellip_f(PI / 2.0, m)
+    }
+}
+
+/// `E(φ, m) = F(φ, m) - m/3 sin^3 φ RD(cos^2 φ, 1 - m sin^2 φ, 1)`.
+fn ellip_e(phi: f64, m: f64) -> f64 {
+    let (turns, phi) = reduce_amplitude(phi);
+    let (sin, cos) = phi.sin_cos();
+    let (x, y) = (cos * cos, 1.0 - m * sin * sin);
+
+    let value = sin * carlson_rf(x, y, 1.0) - m / 3.0 * sin * sin * sin * carlson_rd(x, y, 1.0);
+    if turns == 0.0 {
+        value
+    } else {
+        value + 2.0 * turns * ellip_e(PI / 2.0, m)
+    }
+}
+
+/// `φ = n π + r` with `-π/2 < r <= π/2`.
+fn reduce_amplitude(phi: f64) -> (f64, f64) {
+    let turns = ((phi - PI / 2.0) / PI).ceil();
+    (turns, phi - turns * PI)
+}
+
+/// Carlson's symmetric integral of the first kind, by duplication.
+fn carlson_rf(mut x: f64, mut y: f64, mut z: f64) -> f64 {
+    if x < 0.0 || y < 0.0 || z < 0.0 {
+        return f64::NAN;
+    }
+
+    for _ in 0..100 {
+        let mean = (x + y + z) / 3.0;
+        let (dx, dy, dz) = (1.0 - x / mean, 1.0 - y / mean, 1.0 - z / mean);
+
+        if dx.abs().max(dy.abs()).max(dz.abs()) < 1e-4 {
+            let (e2, e3) = (dx * dy - dz * dz, dx * dy * dz);
+            return (1.0 - e2 / 10.0 + e3 / 14.0 + e2 * e2 / 24.0 - 3.0 * e2 * e3 / 44.0)
+                / mean.sqrt();
+        }
+
+        let lambda = x.sqrt() * y.sqrt() + y.sqrt() * z.sqrt() + z.sqrt() * x.sqrt();
+        x = (x + lambda) / 4.0;
+        y = (y + lambda) / 4.0;
+        z = (z + lambda) / 4.0;
+    }
+
+    // a zero argument and a pole, like K(1)
+    f64::INFINITY
+}
+
+/// Carlson's symmetric integral of the second kind, by duplication.
+fn carlson_rd(mut x: f64, mut y: f64, mut z: f64) -> f64 {
+    if x < 0.0 || y < 0.0 || z <= 0.0 {
+        return f64::NAN;
+    }
+
+    let (mut sum, mut factor) = (0.0, 1.0);
+    for _ in 0..100 {
+        let mean = (x + y + 3.0 * z) / 5.0;
+        let (dx, dy) = ((mean - x) / mean, (mean - y) / mean);
+        let dz = -(dx + dy) / 3.0;
+
+        if dx.abs().max(dy.abs()).max(dz.abs()) < 1e-4 {
+            let (xy, zz) = (dx * dy, dz * dz);
+            let (e2, e3, e4, e5) = (
+                xy - 6.0 * zz,
+                (3.0 * xy - 8.0 * zz) * dz,
+                3.0 * (xy - zz) * zz,
+                xy * zz * dz,
+            );
+            return 3.0 * sum
+                + factor
+                    * (1.0 - 3.0 * e2 / 14.0 + e3 / 6.0 + 9.0 * e2 * e2 / 88.0
+                        - 3.0 * e4 / 22.0
+                        - 9.0 * e2 * e3 / 52.0
+                        + 3.0 * e5 / 26.0)
+                    / (mean * mean.sqrt());
+        }
+
+        let lambda = x.sqrt() * y.sqrt() + y.sqrt() * z.sqrt() + z.sqrt() * x.sqrt();
+        sum += factor / (z.sqrt() * (z + lambda));
+        factor /= 4.0;
+        x = (x + lambda) / 4.0;
+        y = (y + lambda) / 4.0;
+        z = (z + lambda) / 4.0;
+    }
+
+    f64::INFINITY
+}
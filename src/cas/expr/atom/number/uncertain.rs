@@ -0,0 +1,110 @@
+use {
+    super::Number,
+    std::{
+        collections::BTreeMap,
+        rc::Rc,
+        sync::atomic::{AtomicUsize, Ordering},
+    },
+};
+
+/// Number of the next independent error.
+static NEXT_ERROR: AtomicUsize = AtomicUsize::new(0);
+
+/// Values with a standard uncertainty, propagated to first order.
+/// Every number knows which independent errors it depends on, so `x - x` is exact.
+impl Number {
+    /// A value with a new independent error of some standard deviation.
+    pub(crate) fn uncertain(value: f64, deviation: f64) -> Number {
+        let error = NEXT_ERROR.fetch_add(1, Ordering::Relaxed);
+        Number::Uncertain(value, Rc::new([(error, deviation.abs())]))
+    }
+
+    pub(crate) fn is_uncertain(&self) -> bool {
+        matches!(self, Number::Uncertain(_, _))
+    }
+
+    /// Standard deviation, which is 0 for all other numbers.
+    pub(crate) fn deviation(&self) -> f64 {
+        self.errors()
+            .iter()
+            .fold(0.0, |sum, (_, coef)| sum + coef * coef)
+            .sqrt()
+    }
+
+    fn errors(&self) -> &[(usize, f64)] {
+        match self {
+            Number::Uncertain(_, errors) => errors,
+            _ => &[],
+        }
+    }
+
+    /// A value, that changes with the arguments by their slopes.
+    pub(super) fn linear(value: f64, slopes: &[(f64, Number)]) -> Number {
+        let mut sum = BTreeMap::new();
+
+        // exact arguments have no errors, even where the slope is not finite
+        for (slope, number) in slopes.iter().filter(|(_, number)| number.is_uncertain()) {
+            for (error, coef) in number.errors() {
+                *sum.entry(*error).or_insert(0.0) += slope * coef;
+            }
+        }
+
+        Number::Uncertain(
+            value,
+            sum.into_iter().filter(|(_, coef)| *coef != 0.0).collect(),
+        )
+    }
+
+    /// Result of a function with a known slope at the value.
+    pub(crate) fn propagate(self, value: Number, slope: f64) -> Number {
+        Number::linear(value.as_f64(), &[(slope, self)])
+    }
+
+    pub(super) fn uncertain_add(self, rhs: Number) -> Number {
+        Number::linear(self.as_f64() + rhs.as_f64(), &[(1.0, self), (1.0, rhs)])
+    }
+
+    pub(super) fn uncertain_mul(self, rhs: Number) -> Number {
+        let (a, b) = (self.as_f64(), rhs.as_f64());
+        Number::linear(a * b, &[(b, self), (a, rhs)])
+    }
+
+    pub(super) fn uncertain_div(self, rhs: Number) -> Number {
+        let (a, b) = (self.as_f64(), rhs.as_f64());
+        Number::linear(a / b, &[(1.0 / b, self), (-a / (b * b), rhs)])
+    }
+
+    pub(super) fn uncertain_pow(self, rhs: Number) -> Number {
+        let (a, b) = (self.as_f64(), rhs.as_f64());
+        let value = a.powf(b);
+
+        Number::linear(value, &[(b * a.powf(b - 1.0), self), (value * a.ln(), rhs)])
+    }
+
+    pub(super) fn uncertain_modulus(self, rhs: Number) -> Number {
+        let (a, b) = (self.as_f64(), rhs.as_f64());
+        let floor = (a / b).floor();
+
+        Number::linear(a - b * floor, &[(1.0, self), (-floor, rhs)])
+    }
+
+    /// Function of two numbers, with slopes by central differences.
+    pub(crate) fn propagate_two(self, rhs: Number, call: fn(Number, Number) -> Number) -> Number {
+        let (a, b) = (self.as_f64(), rhs.as_f64());
+        let f = |a: f64, b: f64| call(Number::Float(a), Number::Float(b)).as_f64();
+        let (da, db) = (step(a), step(b));
+
+        Number::linear(
+            f(a, b),
+            &[
+                ((f(a + da, b) - f(a - da, b)) / (2.0 * da), self),
+                ((f(a, b + db) - f(a, b - db)) / (2.0 * db), rhs),
+            ],
+        )
+    }
+}
+
+/// Step of a central difference, about the cube root of the machine epsilon.
+fn step(x: f64) -> f64 {
+    6e-6 * x.abs().max(1.0)
+}
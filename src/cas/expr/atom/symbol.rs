@@ -8,40 +8,77 @@ impl Symbol {
     pub(crate) fn parse(lexer: &mut Lexer) -> Result<Symbol, LexerErr> {
         let mut string = String::with_capacity(E_TOKEN_LEN);
 
-        if let Some((_, c)) = lexer.chars.peek() {
-            if let 'α'..='ω' | 'Α'..='Ω' = c {
-                string.push(
-                    lexer
-                        .chars
-                        .next()
-                        .expect("Lexer.chars.next() None, but Lexer.chars.peek() was not")
-                        .1,
-                );
-                return Ok(Symbol(string));
+        if let Some((_, 'α'..='ω' | 'Α'..='Ω')) = lexer.chars.peek() {
+            string.push(
+                lexer
+                    .chars
+                    .next()
+                    .expect("Lexer.chars.next() None, but Lexer.chars.peek() was not")
+                    .1,
+            );
+
+            // units like `μs` are one symbol
+            let letters: String = (lexer.chars.clone())
+                .map(|(_, letter)| letter)
+                .take_while(char::is_ascii_alphabetic)
+                .collect();
+            let unit = format!("{}{}", string, letters);
+
+            if !letters.is_empty() && lexer.is_prefixed(&unit) {
+                lexer.eat(|pot| pot.is_ascii_alphabetic());
+                return Ok(Symbol(unit));
             }
+
+            return Ok(Symbol(string));
         }
 
+        // digits may follow the letters, like in `x0` or `stirling2`
         loop {
-            match lexer.chars.peek() {
-                Some((_, c)) => match c {
-                    'a'..='z' | 'A'..='Z' => string.push(
-                        lexer
-                            .chars
-                            .next()
-                            .expect("Lexer.chars.next() None, but Lexer.chars.peek() was not")
-                            .1,
-                    ),
-                    _ => break,
-                },
-                None => break,
+            let mut ahead = lexer.chars.clone().map(|(_, pot)| pot);
+
+            match (ahead.next(), ahead.next()) {
+                (Some(pot), _) if pot.is_ascii_alphabetic() => {}
+                (Some(pot), _) if !string.is_empty() && pot.is_ascii_digit() => {}
+                // an underscore joins names like `k_B`, but `x_0` is a child
+                (Some('_'), Some(next)) if !string.is_empty() && next.is_ascii_alphabetic() => {}
+                // a trailing underscore marks a pattern variable like `a_`
+                (Some('_'), next)
+                    if !string.is_empty()
+                        && !next.is_some_and(|next| {
+                            next.is_ascii_alphanumeric() || "_(".contains(next)
+                        }) =>
+                {
+                    lexer.chars.next();
+                    string.push('_');
+                    break;
+                }
+                _ => break,
             }
 
+            string.push(
+                lexer
+                    .chars
+                    .next()
+                    .expect("Lexer.chars.next() None, but Lexer.chars.peek() was not")
+                    .1,
+            );
+
             // check if defined here
             /* if lexer.env.contains_key(&Symbol(string.clone())) {
                 break;
             } */
         }
 
+        // units like `kΩ` are one symbol
+        if let Some((_, letter @ ('α'..='ω' | 'Α'..='Ω'))) = lexer.chars.peek() {
+            let unit = format!("{}{}", string, letter);
+
+            if !string.is_empty() && lexer.is_prefixed(&unit) {
+                lexer.chars.next();
+                return Ok(Symbol(unit));
+            }
+        }
+
         Ok(Symbol(string))
     }
 }
@@ -12,6 +12,9 @@ pub(crate) enum Op {
     Div,
     Pow,
     Fact,
+    Prime,
+    Interval,
+    PlusMinus,
     Mod,
     Def,
     Child,
@@ -24,15 +27,20 @@ pub(crate) enum Op {
     More,
     LessEq,
     MoreEq,
+    Rule,
+    If,
+    To,
 }
 
-const DOUBLE_OP: [(char, char); 6] = [
+const DOUBLE_OP: [(char, char); 8] = [
     ('=', '='),
     ('!', '='),
     ('~', '='),
     (':', '='),
     ('<', '='),
     ('>', '='),
+    ('-', '>'),
+    ('.', '.'),
 ];
 
 impl Op {
@@ -76,6 +84,7 @@ impl Op {
             "*" => Op::Mul,
             "^" => Op::Pow,
             "!" => Op::Fact,
+            "'" => Op::Prime,
             "%" => Op::Mod,
             "<" => Op::Less,
             ">" => Op::More,
@@ -89,6 +98,9 @@ impl Op {
             "<=" => Op::LessEq,
             ">=" => Op::MoreEq,
             "=" | "==" => Op::Eq,
+            "->" => Op::Rule,
+            ".." => Op::Interval,
+            "±" => Op::PlusMinus,
             _ => return None,
         })
     }
@@ -0,0 +1,85 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::{number::Number, symbol::Symbol, Atom},
+        Expr, TypeErr,
+    },
+};
+
+/// Forward-mode automatic differentiation with dual numbers.
+impl Expr {
+    /// `gradient(f, (x; y), (1; 2))` are the partial derivatives of `f` at the point,
+    /// or `gradient(f, x, 1)` the derivative by one variable.
+    pub(crate) fn gradient(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (f, vars, point) =
+            match args {
+                // a list as the last argument is flattened into the arguments
+                [f, vars, point @ ..] if !point.is_empty() => {
+                    (f.eval(env)?, vars.clone().list(), Expr::values(point, env)?)
+                }
+                _ => return Err(TypeErr(
+                    "call `gradient` undefined on (expr, ...), expected (expr, symbols, numbers)"
+                        .to_string(),
+                )),
+            };
+
+        let vars = vars
+            .iter()
+            .map(Expr::symbol)
+            .collect::<Result<Vec<_>, _>>()?;
+        if vars.len() != point.len() {
+            return Err(TypeErr(format!(
+                "call `gradient` expected a value for each of {} variables, found {}",
+                vars.len(),
+                point.len()
+            )));
+        }
+
+        let point = point
+            .iter()
+            .map(|x| x.number(env))
+            .collect::<Result<Vec<_>, _>>()?;
+        let gradient = Expr::gradient_at(&f, &vars, &point, env)?;
+
+        Ok(Expr::from_list(
+            gradient
+                .into_iter()
+                .map(|slope| Expr::num(Number::Float(slope)))
+                .collect(),
+        ))
+    }
+
+    /// Partial derivatives at a point, each from one evaluation with a dual number.
+    pub(crate) fn gradient_at(
+        &self,
+        vars: &[Symbol],
+        point: &[Number],
+        env: &mut Env,
+    ) -> Result<Vec<f64>, TypeErr> {
+        (0..vars.len())
+            .map(|i| {
+                let mut f = self.clone();
+                for (j, (var, x)) in vars.iter().zip(point).enumerate() {
+                    let x = if i == j {
+                        Number::Dual(x.as_f64(), 1.0)
+                    } else {
+                        x.clone()
+                    };
+                    f = f.replace(&Expr::Atom(Atom::Symbol(var.clone())), &Expr::num(x));
+                }
+
+                match f.number(env) {
+                    Ok(value) => Ok(value.tangent()),
+                    Err(_) => Err(TypeErr(format!(
+                        "`{}` has other variables than `{}` or unknown functions",
+                        self.tidy(),
+                        vars.iter()
+                            .map(|var| var.0.clone())
+                            .collect::<Vec<_>>()
+                            .join("`, `")
+                    ))),
+                }
+            })
+            .collect()
+    }
+}
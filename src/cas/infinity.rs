@@ -0,0 +1,210 @@
+use super::expr::{
+    atom::{number::Number, symbol::Symbol, Atom},
+    call::{op::Op, Call},
+    Expr,
+};
+
+/// Arithmetic on the extended reals: `inf` swallows every finite constant,
+/// while indeterminate forms like `inf - inf` or `0 * inf` are `nan`.
+impl Expr {
+    pub(crate) fn inf() -> Expr {
+        Expr::Atom(Atom::Symbol(Symbol("inf".to_string())))
+    }
+
+    pub(crate) fn nan() -> Expr {
+        Expr::Atom(Atom::Symbol(Symbol("nan".to_string())))
+    }
+
+    /// `inf` or `-inf`, already in canonical form.
+    pub(crate) fn infinity(positive: bool) -> Expr {
+        if positive {
+            Expr::inf()
+        } else {
+            Expr::Call(Call::new(Op::Mul, vec![Expr::int(-1), Expr::inf()]))
+        }
+    }
+
+    pub(crate) fn is_nan(&self) -> bool {
+        matches!(self, Expr::Atom(Atom::Symbol(Symbol(name))) if name == "nan")
+    }
+
+    /// The sign, if this is `inf` or `-inf`.
+    pub(crate) fn as_infinity(&self) -> Option<bool> {
+        match self {
+            Expr::Atom(Atom::Symbol(Symbol(name))) if name == "inf" => Some(true),
+            Expr::Call(Call { op: Op::Mul, args })
+                if args.len() == 2 && args[1].as_infinity() == Some(true) =>
+            {
+                args[0]
+                    .as_number()
+                    .filter(|coef| !coef.is_zero())
+                    .map(|coef| !coef.is_negative())
+            }
+            _ => None,
+        }
+    }
+
+    /// Neither an infinity, `nan` nor a pole like `0^-1` anywhere.
+    pub(crate) fn is_finite(&self) -> bool {
+        match self {
+            Expr::Atom(Atom::Number(number)) => number.as_f64().is_finite(),
+            Expr::Atom(Atom::Symbol(Symbol(name))) => name != "inf" && name != "nan",
+            Expr::Call(Call { op: Op::Pow, args })
+                if args[0].is_zero() && args[1].float().is_some_and(|exp| exp < 0.0) =>
+            {
+                false
+            }
+            Expr::Call(Call { args, .. }) => args.iter().all(Expr::is_finite),
+        }
+    }
+
+    /// Float value of an expression without any variables.
+    pub(crate) fn float(&self) -> Option<f64> {
+        self.float_with(&[])
+    }
+
+    /// Float value of an expression, where the only variable is `var = x`.
+    pub(crate) fn float_at(&self, var: &Symbol, x: f64) -> Option<f64> {
+        self.float_with(&[(var, x)])
+    }
+
+    /// Float value of an expression, where the variables have the given values.
+    pub(crate) fn float_with(&self, vars: &[(&Symbol, f64)]) -> Option<f64> {
+        match self {
+            Expr::Atom(Atom::Number(number)) => Some(number.as_f64()),
+            Expr::Atom(Atom::Symbol(symbol)) => match vars.iter().find(|(var, _)| *var == symbol) {
+                Some((_, x)) => Some(*x),
+                None => Number::constant(&symbol.0).map(|number| number.as_f64()),
+            },
+            Expr::Call(Call { op, args }) => {
+                let values = args
+                    .iter()
+                    .map(|arg| arg.float_with(vars))
+                    .collect::<Option<Vec<_>>>()?;
+
+                match (op, &values[..]) {
+                    (Op::Add, _) => Some(values.iter().sum()),
+                    (Op::Mul, _) => Some(values.iter().product()),
+                    (Op::Pow, [x, y]) => Some(x.powf(*y)),
+                    (Op::Call(call), [x]) => {
+                        Some(Expr::builtin_one(&call.0)?(Number::Float(*x)).as_f64())
+                    }
+                    (Op::Call(call), [x, y]) => Some(
+                        Expr::builtin_two(&call.0)?(Number::Float(*x), Number::Float(*y)).as_f64(),
+                    ),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Sum of already flat terms, if one of them is infinite.
+    pub(crate) fn infinite_sum(terms: &[Expr]) -> Option<Expr> {
+        if terms.iter().any(Expr::is_nan) {
+            return Some(Expr::nan());
+        }
+
+        let signs: Vec<bool> = terms.iter().filter_map(Expr::as_infinity).collect();
+        let positive = *signs.first()?;
+
+        if signs.iter().any(|sign| *sign != positive) {
+            return Some(Expr::nan());
+        }
+
+        // a variable might be infinite as well, so keep those sums
+        if terms
+            .iter()
+            .all(|term| term.as_infinity().is_some() || term.float().is_some_and(f64::is_finite))
+        {
+            Some(Expr::infinity(positive))
+        } else {
+            None
+        }
+    }
+
+    /// Product of already flat factors, if one of them is infinite.
+    pub(crate) fn infinite_product(factors: &[Expr]) -> Option<Expr> {
+        if factors.iter().any(Expr::is_nan) {
+            return Some(Expr::nan());
+        }
+
+        factors.iter().find_map(Expr::as_infinity)?;
+
+        let mut positive = true;
+        for factor in factors {
+            let value = factor.float()?;
+
+            if value == 0.0 {
+                return Some(Expr::nan());
+            }
+            if value.is_nan() {
+                return None;
+            }
+            positive = positive == (value > 0.0);
+        }
+
+        Some(Expr::infinity(positive))
+    }
+
+    /// Power with an infinite base or exponent.
+    pub(crate) fn infinite_pow(base: &Expr, exp: &Expr) -> Option<Expr> {
+        if base.is_nan() || exp.is_nan() {
+            return Some(Expr::nan());
+        }
+
+        if let Some(positive) = base.as_infinity() {
+            let value = exp.float()?;
+
+            return if value < 0.0 {
+                Some(Expr::int(0))
+            } else if positive {
+                Some(Expr::inf())
+            } else {
+                // only integer powers of `-inf` have a sign
+                let exp = exp.as_number()?.as_int()?;
+                Some(Expr::infinity(exp % 2 == 0))
+            };
+        }
+
+        let positive = exp.as_infinity()?;
+        let value = base.float()?;
+
+        Some(match (positive, value) {
+            (true, value) if value > 1.0 => Expr::inf(),
+            (true, value) if value.abs() < 1.0 => Expr::int(0),
+            (false, value) if value > 1.0 => Expr::int(0),
+            (false, value) if (0.0..1.0).contains(&value) => Expr::inf(),
+            // `1^inf` is indeterminate and negative bases oscillate
+            _ => Expr::nan(),
+        })
+    }
+
+    /// Built-in function of an infinite argument.
+    pub(crate) fn infinite_function(call: &str, x: &Expr) -> Option<Expr> {
+        if x.is_nan() {
+            return Some(Expr::nan());
+        }
+
+        let positive = x.as_infinity()?;
+
+        Some(match (call, positive) {
+            ("exp" | "ln" | "lg" | "acosh" | "fact" | "gamma" | "lgamma", true)
+            | ("abs" | "cosh", _) => Expr::inf(),
+            ("exp", false) => Expr::int(0),
+            ("sinh" | "asinh" | "cbrt" | "ceil" | "floor" | "round" | "trunc", _) => {
+                Expr::infinity(positive)
+            }
+            ("signum" | "tanh" | "erf", _) => Expr::int(if positive { 1 } else { -1 }),
+            ("erfc", _) => Expr::int(if positive { 0 } else { 2 }),
+            ("zeta", true) => Expr::int(1),
+            ("lambertw" | "digamma" | "airybi" | "airybiprime", true) => Expr::inf(),
+            ("airyai" | "airyaiprime", true) => Expr::int(0),
+            ("atan", _) => Expr::mul(vec![
+                Expr::num(Number::ratio(if positive { 1 } else { -1 }, 2)),
+                Expr::pi(),
+            ]),
+            // oscillating or outside of the domain
+            _ => Expr::nan(),
+        })
+    }
+}
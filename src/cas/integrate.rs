@@ -0,0 +1,229 @@
+use super::expr::{
+    atom::{number::Number, symbol::Symbol, Atom},
+    call::{op::Op, Call},
+    Expr, TypeErr,
+};
+
+/// How often a polynomial factor is derived in integration by parts.
+const MAX_PARTS: usize = 20;
+
+/// Antiderivatives of elementary functions: linear combinations, substitutions
+/// `f(u) u'`, and polynomials times exponentials, sines and cosines by parts.
+impl Expr {
+    /// Antiderivative of a canonical expression by a variable, without a constant.
+    pub(crate) fn antiderivative(&self, var: &Symbol) -> Result<Expr, TypeErr> {
+        self.expand()
+            .integrate(var)
+            .ok_or_else(|| TypeErr(format!("could not find an antiderivative of `{}`", self)))
+    }
+
+    fn integrate(&self, var: &Symbol) -> Option<Expr> {
+        let x = Expr::Atom(Atom::Symbol(var.clone()));
+        if !self.contains(var) {
+            return Some(Expr::mul(vec![self.clone(), x]));
+        }
+
+        match self {
+            Expr::Call(Call { op: Op::Add, args }) => Some(Expr::add(
+                args.iter()
+                    .map(|arg| arg.integrate(var))
+                    .collect::<Option<_>>()?,
+            )),
+            Expr::Call(Call { op: Op::Mul, args }) => {
+                let (constant, factors): (Vec<Expr>, Vec<Expr>) =
+                    args.iter().cloned().partition(|arg| !arg.contains(var));
+
+                let integral = match &factors[..] {
+                    [factor] => factor.integrate(var)?,
+                    _ => Expr::mul(factors.clone())
+                        .substitution(var, &factors)
+                        .or_else(|| by_parts(var, &factors))
+                        .or_else(|| exp_trig(var, &factors))?,
+                };
+                Some(Expr::mul(
+                    constant.into_iter().chain(Some(integral)).collect(),
+                ))
+            }
+            _ => self.substitution(var, std::slice::from_ref(self)),
+        }
+    }
+
+    /// `∫ f(u) u' = F(u)`, if all but one factor are a constant times `u'`.
+    fn substitution(&self, var: &Symbol, factors: &[Expr]) -> Option<Expr> {
+        let u_var = self.fresh("u");
+        let u_expr = Expr::Atom(Atom::Symbol(u_var.clone()));
+
+        factors.iter().enumerate().find_map(|(i, factor)| {
+            let (outer, u) = outer(factor, &u_expr)?;
+            let rest = Expr::mul(
+                factors
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, factor)| factor.clone())
+                    .collect(),
+            );
+
+            let constant = Expr::div(rest, u.derive(var).ok()?);
+            if constant.contains(var) {
+                return None;
+            }
+
+            let integral = elementary(&outer, &u_var)?;
+            Some(Expr::mul(vec![constant, integral.replace(&u_expr, &u)]))
+        })
+    }
+}
+
+/// Split off the inner function `u` of `f(u)`, `u^n` or `c^u`, with the outer function in `u_expr`.
+fn outer(factor: &Expr, u_expr: &Expr) -> Option<(Expr, Expr)> {
+    match factor {
+        Expr::Call(Call {
+            op: Op::Call(call),
+            args,
+        }) if args.len() == 1 => Some((
+            Expr::function(call.clone(), u_expr.clone()),
+            args[0].clone(),
+        )),
+        Expr::Call(Call { op: Op::Pow, args }) if args[1].as_number().is_some() => {
+            Some((Expr::pow(u_expr.clone(), args[1].clone()), args[0].clone()))
+        }
+        Expr::Call(Call { op: Op::Pow, args }) if args[0].as_number().is_some() => {
+            Some((Expr::pow(args[0].clone(), u_expr.clone()), args[1].clone()))
+        }
+        Expr::Atom(Atom::Symbol(_)) => Some((u_expr.clone(), factor.clone())),
+        _ => None,
+    }
+}
+
+/// Table of antiderivatives `∫ f(u) du`.
+fn elementary(f: &Expr, u: &Symbol) -> Option<Expr> {
+    let x = Expr::Atom(Atom::Symbol(u.clone()));
+    let call = |name: &str| Expr::function(Symbol(name.to_string()), x.clone());
+
+    Some(match f {
+        Expr::Atom(Atom::Symbol(_)) => Expr::mul(vec![
+            Expr::num(Number::ratio(1, 2)),
+            Expr::pow(x.clone(), Expr::int(2)),
+        ]),
+        Expr::Call(Call { op: Op::Pow, args }) if args[0] == x => match args[1].as_number()? {
+            n if n == Number::int(-1) => call("ln"),
+            n => Expr::div(
+                Expr::pow(x.clone(), Expr::num(n.clone() + Number::int(1))),
+                Expr::num(n + Number::int(1)),
+            ),
+        },
+        // c^u = exp(u ln c)
+        Expr::Call(Call { op: Op::Pow, args }) if args[1] == x => Expr::div(
+            f.clone(),
+            Expr::function(Symbol("ln".to_string()), args[0].clone()),
+        ),
+        Expr::Call(Call {
+            op: Op::Call(name),
+            args,
+        }) if args[0] == x => match &name.0[..] {
+            "exp" => call("exp"),
+            "sin" => Expr::neg(call("cos")),
+            "cos" => call("sin"),
+            "tan" => Expr::neg(Expr::function(Symbol("ln".to_string()), call("cos"))),
+            "sinh" => call("cosh"),
+            "cosh" => call("sinh"),
+            "tanh" => Expr::function(Symbol("ln".to_string()), call("cosh")),
+            // u ln u - u
+            "ln" => Expr::sub(Expr::mul(vec![x.clone(), call("ln")]), x.clone()),
+            "atan" => Expr::sub(
+                Expr::mul(vec![x.clone(), call("atan")]),
+                Expr::mul(vec![
+                    Expr::num(Number::ratio(1, 2)),
+                    Expr::function(
+                        Symbol("ln".to_string()),
+                        Expr::add(vec![Expr::int(1), Expr::pow(x.clone(), Expr::int(2))]),
+                    ),
+                ]),
+            ),
+            _ => return None,
+        },
+        _ => return None,
+    })
+}
+
+/// `∫ p f = p F1 - p' F2 + p'' F3 - ...` for a polynomial `p`,
+/// where `f` can be integrated again and again.
+fn by_parts(var: &Symbol, factors: &[Expr]) -> Option<Expr> {
+    let (i, f) = factors.iter().enumerate().find(|(_, factor)| {
+        matches!(factor, Expr::Call(Call { op: Op::Call(call), .. })
+            if matches!(&call.0[..], "exp" | "sin" | "cos" | "sinh" | "cosh"))
+    })?;
+    let mut p = Expr::mul(
+        factors
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, factor)| factor.clone())
+            .collect(),
+    );
+    p.coeffs(var)?;
+
+    let mut terms = Vec::new();
+    let mut integral = f.integrate(var)?;
+    for k in 0..MAX_PARTS {
+        if p.is_zero() {
+            return Some(Expr::add(terms));
+        }
+
+        let sign = if k % 2 == 0 { 1 } else { -1 };
+        terms.push(Expr::mul(vec![
+            Expr::int(sign),
+            p.clone(),
+            integral.clone(),
+        ]));
+        p = p.derive(var).ok()?;
+        integral = integral.integrate(var)?;
+    }
+
+    None
+}
+
+/// `∫ exp(u) sin(v)` and `∫ exp(u) cos(v)` of linear `u = a x + b` and `v = c x + d`.
+fn exp_trig(var: &Symbol, factors: &[Expr]) -> Option<Expr> {
+    let argument = |name: &str| {
+        factors.iter().find_map(|factor| match factor {
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) if call.0 == name => Some(args[0].clone()),
+            _ => None,
+        })
+    };
+    let slope = |u: &Expr| u.derive(var).ok().filter(|slope| !slope.contains(var));
+
+    if factors.len() != 2 {
+        return None;
+    }
+    let u = argument("exp")?;
+    let (sine, v) = match argument("sin") {
+        Some(v) => (true, v),
+        None => (false, argument("cos")?),
+    };
+    let (a, c) = (slope(&u)?, slope(&v)?);
+
+    let sin = Expr::function(Symbol("sin".to_string()), v.clone());
+    let cos = Expr::function(Symbol("cos".to_string()), v);
+    // exp(u) (a sin v - c cos v) / (a^2 + c^2) and exp(u) (a cos v + c sin v) / (a^2 + c^2)
+    let trig = if sine {
+        Expr::sub(
+            Expr::mul(vec![a.clone(), sin]),
+            Expr::mul(vec![c.clone(), cos]),
+        )
+    } else {
+        Expr::add(vec![
+            Expr::mul(vec![a.clone(), cos]),
+            Expr::mul(vec![c.clone(), sin]),
+        ])
+    };
+
+    Some(Expr::div(
+        Expr::mul(vec![Expr::function(Symbol("exp".to_string()), u), trig]),
+        Expr::add(vec![Expr::pow(a, Expr::int(2)), Expr::pow(c, Expr::int(2))]),
+    ))
+}
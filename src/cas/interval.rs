@@ -0,0 +1,59 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::number::Number,
+        call::{op::Op, Call},
+        Expr, TypeErr,
+    },
+};
+
+/// Intervals of numbers, which are closed under arithmetic and built-in functions.
+impl Expr {
+    /// `interval(a, b)` is the same as `[a .. b]`.
+    pub(crate) fn interval(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        match args {
+            [a, b] => Expr::hull(a.eval(env)?, b.eval(env)?),
+            _ => Err(TypeErr(
+                "call `interval` undefined on (expr, ...), expected (expr, expr)".to_string(),
+            )),
+        }
+    }
+
+    /// Interval in between two bounds, where constants like `π` are rounded outward.
+    /// Bounds with variables stay an unevaluated `a .. b`.
+    pub(crate) fn hull(a: Expr, b: Expr) -> Result<Expr, TypeErr> {
+        let bound = |x: &Expr| {
+            let nan = || Err(TypeErr(format!("interval bound `{}` is not a number", x)));
+
+            match (x.as_number(), x.as_infinity(), x.float()) {
+                (Some(number), _, _) if number.as_f64().is_nan() => nan(),
+                (Some(number), _, _) => Ok(Some(number)),
+                (_, Some(true), _) => Ok(Some(Number::Float(f64::INFINITY))),
+                (_, Some(false), _) => Ok(Some(Number::Float(f64::NEG_INFINITY))),
+                (_, _, Some(value)) if value.is_nan() => nan(),
+                (_, _, Some(value)) => {
+                    Ok(Some(Number::Interval(value.next_down(), value.next_up())))
+                }
+                _ => Ok(None),
+            }
+        };
+
+        match (bound(&a)?, bound(&b)?) {
+            (Some(lower), Some(upper)) if lower.bounds().0 > upper.bounds().1 => Err(TypeErr(
+                format!("interval `[{} .. {}]` has its bounds reversed", a, b),
+            )),
+            (Some(lower), Some(upper)) => Ok(Expr::num(lower.hull(upper))),
+            _ => Ok(Expr::Call(Call::new(Op::Interval, vec![a, b]))),
+        }
+    }
+
+    /// Fails for an interval without real numbers, like the square root of negative numbers.
+    pub(crate) fn nonempty(self, call: &Expr) -> Result<Expr, TypeErr> {
+        match self.as_number() {
+            Some(Number::Interval(lower, upper)) if lower.is_nan() || lower > upper => {
+                Err(TypeErr(format!("`{}` has no real values", call)))
+            }
+            _ => Ok(self),
+        }
+    }
+}
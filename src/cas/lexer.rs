@@ -1,7 +1,11 @@
 use {
     super::{
         env::{Def, Env},
-        expr::{atom::Atom, call::op::Op},
+        expr::{
+            atom::{symbol::Symbol, Atom},
+            call::op::Op,
+            Expr,
+        },
         parser::ParserErr,
     },
     std::{iter::Peekable, str::CharIndices},
@@ -14,6 +18,7 @@ pub(crate) struct Lexer<'s, 'e> {
     peek: Option<Result<Token, LexerErr>>,
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(PartialEq)]
 pub enum LexerErr {
     EOF,
@@ -26,11 +31,12 @@ pub(crate) enum Token {
     Op(Op),
 }
 
-const WHITESPACE: &'static str = " \t\n";
-const OP_BEGIN: &'static str = "+-*/:^%()[]{},;_<>!~=";
-pub(crate) const DEC_DIGITS: &'static str = "0123456789";
-pub(crate) const HEX_DIGITS: &'static str = "0123456789ABCDEF";
-pub(crate) const BIN_DIGITS: &'static str = "01";
+const WHITESPACE: &str = " \t\n";
+const OP_BEGIN: &str = "+-*/:^%()[]{},;_<>!~='±";
+pub(crate) const DEC_DIGITS: &str = "0123456789";
+pub(crate) const HEX_DIGITS: &str = "0123456789ABCDEFabcdef";
+pub(crate) const OCT_DIGITS: &str = "01234567";
+pub(crate) const BIN_DIGITS: &str = "01";
 pub(crate) const E_TOKEN_LEN: usize = 6;
 
 impl<'s, 'e> Lexer<'s, 'e> {
@@ -44,7 +50,7 @@ impl<'s, 'e> Lexer<'s, 'e> {
     }
 
     pub(crate) fn peek(&mut self) -> Result<&Token, &LexerErr> {
-        if let None = self.peek {
+        if self.peek.is_none() {
             self.peek = Some(self.token());
         }
 
@@ -93,12 +99,24 @@ impl<'s, 'e> Lexer<'s, 'e> {
             }
 
             &first if OP_BEGIN.contains(first) => Ok(Token::Op(Op::parse(self)?)),
+            // `..` of an interval, not the point of a number
+            '.' if self.input[*at..].starts_with("..") => Ok(Token::Op(Op::parse(self)?)),
 
             'a'..='z' | 'A'..='Z' | 'α'..='ω' | 'Α'..='Ω' | '0'..='9' | '.' => {
                 Ok(match Atom::parse(self)? {
-                    Atom::Symbol(symbol) => match self.env.0.get(&symbol) {
-                        Some(Def::OSCall) => Token::Op(Op::Call(symbol)),
-                        Some(Def::Call { args: _, call: _ }) => Token::Op(Op::Call(symbol)),
+                    // the condition of a rule
+                    Atom::Symbol(symbol) if symbol.0 == "if" => Token::Op(Op::If),
+                    // the conversion of a quantity into units, unless `to` or `in` is defined
+                    Atom::Symbol(symbol)
+                        if (symbol.0 == "to" || symbol.0 == "in")
+                            && self.env.has_units()
+                            && self.env.get(&symbol).is_err() =>
+                    {
+                        Token::Op(Op::To)
+                    }
+                    Atom::Symbol(symbol) => match self.env.get(&symbol) {
+                        Ok(Def::OSCall) => Token::Op(Op::Call(symbol)),
+                        Ok(Def::Call { args: _, call: _ }) => Token::Op(Op::Call(symbol)),
                         _ => Token::Atom(Atom::Symbol(symbol)),
                     },
                     atom => Token::Atom(atom),
@@ -109,6 +127,29 @@ impl<'s, 'e> Lexer<'s, 'e> {
         }
     }
 
+    /// Whether the input continues with some text.
+    pub(crate) fn ahead(&mut self, text: &str) -> bool {
+        match self.chars.peek() {
+            Some((at, _)) => self.input[*at..].starts_with(text),
+            None => false,
+        }
+    }
+
+    /// Whether a symbol is a built-in function, like `sin` or `N`.
+    pub(crate) fn is_builtin(&self, symbol: &Symbol) -> bool {
+        matches!(self.env.get(symbol), Ok(Def::OSCall))
+    }
+
+    /// Whether a symbol is a prefixed unit, like `μs` or `kΩ`.
+    pub(crate) fn is_prefixed(&self, symbol: &str) -> bool {
+        Expr::prefixed(&Symbol(symbol.to_string()), self.env).is_some()
+    }
+
+    /// Whether a symbol is a unit, like `m`, `newton` or `km`.
+    pub(crate) fn is_unit(&self, symbol: &Symbol) -> bool {
+        Expr::is_unit(symbol, self.env, 0) || self.is_prefixed(&symbol.0)
+    }
+
     pub(crate) fn eat(&mut self, taste: impl Fn(&char) -> bool) -> String {
         let mut food = String::with_capacity(E_TOKEN_LEN);
 
@@ -0,0 +1,244 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::{symbol::Symbol, Atom},
+        call::{op::Op, Call},
+        Expr, TypeErr,
+    },
+    series::Series,
+};
+
+/// How often L'Hôpital's rule may be applied in a row.
+const MAX_LHOPITAL: usize = 6;
+
+impl Expr {
+    /// `limit(expr, x, x0)` is the two-sided limit of expr for `x -> x0`, where `x0` may be `inf`.
+    /// A positive or negative fourth argument only approaches `x0` from above or below.
+    pub(crate) fn limit(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (expr, var, x0, above) =
+            match args {
+                [expr, var, x0] => (expr, var, x0, None),
+                [expr, var, x0, dir] => {
+                    let dir = dir.number(env)?;
+                    if dir.is_zero() {
+                        return Err(TypeErr(format!("limit direction `{:?}` is zero", dir)));
+                    }
+                    (expr, var, x0, Some(!dir.is_negative()))
+                }
+                _ => return Err(TypeErr(
+                    "call `limit` undefined on (expr, ...), expected (expr, symbol, expr, number)"
+                        .to_string(),
+                )),
+            };
+
+        let var = var.symbol()?;
+        let expr = expr.eval(env)?;
+        let x0 = x0.eval(env)?;
+
+        expr.limit_of(&var, &x0, above)
+    }
+
+    /// Limit of a canonical expression, from above, below or two-sided for `None`.
+    pub(crate) fn limit_of(
+        &self,
+        var: &Symbol,
+        x0: &Expr,
+        above: Option<bool>,
+    ) -> Result<Expr, TypeErr> {
+        match above {
+            Some(above) => self.limit_at(var, x0, above, MAX_LHOPITAL),
+            // infinity can only be approached from one side
+            None if x0.as_infinity().is_some() => self.limit_at(var, x0, true, MAX_LHOPITAL),
+            None => {
+                let from_above = self.limit_at(var, x0, true, MAX_LHOPITAL)?;
+                let from_below = self.limit_at(var, x0, false, MAX_LHOPITAL)?;
+
+                if from_above == from_below {
+                    Ok(from_above)
+                } else {
+                    Err(TypeErr(format!(
+                        "limit of `{}` does not exist, it is `{}` from above and `{}` from below",
+                        self,
+                        from_above.tidy(),
+                        from_below.tidy()
+                    )))
+                }
+            }
+        }
+    }
+
+    /// One-sided limit of a canonical expression, trying substitution,
+    /// then L'Hôpital's rule and finally the leading term of the series.
+    fn limit_at(
+        &self,
+        var: &Symbol,
+        x0: &Expr,
+        mut above: bool,
+        depth: usize,
+    ) -> Result<Expr, TypeErr> {
+        let x = Expr::Atom(Atom::Symbol(var.clone()));
+
+        if !x0.is_finite() && x0.as_infinity().is_none() {
+            return Err(TypeErr(format!("can not approach `{}`", x0)));
+        }
+
+        if let Some(jump) = self.jump(var, x0) {
+            return Err(TypeErr(format!("`{}` jumps at `{}`", jump, x0)));
+        }
+
+        // continuous at x0, or a definite infinity like `ln 0`
+        let value = self.replace(&x, x0);
+        if value.is_finite() || value.as_infinity().is_some() {
+            return Ok(value);
+        }
+
+        // b^e = exp(e ln b) for `1^inf`, `0^0` and `inf^0`
+        if let Expr::Call(Call { op: Op::Pow, args }) = self {
+            if args[1].contains(var) {
+                let exp = Expr::mul(vec![
+                    args[1].clone(),
+                    Expr::function(Symbol("ln".to_string()), args[0].clone()),
+                ]);
+
+                return Ok(Expr::function(
+                    Symbol("exp".to_string()),
+                    exp.limit_at(var, x0, above, depth)?,
+                ));
+            }
+        }
+
+        if depth > 0 {
+            if let Some(limit) = self.lhopital(var, x0, above, depth - 1) {
+                return Ok(limit);
+            }
+        }
+
+        let (coeff, val) = match x0.as_infinity() {
+            Some(positive) => {
+                // x = ±1/t with t -> 0 from above
+                let t = self.fresh("t");
+                let inverse = Expr::mul(vec![
+                    Expr::int(if positive { 1 } else { -1 }),
+                    Expr::pow(Expr::Atom(Atom::Symbol(t.clone())), Expr::int(-1)),
+                ]);
+
+                above = true;
+                Series::leading(&self.replace(&x, &inverse), &t, &Expr::int(0))
+            }
+            None => Series::leading(self, var, x0),
+        }
+        .map_err(|_| {
+            TypeErr(format!(
+                "could not find the limit of `{}` at `{}`",
+                self, x0
+            ))
+        })?;
+
+        Ok(if val > 0 {
+            Expr::int(0)
+        } else if val == 0 {
+            coeff
+        } else {
+            // odd powers of a negative offset are negative
+            let negative = !above && val % 2 != 0;
+            Expr::mul(vec![coeff, Expr::infinity(!negative)])
+        })
+    }
+
+    /// Limit of `0/0` or `inf/inf` as the limit of the quotient of the derivatives.
+    /// A product `0 * inf` is rewritten into such a quotient first.
+    fn lhopital(&self, var: &Symbol, x0: &Expr, above: bool, depth: usize) -> Option<Expr> {
+        let factors = match self {
+            Expr::Call(Call { op: Op::Mul, args }) => args.clone(),
+            _ => vec![self.clone()],
+        };
+        // constant factors only get in the way
+        let (constant, factors): (Vec<Expr>, Vec<Expr>) = factors
+            .into_iter()
+            .partition(|factor| !factor.contains(var));
+
+        let (mut num, mut den) = (Vec::new(), Vec::new());
+        for factor in factors {
+            match factor {
+                Expr::Call(Call {
+                    op: Op::Pow,
+                    ref args,
+                }) if args[1].is_negative() => {
+                    den.push(Expr::pow(args[0].clone(), Expr::neg(args[1].clone())))
+                }
+                factor => num.push(factor),
+            }
+        }
+
+        if den.is_empty() {
+            // `0 * inf` is `inf / (1 / 0)`
+            let (zeros, rest): (Vec<Expr>, Vec<Expr>) = num.into_iter().partition(|factor| {
+                factor
+                    .limit_at(var, x0, above, depth)
+                    .is_ok_and(|limit| limit.is_zero())
+            });
+
+            if zeros.is_empty() || rest.is_empty() {
+                return None;
+            }
+
+            // the reciprocal of the zeros is infinite anyway
+            let rest = Expr::mul(rest);
+            rest.limit_at(var, x0, above, depth).ok()?.as_infinity()?;
+
+            num = vec![rest];
+            den = vec![Expr::pow(Expr::mul(zeros), Expr::int(-1))];
+        } else {
+            let (a, b) = (
+                Expr::mul(num.clone())
+                    .limit_at(var, x0, above, depth)
+                    .ok()?,
+                Expr::mul(den.clone())
+                    .limit_at(var, x0, above, depth)
+                    .ok()?,
+            );
+
+            let indeterminate = (a.is_zero() && b.is_zero())
+                || (a.as_infinity().is_some() && b.as_infinity().is_some());
+            if !indeterminate {
+                return None;
+            }
+        }
+
+        let (num, den) = (Expr::mul(num), Expr::mul(den));
+
+        let limit = Expr::div(num.derive(var).ok()?, den.derive(var).ok()?)
+            .limit_at(var, x0, above, depth)
+            .ok()?;
+
+        Some(Expr::mul(vec![Expr::mul(constant), limit]))
+    }
+
+    /// A piecewise constant function, which is not continuous at x0.
+    fn jump(&self, var: &Symbol, x0: &Expr) -> Option<&Expr> {
+        match self {
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) if args.len() == 1 && args[0].contains(var) => {
+                let x = Expr::Atom(Atom::Symbol(var.clone()));
+                let at = args[0].replace(&x, x0).float();
+
+                let jumps = at.is_some_and(|at| match &call.0[..] {
+                    "signum" => at == 0.0,
+                    "ceil" | "floor" | "trunc" | "fract" => at.fract() == 0.0,
+                    "round" => at.fract().abs() == 0.5,
+                    _ => false,
+                });
+
+                if jumps {
+                    Some(self)
+                } else {
+                    args[0].jump(var, x0)
+                }
+            }
+            Expr::Call(Call { args, .. }) => args.iter().find_map(|arg| arg.jump(var, x0)),
+            Expr::Atom(_) => None,
+        }
+    }
+}
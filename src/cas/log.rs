@@ -0,0 +1,200 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::{number::Number, symbol::Symbol},
+        call::{op::Op, Call},
+        Expr, TypeErr,
+    },
+};
+
+/// Largest prime factor, that `expandlog` looks for by trial division.
+const MAX_TRIAL: i128 = 1_000_000;
+
+/// Logarithms are canonical in `ln`: `log(x, b) = ln x / ln b`, unless that is an integer.
+/// Splitting a logarithm is only valid for positive arguments, combining always where defined.
+impl Expr {
+    /// `expandlog(expr)` splits `ln(a b) = ln a + ln b` and `ln(a^b) = b ln a` for positive a, b.
+    pub(crate) fn expandlog(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        match args {
+            [expr] => Ok(expr.eval(env)?.expand_log(env)),
+            _ => Err(TypeErr(
+                "call `expandlog` undefined on (expr, ...), expected (expr)".to_string(),
+            )),
+        }
+    }
+
+    /// `combinelog(expr)` combines `ln a + ln b = ln(a b)` if all but one of them are
+    /// positive, and `b ln a = ln(a^b)` for positive a.
+    pub(crate) fn combinelog(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        match args {
+            [expr] => Ok(expr.eval(env)?.combine_log(env)),
+            _ => Err(TypeErr(
+                "call `combinelog` undefined on (expr, ...), expected (expr)".to_string(),
+            )),
+        }
+    }
+
+    /// Logarithm of x to a base, changed to the natural base if it is not exact.
+    pub(crate) fn logarithm(x: Expr, base: Expr) -> Expr {
+        if let (Some(a), Some(b)) = (x.as_number(), base.as_number()) {
+            let value = a.clone().log(b.clone());
+            if value.is_exact() || !(a.is_exact() && b.is_exact()) {
+                return Expr::num(value);
+            }
+        }
+
+        Expr::div(ln(x), ln(base))
+    }
+
+    /// Exponentials of logarithms are powers: `exp(b ln a + c) = a^b exp c`.
+    pub(crate) fn exp_of_logs(x: &Expr) -> Option<Expr> {
+        let terms = match x {
+            Expr::Call(Call { op: Op::Add, args }) => args.clone(),
+            _ => vec![x.clone()],
+        };
+
+        let (mut powers, mut rest) = (Vec::new(), Vec::new());
+        for term in terms {
+            let (coef, factor) = term.split_coef();
+            match factor {
+                Expr::Call(Call {
+                    op: Op::Call(call),
+                    args,
+                }) if call.0 == "ln" => powers.push(Expr::pow(args[0].clone(), Expr::num(coef))),
+                _ => rest.push(term),
+            }
+        }
+
+        if powers.is_empty() {
+            return None;
+        }
+
+        powers.push(Expr::function(Symbol("exp".to_string()), Expr::add(rest)));
+        Some(Expr::mul(powers))
+    }
+
+    fn expand_log(&self, env: &Env) -> Expr {
+        match self {
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) if call.0 == "ln" && args.len() == 1 => args[0].expand_log(env).split_log(env),
+            Expr::Call(Call { op, args }) => {
+                Expr::rebuild(op, args.iter().map(|arg| arg.expand_log(env)).collect())
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Split the logarithm of an argument as far as it is positive.
+    fn split_log(&self, env: &Env) -> Expr {
+        match self {
+            Expr::Call(Call { op: Op::Mul, args })
+                if args.iter().all(|arg| arg.is_positive(env)) =>
+            {
+                Expr::add(args.iter().map(|arg| arg.split_log(env)).collect())
+            }
+            Expr::Call(Call { op: Op::Pow, args })
+                if args[0].is_positive(env) && args[1].float().is_some() =>
+            {
+                Expr::mul(vec![args[1].clone(), args[0].split_log(env)])
+            }
+            Expr::Atom(_) => match self.as_number() {
+                Some(Number::Ratio(num, den)) if num > 0 => match (factor(num), factor(den)) {
+                    (Some(num), Some(den)) => Expr::sub(ln_factors(num), ln_factors(den)),
+                    _ => ln(self.clone()),
+                },
+                _ => ln(self.clone()),
+            },
+            _ => ln(self.clone()),
+        }
+    }
+
+    fn combine_log(&self, env: &Env) -> Expr {
+        let expr = match self {
+            Expr::Call(Call { op, args }) => {
+                Expr::rebuild(op, args.iter().map(|arg| arg.combine_log(env)).collect())
+            }
+            _ => return self.clone(),
+        };
+
+        let terms = match &expr {
+            Expr::Call(Call { op: Op::Add, args }) => args.clone(),
+            Expr::Call(Call { op: Op::Mul, .. }) => vec![expr.clone()],
+            _ => return expr,
+        };
+
+        let (mut powers, mut rest) = (Vec::new(), Vec::new());
+        // `ln(-1) + ln(-1)` is not `ln 1`, at most one argument may be negative
+        let mut unknown = false;
+        for term in terms {
+            let (coef, factor) = term.split_coef();
+            match factor {
+                Expr::Call(Call {
+                    op: Op::Call(call),
+                    args,
+                }) if call.0 == "ln" && coef.is_exact() => {
+                    if !args[0].is_positive(env) {
+                        if unknown || !coef.is_one() {
+                            rest.push(term);
+                            continue;
+                        }
+                        unknown = true;
+                    }
+                    powers.push(Expr::pow(args[0].clone(), Expr::num(coef)))
+                }
+                _ => rest.push(term),
+            }
+        }
+
+        if powers.is_empty() {
+            return expr;
+        }
+
+        rest.push(ln(Expr::mul(powers)));
+        Expr::add(rest)
+    }
+}
+
+fn ln(x: Expr) -> Expr {
+    Expr::function(Symbol("ln".to_string()), x)
+}
+
+/// Sum of `k ln p` for the prime factors `p^k`.
+fn ln_factors(factors: Vec<(i128, i128)>) -> Expr {
+    Expr::add(
+        factors
+            .into_iter()
+            .map(|(prime, exp)| Expr::mul(vec![Expr::int(exp), ln(Expr::int(prime))]))
+            .collect(),
+    )
+}
+
+/// Prime factors with their multiplicity, if they are small enough for trial division.
+fn factor(mut n: i128) -> Option<Vec<(i128, i128)>> {
+    let mut factors = Vec::new();
+    let mut prime = 2;
+
+    while prime * prime <= n {
+        if prime > MAX_TRIAL {
+            return None;
+        }
+
+        let mut exp = 0;
+        while n % prime == 0 {
+            n /= prime;
+            exp += 1;
+        }
+        if exp > 0 {
+            factors.push((prime, exp));
+        }
+
+        prime += if prime == 2 { 1 } else { 2 };
+    }
+
+    if n > 1 {
+        factors.push((n, 1));
+    }
+
+    Some(factors)
+}
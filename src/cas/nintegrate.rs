@@ -0,0 +1,317 @@
+use {
+    super::{
+        env::{Def, Env},
+        expr::{
+            atom::{number::Number, symbol::Symbol},
+            Expr, TypeErr,
+        },
+    },
+    std::f64::consts::FRAC_PI_2,
+};
+
+/// Nodes of the 15-point Kronrod rule on `[0, 1]`, where every second one is a Gauss node.
+const KRONROD_NODES: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_5,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_48,
+    0.0,
+];
+
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022_935_322_010_529_224,
+    0.063_092_092_629_978_56,
+    0.104_790_010_322_250_19,
+    0.140_653_259_715_525_92,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_42,
+    0.204_432_940_075_298_89,
+    0.209_482_141_084_727_82,
+];
+
+/// Weights of the 7-point Gauss rule at the odd Kronrod nodes.
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.129_484_966_168_869_7,
+    0.279_705_391_489_276_64,
+    0.381_830_050_505_118_9,
+    0.417_959_183_673_469_4,
+];
+
+/// Most intervals of the adaptive Gauss-Kronrod rule.
+const MAX_INTERVALS: usize = 500;
+
+/// Relative accuracy of the integrals, as far as the estimate can tell.
+const TOLERANCE: f64 = 1e-12;
+
+/// Halvings of the step of the tanh-sinh rule.
+const MAX_LEVEL: usize = 10;
+
+/// Largest `t` of the tanh-sinh rule, where the weights are far below the precision of a float.
+const MAX_TANH_SINH: f64 = 4.5;
+
+/// Numeric definite integrals, returned with an estimate of their error.
+impl Expr {
+    /// `nintegrate(f, x, a, b)` is the list `integral, error` of f from `a` to `b`,
+    /// which may be infinite. Adaptive Gauss-Kronrod falls back to tanh-sinh
+    /// quadrature, which handles singularities at the bounds better.
+    /// If neither converges, as for divergent integrals, the error is `inf`.
+    pub(crate) fn nintegrate(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (expr, var, a, b) = match args {
+            [expr, var, a, b] => (expr.eval(env)?, var.symbol()?, a.eval(env)?, b.eval(env)?),
+            _ => return Err(TypeErr(
+                "call `nintegrate` undefined on (expr, ...), expected (expr, symbol, expr, expr)"
+                    .to_string(),
+            )),
+        };
+
+        let a = match a.as_infinity() {
+            Some(positive) => Bound::Infinite(positive),
+            None => Bound::Finite(a.number(env)?.as_f64()),
+        };
+        let b = match b.as_infinity() {
+            Some(positive) => Bound::Infinite(positive),
+            None => Bound::Finite(b.number(env)?.as_f64()),
+        };
+
+        let mut f = expr.numeric_at(&var, env)?;
+        let (integral, error) = match (a, b) {
+            (Bound::Finite(a), Bound::Finite(b)) if a == b => (0.0, 0.0),
+            (Bound::Infinite(lower), Bound::Infinite(upper)) if lower == upper => (0.0, 0.0),
+            (Bound::Finite(a), Bound::Finite(b)) if a > b => {
+                let (integral, error) = integrate(&mut f, b, a);
+                (-integral, error)
+            }
+            (Bound::Finite(a), Bound::Finite(b)) => integrate(&mut f, a, b),
+            // x = t / (1 - t^2)
+            (Bound::Infinite(lower), Bound::Infinite(_)) => {
+                let sign = if lower { -1.0 } else { 1.0 };
+                let (integral, error) = integrate(
+                    &mut |t: f64| {
+                        let s = 1.0 - t * t;
+                        f(t / s) * (1.0 + t * t) / (s * s)
+                    },
+                    -1.0,
+                    1.0,
+                );
+                (sign * integral, error)
+            }
+            (Bound::Finite(a), Bound::Infinite(true)) => above(&mut f, a),
+            (Bound::Infinite(false), Bound::Finite(b)) => below(&mut f, b),
+            (Bound::Finite(a), Bound::Infinite(false)) => {
+                let (integral, error) = below(&mut f, a);
+                (-integral, error)
+            }
+            (Bound::Infinite(true), Bound::Finite(b)) => {
+                let (integral, error) = above(&mut f, b);
+                (-integral, error)
+            }
+        };
+
+        Ok(Expr::from_list(vec![
+            Expr::num(Number::Float(integral)),
+            Expr::num(Number::Float(error)),
+        ]))
+    }
+
+    /// Float function of a variable, which tries the fast `float_at` and
+    /// else substitutes the variable into the expression and evaluates it.
+    pub(crate) fn numeric_at<'e>(
+        &'e self,
+        var: &'e Symbol,
+        env: &'e mut Env,
+    ) -> Result<impl FnMut(f64) -> f64 + 'e, TypeErr> {
+        let mut at = move |x: f64| -> Result<f64, TypeErr> {
+            if let Some(y) = self.float_at(var, x) {
+                return Ok(y);
+            }
+
+            let mut vars = Env::new();
+            vars.insert(var.clone(), Def::Expr(Expr::num(Number::Float(x))));
+            Ok(self.apply_env(&mut vars)?.number(env)?.as_f64())
+        };
+
+        // anything but the variable stays the same at every point
+        at(0.5).map_err(|_| {
+            TypeErr(format!(
+                "`{}` has other variables than `{}`",
+                self.tidy(),
+                var.0
+            ))
+        })?;
+
+        Ok(move |x| at(x).unwrap_or(f64::NAN))
+    }
+}
+
+struct Interval {
+    a: f64,
+    b: f64,
+    integral: f64,
+    error: f64,
+    /// Integral of `|f|`, the scale of the rounding errors.
+    absolute: f64,
+}
+
+#[derive(Clone, Copy)]
+enum Bound {
+    Finite(f64),
+    Infinite(bool),
+}
+
+/// Integral from `a` to `inf`, with `x = a + t / (1 - t)`.
+fn above(f: &mut impl FnMut(f64) -> f64, a: f64) -> (f64, f64) {
+    integrate(
+        &mut |t: f64| f(a + t / (1.0 - t)) / ((1.0 - t) * (1.0 - t)),
+        0.0,
+        1.0,
+    )
+}
+
+/// Integral from `-inf` to `b`, with `x = b - (1 - t) / t`.
+fn below(f: &mut impl FnMut(f64) -> f64, b: f64) -> (f64, f64) {
+    integrate(&mut |t: f64| f(b - (1.0 - t) / t) / (t * t), 0.0, 1.0)
+}
+
+/// Integral from `a < b` and its error, by Gauss-Kronrod or else by tanh-sinh.
+/// The error of an integral, that converges by neither, is infinite.
+fn integrate(f: &mut impl FnMut(f64) -> f64, a: f64, b: f64) -> (f64, f64) {
+    let (integral, error, converged) = gauss_kronrod(f, a, b);
+    if converged {
+        return (integral, error);
+    }
+
+    match tanh_sinh(f, a, b) {
+        (other, other_error, true) => (other, other_error),
+        (other, other_error, false) if other_error < error || error.is_nan() => {
+            (other, f64::INFINITY)
+        }
+        _ => (integral, f64::INFINITY),
+    }
+}
+
+/// Adaptive Gauss-Kronrod, which always halves the interval with the largest error.
+fn gauss_kronrod(f: &mut impl FnMut(f64) -> f64, a: f64, b: f64) -> (f64, f64, bool) {
+    let mut intervals = vec![kronrod(f, a, b)];
+
+    loop {
+        let integral: f64 = intervals.iter().map(|interval| interval.integral).sum();
+        let error: f64 = intervals.iter().map(|interval| interval.error).sum();
+        let absolute: f64 = intervals.iter().map(|interval| interval.absolute).sum();
+
+        let tolerance = (TOLERANCE * integral.abs()).max(50.0 * f64::EPSILON * absolute);
+        if error <= tolerance || !error.is_finite() || intervals.len() >= MAX_INTERVALS {
+            return (integral, error, error <= tolerance);
+        }
+
+        let worst = (0..intervals.len())
+            .max_by(|i, j| intervals[*i].error.total_cmp(&intervals[*j].error))
+            .expect("there is always an interval");
+        let Interval { a, b, .. } = intervals.swap_remove(worst);
+        let middle = (a + b) / 2.0;
+
+        // the interval can not be split any further
+        if middle <= a || middle >= b {
+            return (integral, error, false);
+        }
+
+        intervals.push(kronrod(f, a, middle));
+        intervals.push(kronrod(f, middle, b));
+    }
+}
+
+/// 15-point Kronrod rule with the error estimate of QUADPACK.
+fn kronrod(f: &mut impl FnMut(f64) -> f64, a: f64, b: f64) -> Interval {
+    let (center, half) = ((a + b) / 2.0, (b - a) / 2.0);
+
+    let mut values = [0.0; 15];
+    for (i, node) in KRONROD_NODES.iter().enumerate() {
+        values[i] = f(center - half * node);
+        values[14 - i] = f(center + half * node);
+    }
+
+    let weight = |i: usize| KRONROD_WEIGHTS[i.min(14 - i)];
+    let kronrod: f64 = (0..15).map(|i| weight(i) * values[i]).sum();
+    let gauss: f64 = (1..15)
+        .step_by(2)
+        .map(|i| GAUSS_WEIGHTS[i.min(14 - i) / 2] * values[i])
+        .sum();
+    let absolute: f64 = (0..15).map(|i| weight(i) * values[i].abs()).sum();
+    let deviation: f64 = (0..15)
+        .map(|i| weight(i) * (values[i] - kronrod / 2.0).abs())
+        .sum();
+
+    let mut error = (kronrod - gauss).abs() * half;
+    let deviation = deviation * half;
+    if deviation != 0.0 && error != 0.0 {
+        error = deviation * (200.0 * error / deviation).powf(1.5).min(1.0);
+    }
+
+    Interval {
+        a,
+        b,
+        integral: kronrod * half,
+        error,
+        absolute: absolute * half,
+    }
+}
+
+/// Double exponential quadrature, which samples densely at the bounds.
+/// It converges, if the levels agree, the terms vanish at the last nodes
+/// and `f` is finite in between the bounds.
+fn tanh_sinh(f: &mut impl FnMut(f64) -> f64, a: f64, b: f64) -> (f64, f64, bool) {
+    let half = (b - a) / 2.0;
+    let mut singular = false;
+
+    // contribution of the nodes ±t, where the distance to a bound is computed without cancellation
+    let mut at = |t: f64| {
+        let s = FRAC_PI_2 * t.sinh();
+        let weight = FRAC_PI_2 * t.cosh() / (s.cosh() * s.cosh());
+        let distance = half / (s.exp() * s.cosh());
+
+        let mut sum = 0.0;
+        for x in [a + distance, b - distance] {
+            let y = f(x);
+            if y.is_finite() {
+                sum += y;
+            } else if x != a && x != b {
+                // only nodes, that are rounded to a bound, may be singular
+                singular = true;
+            }
+        }
+        sum * weight
+    };
+
+    let mut h = 1.0;
+    let mut sum = at(0.0) / 2.0
+        + (1..=(MAX_TANH_SINH / h) as usize)
+            .map(|k| at(k as f64 * h))
+            .sum::<f64>();
+    let mut integral = h * sum * half;
+    let mut error = f64::INFINITY;
+
+    for _ in 0..MAX_LEVEL {
+        // the new nodes are in between the old ones
+        h /= 2.0;
+        sum += (0..((MAX_TANH_SINH / h) as usize).div_ceil(2))
+            .map(|k| at((2 * k + 1) as f64 * h))
+            .sum::<f64>();
+
+        let next = h * sum * half;
+        error = (next - integral).abs();
+        integral = next;
+
+        if error <= TOLERANCE * integral.abs() {
+            break;
+        }
+    }
+
+    // the terms of a divergent integral do not vanish at the bounds
+    let tolerance = TOLERANCE * integral.abs();
+    let edge = (h * at(MAX_TANH_SINH) * half).abs();
+    let converged = error <= tolerance && edge <= tolerance && !singular;
+
+    (integral, error.max(edge), converged)
+}
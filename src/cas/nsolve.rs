@@ -0,0 +1,497 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::{number::Number, symbol::Symbol},
+        Expr, TypeErr,
+    },
+};
+
+/// Steps of Newton's method, before it gives up on a guess.
+const MAX_NEWTON: usize = 100;
+
+/// How often a Newton step is halved, when it does not decrease the residual.
+const MAX_DAMPING: usize = 30;
+
+/// Steps of Brent's method, which always converges on a bracket.
+const MAX_BRENT: usize = 200;
+
+/// QR iterations per eigenvalue of the companion matrix.
+const MAX_QR: usize = 60;
+
+/// Relative imaginary part below which an eigenvalue counts as a real, multiple root.
+const MAX_IMAGINARY: f64 = 1e-7;
+
+/// Numeric roots, each returned with its residual `|f(x)|`.
+impl Expr {
+    /// `nsolve(f, x, guess)` finds a root of `f = 0` by Newton's method,
+    /// `nsolve(f, x, a, b)` one in between `a` and `b` by Brent's method and
+    /// `nsolve(p, x)` all real roots of a polynomial by the eigenvalues of its companion matrix.
+    /// The result is the list `root, residual`, where root may be a list of roots.
+    pub(crate) fn nsolve(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (expr, var, bounds) = match args {
+            [expr, var, bounds @ ..] if bounds.len() <= 2 => (expr, var.symbol()?, bounds),
+            _ => return Err(TypeErr(
+                "call `nsolve` undefined on (expr, ...), expected (expr, symbol, number, number)"
+                    .to_string(),
+            )),
+        };
+
+        let expr = expr.eval(env)?;
+        let f = |x: f64| expr.float_at(&var, x).unwrap_or(f64::NAN);
+        if expr.float_at(&var, 0.5).is_none() {
+            return Err(TypeErr(format!(
+                "`{}` has other variables than `{}` or unknown functions",
+                expr.tidy(),
+                var.0
+            )));
+        }
+
+        let root = match bounds {
+            [] => return polynomial_roots(&expr, &var),
+            [guess] => {
+                let derivative = expr.derive(&var).ok();
+                let df = |x: f64| match derivative.as_ref().and_then(|df| df.float_at(&var, x)) {
+                    Some(df) => df,
+                    None => central_difference(&f, x),
+                };
+                newton(&f, &df, guess.number(env)?.as_f64())
+            }
+            [a, b] => brent(&f, a.number(env)?.as_f64(), b.number(env)?.as_f64()),
+            _ => unreachable!("at most two bounds"),
+        }
+        .ok_or_else(|| TypeErr(format!("could not find a root of `{}`", expr.tidy())))?;
+
+        Ok(Expr::from_list(vec![
+            Expr::num(Number::Float(root)),
+            Expr::num(Number::Float(f(root).abs())),
+        ]))
+    }
+}
+
+/// Roots of a polynomial with number coefficients, sorted and with the largest residual.
+fn polynomial_roots(expr: &Expr, var: &Symbol) -> Result<Expr, TypeErr> {
+    let coeffs = expr
+        .coeffs(var)
+        .and_then(|coeffs| coeffs.iter().map(Expr::float).collect::<Option<Vec<_>>>())
+        .filter(|coeffs| coeffs.len() > 1)
+        .ok_or_else(|| {
+            TypeErr(format!(
+                "`{}` is no polynomial in `{}`, so `nsolve` needs a guess",
+                expr.tidy(),
+                var.0
+            ))
+        })?;
+
+    // x = 0 is a root of every polynomial without a constant term
+    let zeros = coeffs.iter().take_while(|c| **c == 0.0).count();
+    let mut roots = vec![0.0; zeros];
+    roots.extend(
+        companion_eigenvalues(&coeffs[zeros..])
+            .into_iter()
+            .filter(|(re, im)| im.abs() <= MAX_IMAGINARY * re.abs().max(1.0))
+            .map(|(re, _)| polish(&coeffs, re)),
+    );
+    roots.sort_by(f64::total_cmp);
+
+    if roots.is_empty() {
+        return Err(TypeErr(format!("`{}` has no real roots", expr.tidy())));
+    }
+
+    let residual = roots
+        .iter()
+        .map(|x| horner(&coeffs, *x).0.abs())
+        .fold(0.0, f64::max);
+    let roots = roots
+        .into_iter()
+        .map(|x| Expr::num(Number::Float(x)))
+        .collect::<Vec<_>>();
+
+    Ok(Expr::from_list(vec![
+        if roots.len() == 1 {
+            roots[0].clone()
+        } else {
+            Expr::from_list(roots)
+        },
+        Expr::num(Number::Float(residual)),
+    ]))
+}
+
+/// Newton's method, damped so that every step decreases `|f|`.
+fn newton(f: &impl Fn(f64) -> f64, df: &impl Fn(f64) -> f64, guess: f64) -> Option<f64> {
+    let mut x = guess;
+    let mut fx = f(x);
+
+    for _ in 0..MAX_NEWTON {
+        if fx == 0.0 {
+            return Some(x);
+        }
+
+        let mut step = fx / df(x);
+        if !step.is_finite() {
+            return None;
+        }
+
+        // halve the step, until it is an improvement
+        let mut next = x - step;
+        let mut f_next = f(next);
+        for _ in 0..MAX_DAMPING {
+            if f_next.abs() < fx.abs() {
+                break;
+            }
+            step /= 2.0;
+            next = x - step;
+            f_next = f(next);
+        }
+
+        let converged = (next - x).abs() <= 4.0 * f64::EPSILON * next.abs().max(f64::MIN_POSITIVE);
+        x = next;
+        fx = f_next;
+        if converged {
+            break;
+        }
+    }
+
+    // a stalled iteration is only a root, if the next step would be tiny on the scale of
+    // the problem, and not like for `exp(x)` running off with steps of 1
+    let scale = x.abs().max(guess.abs());
+    let tolerance = df(x).abs() * scale * f64::EPSILON.sqrt();
+    (x.is_finite() && fx.abs() <= tolerance).then_some(x)
+}
+
+fn central_difference(f: &impl Fn(f64) -> f64, x: f64) -> f64 {
+    let h = f64::EPSILON.cbrt() * x.abs().max(1.0);
+    (f(x + h) - f(x - h)) / (2.0 * h)
+}
+
+/// Brent's method of bisection, secants and inverse quadratic interpolation,
+/// which needs a sign change between the bounds.
+fn brent(f: &impl Fn(f64) -> f64, a: f64, b: f64) -> Option<f64> {
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f(a), f(b));
+    if fa == 0.0 {
+        return Some(a);
+    }
+    if fb == 0.0 {
+        return Some(b);
+    }
+    if fa.signum() == fb.signum() || fa.is_nan() || fb.is_nan() {
+        return None;
+    }
+
+    let (mut c, mut fc) = (b, fb);
+    let (mut d, mut e) = (0.0, 0.0);
+
+    for _ in 0..MAX_BRENT {
+        // keep the root between b and c
+        if fb.signum() == fc.signum() {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+
+        let tolerance = 2.0 * f64::EPSILON * b.abs() + f64::MIN_POSITIVE;
+        let middle = (c - b) / 2.0;
+        if middle.abs() <= tolerance || fb == 0.0 {
+            return Some(b);
+        }
+
+        if e.abs() >= tolerance && fa.abs() > fb.abs() {
+            // interpolate, with a secant if only two points are known
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2.0 * middle * s, 1.0 - s)
+            } else {
+                let (q, r) = (fa / fc, fb / fc);
+                (
+                    s * (2.0 * middle * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            }
+            p = p.abs();
+
+            if 2.0 * p < (3.0 * middle * q - (tolerance * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                d = middle;
+                e = d;
+            }
+        } else {
+            d = middle;
+            e = d;
+        }
+
+        a = b;
+        fa = fb;
+        b += if d.abs() > tolerance {
+            d
+        } else {
+            tolerance.copysign(middle)
+        };
+        fb = f(b);
+    }
+
+    None
+}
+
+/// Value and derivative of a polynomial.
+fn horner(coeffs: &[f64], x: f64) -> (f64, f64) {
+    coeffs
+        .iter()
+        .rev()
+        .fold((0.0, 0.0), |(p, dp), c| (p * x + c, dp * x + p))
+}
+
+/// A few Newton steps on an eigenvalue, as long as they decrease the residual.
+fn polish(coeffs: &[f64], mut x: f64) -> f64 {
+    let mut residual = horner(coeffs, x).0.abs();
+
+    for _ in 0..MAX_DAMPING {
+        let (p, dp) = horner(coeffs, x);
+        let next = x - p / dp;
+        let next_residual = horner(coeffs, next).0.abs();
+
+        if next_residual >= residual || next_residual.is_nan() {
+            break;
+        }
+        x = next;
+        residual = next_residual;
+    }
+
+    x
+}
+
+/// Eigenvalues `(re, im)` of the companion matrix of a polynomial with a non-zero constant term.
+pub(crate) fn companion_eigenvalues(coeffs: &[f64]) -> Vec<(f64, f64)> {
+    let n = coeffs.len() - 1;
+    let lead = coeffs[n];
+
+    // upper Hessenberg with the monic coefficients in the first row
+    let mut a = vec![vec![0.0; n]; n];
+    for j in 0..n {
+        a[0][j] = -coeffs[n - 1 - j] / lead;
+    }
+    for i in 1..n {
+        a[i][i - 1] = 1.0;
+    }
+
+    balance(&mut a);
+    hessenberg_eigenvalues(a)
+}
+
+/// Scale rows and columns by powers of two, so that their norms are about equal.
+fn balance(a: &mut [Vec<f64>]) {
+    let n = a.len();
+    let mut done = false;
+
+    while !done {
+        done = true;
+        for i in 0..n {
+            let mut c: f64 = a
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, row)| row[i].abs())
+                .sum();
+            let r: f64 = a[i]
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, x)| x.abs())
+                .sum();
+            if c == 0.0 || r == 0.0 {
+                continue;
+            }
+
+            let (sum, mut f) = (c + r, 1.0);
+            while c < r / 2.0 {
+                f *= 2.0;
+                c *= 4.0;
+            }
+            while c > r * 2.0 {
+                f /= 2.0;
+                c /= 4.0;
+            }
+
+            if (c + r) / f < 0.95 * sum {
+                done = false;
+                a[i].iter_mut().for_each(|x| *x /= f);
+                a.iter_mut().for_each(|row| row[i] *= f);
+            }
+        }
+    }
+}
+
+/// Eigenvalues of an upper Hessenberg matrix by the shifted QR algorithm of Francis.
+fn hessenberg_eigenvalues(mut a: Vec<Vec<f64>>) -> Vec<(f64, f64)> {
+    let mut eigenvalues = Vec::with_capacity(a.len());
+    let norm: f64 = (0..a.len())
+        .flat_map(|i| (i.saturating_sub(1)..a.len()).map(move |j| (i, j)))
+        .map(|(i, j)| a[i][j].abs())
+        .sum();
+
+    // rows above size are not deflated yet
+    let mut size = a.len();
+    let mut shift = 0.0;
+
+    while size > 0 {
+        let nn = size - 1;
+        let mut iterations = 0;
+
+        loop {
+            // look for a single small subdiagonal element
+            let mut l = nn;
+            while l > 0 {
+                let s = match a[l - 1][l - 1].abs() + a[l][l].abs() {
+                    0.0 => norm,
+                    s => s,
+                };
+                if a[l][l - 1].abs() + s == s {
+                    a[l][l - 1] = 0.0;
+                    break;
+                }
+                l -= 1;
+            }
+
+            let mut x = a[nn][nn];
+            if l == nn {
+                eigenvalues.push((x + shift, 0.0));
+                size -= 1;
+                break;
+            }
+
+            let mut y = a[nn - 1][nn - 1];
+            let mut w = a[nn][nn - 1] * a[nn - 1][nn];
+            if l == nn - 1 {
+                // the last 2x2 block has two real or a pair of complex eigenvalues
+                let p = (y - x) / 2.0;
+                let q = p * p + w;
+                let z = q.abs().sqrt();
+                x += shift;
+                if q >= 0.0 {
+                    let z = p + z.copysign(p);
+                    eigenvalues.push((x + z, 0.0));
+                    eigenvalues.push((if z == 0.0 { x + z } else { x - w / z }, 0.0));
+                } else {
+                    eigenvalues.push((x + p, -z));
+                    eigenvalues.push((x + p, z));
+                }
+                size -= 2;
+                break;
+            }
+
+            if iterations == MAX_QR {
+                return eigenvalues;
+            }
+            if iterations % 10 == 9 {
+                // exceptional shift against cycles
+                shift += x;
+                for (i, row) in a.iter_mut().enumerate().take(nn + 1) {
+                    row[i] -= x;
+                }
+                let s = a[nn][nn - 1].abs() + a[nn - 1][nn - 2].abs();
+                x = 0.75 * s;
+                y = x;
+                w = -0.4375 * s * s;
+            }
+            iterations += 1;
+
+            // form the double shift and look for two consecutive small subdiagonal elements
+            let mut m = nn - 2;
+            let (mut p, mut q, mut r);
+            loop {
+                let z = a[m][m];
+                let (r0, s0) = (x - z, y - z);
+                p = (r0 * s0 - w) / a[m + 1][m] + a[m][m + 1];
+                q = a[m + 1][m + 1] - z - r0 - s0;
+                r = a[m + 2][m + 1];
+                let s = p.abs() + q.abs() + r.abs();
+                p /= s;
+                q /= s;
+                r /= s;
+                if m == l {
+                    break;
+                }
+                let u = a[m][m - 1].abs() * (q.abs() + r.abs());
+                let v = p.abs() * (a[m - 1][m - 1].abs() + z.abs() + a[m + 1][m + 1].abs());
+                if u + v == v {
+                    break;
+                }
+                m -= 1;
+            }
+            for i in m + 2..=nn {
+                a[i][i - 2] = 0.0;
+                if i != m + 2 {
+                    a[i][i - 3] = 0.0;
+                }
+            }
+
+            // double QR step on rows l to nn and columns m to nn
+            for k in m..nn {
+                if k != m {
+                    p = a[k][k - 1];
+                    q = a[k + 1][k - 1];
+                    r = if k != nn - 1 { a[k + 2][k - 1] } else { 0.0 };
+                    x = p.abs() + q.abs() + r.abs();
+                    if x != 0.0 {
+                        p /= x;
+                        q /= x;
+                        r /= x;
+                    }
+                }
+
+                let s = (p * p + q * q + r * r).sqrt().copysign(p);
+                if s == 0.0 {
+                    continue;
+                }
+                if k == m {
+                    if l != m {
+                        a[k][k - 1] = -a[k][k - 1];
+                    }
+                } else {
+                    a[k][k - 1] = -s * x;
+                }
+
+                p += s;
+                x = p / s;
+                y = q / s;
+                let z = r / s;
+                q /= p;
+                r /= p;
+
+                (k..=nn).for_each(|j| {
+                    let mut p = a[k][j] + q * a[k + 1][j];
+                    if k != nn - 1 {
+                        p += r * a[k + 2][j];
+                        a[k + 2][j] -= p * z;
+                    }
+                    a[k + 1][j] -= p * y;
+                    a[k][j] -= p * x;
+                });
+                for row in a.iter_mut().take(nn.min(k + 3) + 1).skip(l) {
+                    let mut p = x * row[k] + y * row[k + 1];
+                    if k != nn - 1 {
+                        p += z * row[k + 2];
+                        row[k + 2] -= p * r;
+                    }
+                    row[k + 1] -= p * q;
+                    row[k] -= p;
+                }
+            }
+        }
+    }
+
+    eigenvalues
+}
@@ -0,0 +1,446 @@
+use {
+    super::{
+        env::Env,
+        expr::{
+            atom::{number::gcd, symbol::Symbol, Atom},
+            Expr, TypeErr,
+        },
+    },
+    std::convert::TryFrom,
+};
+
+/// Bases of Miller-Rabin, which are enough to be deterministic below `3.3 * 10^24`.
+const WITNESSES: [u128; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// More bases for larger numbers, where the test is only probabilistic.
+const MORE_WITNESSES: [u128; 7] = [43, 47, 53, 59, 61, 67, 71];
+
+/// Integer functions, exact on the whole range of `i128`.
+impl Expr {
+    pub(crate) fn isprime(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let [n] = integers::<1>("isprime", args, env)?;
+        Ok(boolean(n > 1 && is_prime(n as u128)))
+    }
+
+    /// The smallest prime above n.
+    pub(crate) fn nextprime(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let [n] = integers::<1>("nextprime", args, env)?;
+        let mut candidate = n.max(1);
+
+        loop {
+            candidate = candidate
+                .checked_add(1)
+                .ok_or_else(|| TypeErr(format!("no prime above `{}` fits", n)))?;
+            if is_prime(candidate as u128) {
+                return Ok(Expr::int(candidate));
+            }
+        }
+    }
+
+    /// `factorint(n)` is the list of prime factors with their multiplicity `p, k; ...`,
+    /// where the empty product of `factorint(1)` is `1, 1`.
+    pub(crate) fn factorint(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let [n] = integers::<1>("factorint", args, env)?;
+        if n == 0 {
+            return Err(TypeErr("`0` has no prime factors".to_string()));
+        }
+
+        let mut factors: Vec<Expr> = factor(n.unsigned_abs())
+            .into_iter()
+            .map(|(prime, exp)| Expr::from_list(vec![Expr::int(prime as i128), Expr::int(exp)]))
+            .collect();
+        if n < 0 {
+            factors.insert(0, Expr::from_list(vec![Expr::int(-1), Expr::int(1)]));
+        }
+        if factors.is_empty() {
+            factors.push(Expr::from_list(vec![Expr::int(1), Expr::int(1)]));
+        }
+
+        Ok(Expr::from_list(factors))
+    }
+
+    pub(crate) fn gcd(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let numbers = integer_list("gcd", args, env)?;
+
+        let mut result: u128 = 0;
+        for n in numbers {
+            if result != 0 || n != 0 {
+                // `2^127` wraps to `i128::MIN`, which has the same divisors
+                result = gcd(result as i128, n);
+            }
+        }
+
+        i128::try_from(result)
+            .map(Expr::int)
+            .map_err(|_| TypeErr("gcd does not fit into an integer".to_string()))
+    }
+
+    pub(crate) fn lcm(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let numbers = integer_list("lcm", args, env)?;
+
+        let mut lcm: i128 = 1;
+        for n in numbers {
+            if n == 0 {
+                return Ok(Expr::int(0));
+            }
+            lcm = n
+                .checked_abs()
+                .and_then(|n| (lcm / gcd(lcm, n) as i128).checked_mul(n))
+                .ok_or_else(|| TypeErr("lcm does not fit into an integer".to_string()))?;
+        }
+
+        Ok(Expr::int(lcm))
+    }
+
+    /// `egcd(a, b)` is the list `g, x, y` with `a x + b y = g = gcd(a, b)`.
+    pub(crate) fn egcd(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let [a, b] = integers::<2>("egcd", args, env)?;
+        if i128::try_from(gcd(a, b)).is_err() {
+            return Err(TypeErr("gcd does not fit into an integer".to_string()));
+        }
+        let (g, x, y) = egcd(a, b);
+
+        Ok(Expr::from_list(vec![
+            Expr::int(g),
+            Expr::int(x),
+            Expr::int(y),
+        ]))
+    }
+
+    /// `powmod(b, e, m)` is `b^e mod m`, negative exponents use the inverse.
+    pub(crate) fn powmod(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let [base, exp, modulus] = integers::<3>("powmod", args, env)?;
+        let modulus = positive(modulus)?;
+
+        let base = if exp < 0 {
+            inverse(base, modulus)?
+        } else {
+            base.rem_euclid(modulus as i128) as u128
+        };
+
+        Ok(Expr::int(pow_mod(base, exp.unsigned_abs(), modulus) as i128))
+    }
+
+    pub(crate) fn modinv(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let [a, modulus] = integers::<2>("modinv", args, env)?;
+        Ok(Expr::int(inverse(a, positive(modulus)?)? as i128))
+    }
+
+    /// Euler's totient, the number of coprimes up to n.
+    pub(crate) fn totient(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let [n] = integers::<1>("totient", args, env)?;
+        let n = positive(n)?;
+
+        Ok(Expr::int(
+            factor(n)
+                .into_iter()
+                .fold(n, |phi, (prime, _)| phi / prime * (prime - 1)) as i128,
+        ))
+    }
+
+    /// All positive divisors in ascending order.
+    pub(crate) fn divisors(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let [n] = integers::<1>("divisors", args, env)?;
+        let n = positive(n.checked_abs().ok_or_else(|| {
+            TypeErr(format!(
+                "the divisor `{}` of `{}` does not fit into an integer",
+                n.unsigned_abs(),
+                n
+            ))
+        })?)?;
+
+        let mut divisors = vec![1_u128];
+        for (prime, exp) in factor(n) {
+            let mut more = Vec::new();
+            for divisor in &divisors {
+                let mut power = *divisor;
+                for _ in 0..exp {
+                    power *= prime;
+                    more.push(power);
+                }
+            }
+            divisors.extend(more);
+        }
+        divisors.sort_unstable();
+
+        Ok(Expr::from_list(
+            divisors
+                .into_iter()
+                .map(|divisor| Expr::int(divisor as i128))
+                .collect(),
+        ))
+    }
+
+    /// `crt(r1, m1, r2, m2, ...)` solves `x = r1 mod m1, x = r2 mod m2, ...`,
+    /// the moduli need not be coprime.
+    pub(crate) fn crt(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let numbers = integer_list("crt", args, env)?;
+        if numbers.len() % 2 != 0 || numbers.is_empty() {
+            return Err(TypeErr(
+                "call `crt` undefined on (expr, ...), expected (integer, integer, ...)".to_string(),
+            ));
+        }
+
+        let (mut x, mut m) = (0_i128, 1_i128);
+        for pair in numbers.chunks(2) {
+            let (r, n) = (pair[0], positive(pair[1])? as i128);
+
+            // x + m k = r mod n
+            let (g, p, _) = egcd(m, n);
+            if (r - x) % g != 0 {
+                return Err(TypeErr(format!(
+                    "`{} mod {}` contradicts `{} mod {}`",
+                    r, n, x, m
+                )));
+            }
+
+            let lcm = (m / g)
+                .checked_mul(n)
+                .ok_or_else(|| TypeErr("moduli do not fit into an integer".to_string()))?;
+            let k = mul_mod(
+                ((r - x) / g).rem_euclid(n / g) as u128,
+                p.rem_euclid(n / g) as u128,
+                (n / g) as u128,
+            ) as i128;
+
+            x = (x + mul_mod(m as u128, k as u128, lcm as u128) as i128).rem_euclid(lcm);
+            m = lcm;
+        }
+
+        Ok(Expr::int(x))
+    }
+}
+
+fn boolean(value: bool) -> Expr {
+    Expr::Atom(Atom::Symbol(Symbol(value.to_string())))
+}
+
+/// The evaluated arguments, which have to be exact integers.
+pub(crate) fn integer_list(call: &str, args: &[Expr], env: &mut Env) -> Result<Vec<i128>, TypeErr> {
+    let mut numbers = Vec::with_capacity(args.len());
+
+    for arg in args {
+        let value = arg.eval(env)?;
+        match value.as_number().and_then(|number| number.as_int()) {
+            Some(n) => numbers.push(n),
+            None => {
+                return Err(TypeErr(format!(
+                    "call `{}` expected integer, found `{}`",
+                    call, value
+                )))
+            }
+        }
+    }
+
+    Ok(numbers)
+}
+
+pub(crate) fn integers<const N: usize>(
+    call: &str,
+    args: &[Expr],
+    env: &mut Env,
+) -> Result<[i128; N], TypeErr> {
+    let numbers = integer_list(call, args, env)?;
+
+    <[i128; N]>::try_from(numbers).map_err(|_| {
+        TypeErr(format!(
+            "call `{}` undefined on (expr, ...), expected {} integers",
+            call, N
+        ))
+    })
+}
+
+fn positive(n: i128) -> Result<u128, TypeErr> {
+    if n > 0 {
+        Ok(n as u128)
+    } else {
+        Err(TypeErr(format!("expected positive integer, found `{}`", n)))
+    }
+}
+
+/// `(g, x, y)` with `a x + b y = g`, for a gcd that fits.
+fn egcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1_i128, 0_i128);
+    let (mut old_y, mut y) = (0_i128, 1_i128);
+
+    // the coefficients of the last step are `b / g` and `a / g`, which may not fit,
+    // but the result does, so it is exact modulo `2^128`
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x.wrapping_sub(q.wrapping_mul(x)));
+        (old_y, y) = (y, old_y.wrapping_sub(q.wrapping_mul(y)));
+    }
+
+    if old_r < 0 {
+        (-old_r, old_x.wrapping_neg(), old_y.wrapping_neg())
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+fn inverse(a: i128, modulus: u128) -> Result<u128, TypeErr> {
+    let (g, x, _) = egcd(a.rem_euclid(modulus as i128), modulus as i128);
+
+    if g == 1 {
+        Ok(x.rem_euclid(modulus as i128) as u128)
+    } else {
+        Err(TypeErr(format!(
+            "`{}` has no inverse modulo `{}`",
+            a, modulus
+        )))
+    }
+}
+
+/// `a b mod m` without overflowing, by doubling if the product is too large.
+fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    let (mut a, mut b) = (a % m, b % m);
+
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+
+    let mut product = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            product = add_mod(product, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+
+    product
+}
+
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    match a.checked_add(b) {
+        Some(sum) => sum % m,
+        None => a.wrapping_add(b).wrapping_sub(m),
+    }
+}
+
+fn pow_mod(mut base: u128, mut exp: u128, m: u128) -> u128 {
+    let mut power = 1 % m;
+    base %= m;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            power = mul_mod(power, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+
+    power
+}
+
+/// Miller-Rabin, deterministic below `3.3 * 10^24`.
+fn is_prime(n: u128) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for prime in WITNESSES.iter().chain(MORE_WITNESSES.iter()) {
+        if n.is_multiple_of(*prime) {
+            return n == *prime;
+        }
+    }
+
+    let zeros = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> zeros;
+
+    WITNESSES
+        .iter()
+        .chain(MORE_WITNESSES.iter())
+        .all(|witness| {
+            let mut x = pow_mod(*witness, odd, n);
+            if x == 1 || x == n - 1 {
+                return true;
+            }
+
+            for _ in 1..zeros {
+                x = mul_mod(x, x, n);
+                if x == n - 1 {
+                    return true;
+                }
+            }
+
+            false
+        })
+}
+
+/// Prime factors in ascending order with their multiplicity.
+fn factor(n: u128) -> Vec<(u128, i128)> {
+    let mut primes = Vec::new();
+    let mut n = n;
+
+    // small factors first, Pollard's rho is slow for them
+    for prime in WITNESSES.iter().chain(MORE_WITNESSES.iter()) {
+        while n.is_multiple_of(*prime) {
+            primes.push(*prime);
+            n /= prime;
+        }
+    }
+    split(n, &mut primes);
+
+    primes.sort_unstable();
+    let mut factors: Vec<(u128, i128)> = Vec::new();
+    for prime in primes {
+        match factors.last_mut() {
+            Some((last, exp)) if *last == prime => *exp += 1,
+            _ => factors.push((prime, 1)),
+        }
+    }
+
+    factors
+}
+
+fn split(n: u128, primes: &mut Vec<u128>) {
+    if n == 1 {
+        return;
+    }
+    if is_prime(n) {
+        primes.push(n);
+        return;
+    }
+
+    let divisor = (1..)
+        .find_map(|c| rho(n, c))
+        .expect("a composite number has a divisor");
+    split(divisor, primes);
+    split(n / divisor, primes);
+}
+
+/// Pollard's rho with Brent's cycle detection, which may fail for an unlucky constant.
+fn rho(n: u128, c: u128) -> Option<u128> {
+    let f = |x: u128| add_mod(mul_mod(x, x, n), c, n);
+
+    let (mut x, mut y) = (2, 2);
+    let mut power = 1;
+    let mut length = 1;
+
+    loop {
+        if power == length {
+            x = y;
+            power *= 2;
+            length = 0;
+        }
+        y = f(y);
+        length += 1;
+
+        let divisor = gcd_u128(x.abs_diff(y), n);
+        if divisor == n {
+            return None;
+        }
+        if divisor > 1 {
+            return Some(divisor);
+        }
+    }
+}
+
+fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
@@ -0,0 +1,967 @@
+use {
+    super::{
+        env::Env,
+        expr::{
+            atom::{
+                number::{gcd, Number},
+                symbol::Symbol,
+                Atom,
+            },
+            call::{op::Op, Call},
+            Expr, TypeErr,
+        },
+        nsolve::companion_eigenvalues,
+    },
+    std::convert::TryFrom,
+};
+
+/// Largest leading or constant coefficient, whose divisors are tried as rational roots.
+const MAX_RATIONAL_ROOT: i128 = 1_000_000;
+
+/// Samples after the start of `odesolve`, if their number is not given.
+const SAMPLES: i128 = 10;
+
+/// Steps of `odesolve` in between two samples, before it gives up.
+const MAX_STEPS: usize = 100_000;
+
+/// Relative accuracy of every step of `odesolve`.
+const TOLERANCE: f64 = 1e-10;
+
+/// Nodes of the Dormand-Prince method.
+const NODES: [f64; 7] = [0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+
+/// Coefficients of the Dormand-Prince method, where the last row is the solution of order 5.
+const STAGES: [[f64; 6]; 6] = [
+    [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+    [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+    [
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+        0.0,
+        0.0,
+    ],
+    [
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+        0.0,
+    ],
+    [
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+
+/// Difference of the solutions of order 5 and 4, which estimates the error of a step.
+const ERROR: [f64; 7] = [
+    35.0 / 384.0 - 5179.0 / 57600.0,
+    0.0,
+    500.0 / 1113.0 - 7571.0 / 16695.0,
+    125.0 / 192.0 - 393.0 / 640.0,
+    -2187.0 / 6784.0 + 92097.0 / 339_200.0,
+    11.0 / 84.0 - 187.0 / 2100.0,
+    -1.0 / 40.0,
+];
+
+/// A root `re + i im` of a characteristic polynomial, complex roots only with `im > 0`.
+pub(crate) struct Root {
+    pub(crate) re: Expr,
+    pub(crate) im: Expr,
+    pub(crate) multiplicity: usize,
+}
+
+/// Exact complex numbers, to find particular solutions of `exp`, `sin` and `cos`.
+#[derive(Clone)]
+struct Complex(Number, Number);
+
+/// Ordinary differential equations, symbolic in closed form or numeric as a table.
+impl Expr {
+    /// `dsolve(y'' + y = 0, y(x))` is the general solution `y = ...` with constants `C1, C2, ...`.
+    /// Linear equations with constant coefficients may have any order, equations of
+    /// first order may be linear, separable or exact, where the solution may stay implicit.
+    pub(crate) fn dsolve(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (equation, y, x) = match args {
+            [equation, Expr::Call(Call { op: Op::Mul, args })] if args.len() == 2 => {
+                (equation, args[0].symbol()?, args[1].symbol()?)
+            }
+            _ => {
+                return Err(TypeErr(
+                    "call `dsolve` undefined on (expr, ...), expected (equation, y(x))".to_string(),
+                ))
+            }
+        };
+        let expr = match equation {
+            Expr::Call(Call { op: Op::Eq, args }) if args.len() == 2 => {
+                Expr::sub(args[0].eval(env)?, args[1].eval(env)?)
+            }
+            _ => equation.eval(env)?,
+        };
+
+        // the derivatives become symbols `y'`, `y''`, ...
+        let mut order = 0;
+        let expr = unprime(&expr, &y, &mut order)?.expand();
+        if order == 0 {
+            return Err(TypeErr(format!(
+                "`{}` has no derivative of `{}`",
+                expr.tidy(),
+                y.0
+            )));
+        }
+        let ys: Vec<Symbol> = (0..=order)
+            .map(|k| Symbol(format!("{}{}", y.0, "'".repeat(k))))
+            .collect();
+
+        let solution = match linear(&expr, &ys) {
+            Some((coeffs, rest)) if coeffs.iter().all(|coeff| !coeff.contains(&x)) => {
+                Some(constant_coefficients(&coeffs, &rest, &x)?)
+            }
+            Some((coeffs, rest)) if order == 1 => Some(first_order_linear(&coeffs, &rest, &x)?),
+            _ if order == 1 => separable(&expr, &ys, &x).or_else(|| exact(&expr, &ys, &x)),
+            _ => None,
+        };
+
+        match solution {
+            // explicit solutions are `y = ...`
+            Some(Expr::Call(Call { op: Op::Eq, args })) => Ok(Expr::Call(Call::new(Op::Eq, args))),
+            Some(solution) => Ok(Expr::Call(Call::new(
+                Op::Eq,
+                vec![Expr::Atom(Atom::Symbol(y)), solution],
+            ))),
+            None => Err(TypeErr(format!(
+                "could not solve `{} = 0` for `{}`",
+                expr.tidy(),
+                y.0
+            ))),
+        }
+    }
+
+    /// `odesolve(f, y, y0, t, t0, t1, n)` solves `y' = f(t, y)` with `y(t0) = y0`
+    /// by the adaptive Runge-Kutta method of Dormand and Prince. `f`, `y` and `y0` may
+    /// be lists of a system. The result is a table of `n + 1` rows `t, y` from `t0` to `t1`.
+    pub(crate) fn odesolve(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (rates, vars, start, t, t0, t1, samples) = match args {
+            [rates, vars, start, t, t0, t1, rest @ ..] if rest.len() <= 1 => (
+                rates,
+                vars,
+                start,
+                t.symbol()?,
+                t0.number(env)?.as_f64(),
+                t1.number(env)?.as_f64(),
+                match rest {
+                    [n] => n
+                        .eval(env)?
+                        .as_number()
+                        .and_then(|n| n.as_int())
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| TypeErr(format!("`{}` is no positive integer", n)))?,
+                    _ => SAMPLES,
+                },
+            ),
+            _ => {
+                return Err(TypeErr(
+                    "call `odesolve` undefined on (expr, ...), expected (expr, symbol, number, symbol, number, number, integer)"
+                        .to_string(),
+                ))
+            }
+        };
+
+        // `y' = f` or just `f`
+        let mut equations = Vec::new();
+        for rate in rates.clone().list() {
+            equations.push(match rate {
+                Expr::Call(Call { op: Op::Eq, args }) if args.len() == 2 => args[1].eval(env)?,
+                rate => rate.eval(env)?,
+            });
+        }
+        let vars = vars
+            .clone()
+            .list()
+            .iter()
+            .map(Expr::symbol)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut y = Vec::new();
+        for value in start.eval(env)?.list() {
+            y.push(value.number(env)?.as_f64());
+        }
+        if equations.len() != vars.len() || vars.len() != y.len() {
+            return Err(TypeErr(format!(
+                "call `odesolve` expected as many equations as variables and values, found {}, {} and {}",
+                equations.len(),
+                vars.len(),
+                y.len()
+            )));
+        }
+
+        let rate = |t_value: f64, y: &[f64]| {
+            let mut values = vec![(&t, t_value)];
+            values.extend(vars.iter().zip(y.iter().copied()));
+            equations
+                .iter()
+                .map(|equation| equation.float_with(&values))
+                .collect::<Option<Vec<_>>>()
+        };
+        if rate(t0, &y).is_none() {
+            return Err(TypeErr(format!(
+                "`{}` has other variables than `{}` and `{}`",
+                Expr::from_list(equations.clone()).tidy(),
+                t.0,
+                Expr::from_list(
+                    vars.iter()
+                        .cloned()
+                        .map(|var| Expr::Atom(Atom::Symbol(var)))
+                        .collect()
+                )
+                .tidy()
+            )));
+        }
+
+        let row = |t: f64, y: &[f64]| {
+            Expr::from_list(
+                std::iter::once(t)
+                    .chain(y.iter().copied())
+                    .map(|value| Expr::num(Number::Float(value)))
+                    .collect(),
+            )
+        };
+        let mut rows = vec![row(t0, &y)];
+        let (mut time, mut h) = (t0, (t1 - t0) / (samples as f64 * 10.0));
+
+        for k in 1..=samples {
+            let target = t0 + (t1 - t0) * k as f64 / samples as f64;
+            let mut steps = 0;
+
+            while (target - time) * h.signum() > 0.0 {
+                steps += 1;
+                if steps > MAX_STEPS || time + h == time {
+                    return Err(TypeErr(format!(
+                        "could not integrate beyond `{} = {}`, the equations may be stiff or singular",
+                        t.0, time
+                    )));
+                }
+
+                // land on the sample
+                let step = if (time + h - target) * h.signum() > 0.0 {
+                    target - time
+                } else {
+                    h
+                };
+                let (next, error) = dormand_prince(&rate, time, &y, step).ok_or_else(|| {
+                    TypeErr(format!(
+                        "`{}` is undefined at `{} = {}`",
+                        Expr::from_list(equations.clone()).tidy(),
+                        t.0,
+                        time
+                    ))
+                })?;
+
+                if error <= 1.0 {
+                    time = if step == target - time {
+                        target
+                    } else {
+                        time + step
+                    };
+                    y = next;
+                }
+                h = step * (0.9 * error.powf(-0.2)).clamp(0.2, 5.0);
+            }
+
+            rows.push(row(time, &y));
+        }
+
+        Ok(Expr::from_list(rows))
+    }
+}
+
+impl Complex {
+    fn real(re: Number) -> Complex {
+        Complex(re, Number::int(0))
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_zero() && self.1.is_zero()
+    }
+
+    fn add(&self, rhs: &Complex) -> Complex {
+        let (Complex(a, b), Complex(c, d)) = (self.clone(), rhs.clone());
+        Complex(a + c, b + d)
+    }
+
+    fn mul(&self, rhs: &Complex) -> Complex {
+        let (Complex(a, b), Complex(c, d)) = (self.clone(), rhs.clone());
+        Complex(a.clone() * c.clone() - b.clone() * d.clone(), a * d + b * c)
+    }
+
+    fn div(&self, rhs: &Complex) -> Complex {
+        let (Complex(a, b), Complex(c, d)) = (self.clone(), rhs.clone());
+        let norm = c.clone() * c.clone() + d.clone() * d.clone();
+        Complex(
+            (a.clone() * c.clone() + b.clone() * d.clone()) / norm.clone(),
+            (b * c - a * d) / norm,
+        )
+    }
+}
+
+/// Replace the derivatives of `y` by the symbols `y'`, `y''`, ... and find the highest order.
+fn unprime(expr: &Expr, y: &Symbol, order: &mut usize) -> Result<Expr, TypeErr> {
+    match expr {
+        Expr::Call(Call { op: Op::Prime, .. }) => {
+            let (mut inner, mut k) = (expr, 0);
+            while let Expr::Call(Call {
+                op: Op::Prime,
+                args,
+            }) = inner
+            {
+                inner = &args[0];
+                k += 1;
+            }
+
+            if *inner != Expr::Atom(Atom::Symbol(y.clone())) {
+                return Err(TypeErr(format!("`{}` is no derivative of `{}`", expr, y.0)));
+            }
+
+            *order = (*order).max(k);
+            Ok(Expr::Atom(Atom::Symbol(Symbol(format!(
+                "{}{}",
+                y.0,
+                "'".repeat(k)
+            )))))
+        }
+        Expr::Call(Call { op, args }) => Ok(Expr::rebuild(
+            op,
+            args.iter()
+                .map(|arg| unprime(arg, y, order))
+                .collect::<Result<_, _>>()?,
+        )),
+        _ => Ok(expr.clone()),
+    }
+}
+
+/// Coefficients of `a0 y + a1 y' + ... + rest` and the rest, if the equation is linear.
+fn linear(expr: &Expr, ys: &[Symbol]) -> Option<(Vec<Expr>, Expr)> {
+    let mut rest = expr.clone();
+    let mut coeffs = Vec::with_capacity(ys.len());
+
+    for y in ys {
+        let mut split = rest.coeffs(y)?;
+        match split.len() {
+            1 => coeffs.push(Expr::int(0)),
+            2 => {
+                coeffs.push(split.remove(1));
+                rest = split.remove(0);
+            }
+            _ => return None,
+        }
+    }
+
+    if coeffs
+        .iter()
+        .any(|coeff| ys.iter().any(|y| coeff.contains(y)))
+    {
+        return None;
+    }
+
+    Some((coeffs, rest))
+}
+
+/// `a0 y + a1 y' + ... + an y^(n) + rest = 0` with constant `ak`, by the roots
+/// of the characteristic polynomial and undetermined coefficients for the rest.
+fn constant_coefficients(coeffs: &[Expr], rest: &Expr, x: &Symbol) -> Result<Expr, TypeErr> {
+    let numbers = coeffs
+        .iter()
+        .map(Expr::as_number)
+        .collect::<Option<Vec<_>>>();
+    let roots =
+        match (&numbers, coeffs) {
+            (Some(numbers), _) => numeric_roots(numbers.clone()),
+            (None, [a0, a1]) => group(vec![(
+                Expr::neg(Expr::div(a0.clone(), a1.clone())),
+                Expr::int(0),
+            )]),
+            (None, [a0, a1, a2]) => group(quadratic(a2, a1, a0)),
+            _ => return Err(TypeErr(
+                "characteristic polynomials of symbolic coefficients are only solved up to order 2"
+                    .to_string(),
+            )),
+        };
+
+    let var = Expr::Atom(Atom::Symbol(x.clone()));
+    let mut constants = (1..).map(|i| Expr::Atom(Atom::Symbol(Symbol(format!("C{}", i)))));
+    let mut terms = Vec::new();
+
+    for root in roots {
+        let exp = Expr::function(
+            Symbol("exp".to_string()),
+            Expr::mul(vec![root.re.clone(), var.clone()]),
+        );
+        let angle = Expr::mul(vec![root.im.clone(), var.clone()]);
+
+        for j in 0..root.multiplicity {
+            let power = Expr::pow(var.clone(), Expr::int(j as i128));
+            let mut term = |trig: Option<&str>| {
+                let mut factors = vec![
+                    constants.next().expect("there are always more constants"),
+                    power.clone(),
+                    exp.clone(),
+                ];
+                factors.extend(
+                    trig.map(|name| Expr::function(Symbol(name.to_string()), angle.clone())),
+                );
+                Expr::mul(factors)
+            };
+
+            if root.im.is_zero() {
+                terms.push(term(None));
+            } else {
+                terms.push(term(Some("cos")));
+                terms.push(term(Some("sin")));
+            }
+        }
+    }
+
+    if !rest.is_zero() {
+        let numbers = numbers.ok_or_else(|| {
+            TypeErr("inhomogeneous equations are only solved with number coefficients".to_string())
+        })?;
+        terms.push(particular(&numbers, &Expr::neg(rest.clone()), x)?);
+    }
+
+    Ok(Expr::add(terms))
+}
+
+/// Roots of a polynomial with number coefficients, exact if they are rational or
+/// of a quadratic or biquadratic factor, else numeric.
+pub(crate) fn numeric_roots(mut p: Vec<Number>) -> Vec<Root> {
+    let mut roots = Vec::new();
+
+    while p.len() > 1 && p[0].is_zero() {
+        roots.push((Expr::int(0), Expr::int(0)));
+        p.remove(0);
+    }
+
+    for candidate in rational_candidates(&p) {
+        while p.len() > 1 && horner(&p, candidate.clone()).is_zero() {
+            roots.push((Expr::num(candidate.clone()), Expr::int(0)));
+            p = deflate(&p, candidate.clone());
+        }
+    }
+
+    match &p[..] {
+        [_] => (),
+        [a0, a1] => roots.push((Expr::num(-a0.clone() / a1.clone()), Expr::int(0))),
+        [a0, a1, a2] => roots.extend(quadratic(
+            &Expr::num(a2.clone()),
+            &Expr::num(a1.clone()),
+            &Expr::num(a0.clone()),
+        )),
+        [a0, a1, a2, a3, a4] if a1.is_zero() && a3.is_zero() => {
+            roots.extend(biquadratic(a4.clone(), a2.clone(), a0.clone()))
+        }
+        _ => {
+            let floats: Vec<f64> = p.iter().map(Number::as_f64).collect();
+            roots.extend(
+                companion_eigenvalues(&floats)
+                    .into_iter()
+                    .filter(|(_, im)| *im >= 0.0)
+                    .map(|(re, im)| (Expr::num(Number::Float(re)), Expr::num(Number::Float(im)))),
+            );
+        }
+    }
+
+    group(roots)
+}
+
+/// Roots `p/q` with `p` dividing the constant and `q` the leading coefficient.
+fn rational_candidates(p: &[Number]) -> Vec<Number> {
+    if !p.iter().all(Number::is_exact) {
+        return Vec::new();
+    }
+
+    // integer coefficients, multiplied by the least common denominator
+    let denominator = p.iter().try_fold(1_i128, |lcm, coeff| match coeff {
+        Number::Ratio(_, den) => (lcm / gcd(lcm, *den) as i128).checked_mul(*den),
+        Number::Float(_)
+        | Number::Interval(_, _)
+        | Number::Uncertain(_, _)
+        | Number::Fixed(_, _)
+        | Number::Dual(_, _) => None,
+    });
+    let (first, last) = match denominator {
+        Some(den) => (
+            (p[0].clone() * Number::int(den)).as_int(),
+            (p[p.len() - 1].clone() * Number::int(den)).as_int(),
+        ),
+        None => return Vec::new(),
+    };
+
+    match (first, last) {
+        (Some(first), Some(last))
+            if first.abs() <= MAX_RATIONAL_ROOT && last.abs() <= MAX_RATIONAL_ROOT =>
+        {
+            let mut candidates: Vec<Number> = Vec::new();
+            for numerator in divisors(first) {
+                for denominator in divisors(last) {
+                    for sign in [1, -1].iter() {
+                        let candidate = Number::ratio(sign * numerator, denominator);
+                        if !candidates.contains(&candidate) {
+                            candidates.push(candidate);
+                        }
+                    }
+                }
+            }
+            candidates
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn divisors(n: i128) -> Vec<i128> {
+    let n = n.abs();
+    (1..)
+        .take_while(|d| d * d <= n)
+        .filter(|d| n % d == 0)
+        .flat_map(|d| if d * d == n { vec![d] } else { vec![d, n / d] })
+        .collect()
+}
+
+fn horner(p: &[Number], x: Number) -> Number {
+    p.iter().rev().fold(Number::int(0), |value, coeff| {
+        value * x.clone() + coeff.clone()
+    })
+}
+
+/// Divide a polynomial by `x - root`.
+fn deflate(p: &[Number], root: Number) -> Vec<Number> {
+    let mut quotient = vec![Number::int(0); p.len() - 1];
+    quotient[p.len() - 2] = p[p.len() - 1].clone();
+
+    for k in (1..p.len() - 1).rev() {
+        quotient[k - 1] = p[k].clone() + root.clone() * quotient[k].clone();
+    }
+
+    quotient
+}
+
+/// Roots `(re, im)` of `a x^2 + b x + c`, where a complex pair is only returned once.
+fn quadratic(a: &Expr, b: &Expr, c: &Expr) -> Vec<(Expr, Expr)> {
+    let discriminant = Expr::sub(
+        Expr::pow(b.clone(), Expr::int(2)),
+        Expr::mul(vec![Expr::int(4), a.clone(), c.clone()]),
+    );
+    let twice = Expr::mul(vec![Expr::int(2), a.clone()]);
+    let center = Expr::neg(Expr::div(b.clone(), twice.clone()));
+    let sqrt = |x: Expr| Expr::div(Expr::pow(x, Expr::num(Number::ratio(1, 2))), twice.clone());
+
+    let negative = match discriminant.as_number() {
+        Some(number) => number.is_negative(),
+        None => discriminant.split_coef().0.is_negative(),
+    };
+
+    if discriminant.is_zero() {
+        vec![(center.clone(), Expr::int(0)), (center, Expr::int(0))]
+    } else if negative {
+        vec![(center, sqrt(Expr::neg(discriminant)))]
+    } else {
+        vec![
+            (
+                Expr::add(vec![center.clone(), sqrt(discriminant.clone())]),
+                Expr::int(0),
+            ),
+            (Expr::sub(center, sqrt(discriminant)), Expr::int(0)),
+        ]
+    }
+}
+
+/// Roots `(re, im)` of `a x^4 + b x^2 + c` by the roots `u` of `a u^2 + b u + c` and
+/// `x = ±sqrt u`, so that roots like `sqrt(2)/2 (±1 ± i)` stay exact.
+fn biquadratic(a: Number, b: Number, c: Number) -> Vec<(Expr, Expr)> {
+    let half = || Expr::num(Number::ratio(1, 2));
+    let sqrt = |x: Expr| Expr::pow(x, half());
+    let mut roots = Vec::new();
+
+    for (re, im) in quadratic(&Expr::num(a), &Expr::num(b), &Expr::num(c)) {
+        if !im.is_zero() {
+            // sqrt(re + i im) = p + i q with p = sqrt((|u| + re) / 2) and q = sqrt((|u| - re) / 2)
+            let abs = sqrt(Expr::add(vec![
+                Expr::pow(re.clone(), Expr::int(2)),
+                Expr::pow(im, Expr::int(2)),
+            ]));
+            let p = sqrt(Expr::mul(vec![
+                half(),
+                Expr::add(vec![abs.clone(), re.clone()]),
+            ]));
+            let q = sqrt(Expr::mul(vec![half(), Expr::sub(abs, re)]));
+
+            roots.push((p.clone(), q.clone()));
+            roots.push((Expr::neg(p), q));
+        } else if re.float().is_some_and(|u| u < 0.0) {
+            roots.push((Expr::int(0), sqrt(Expr::neg(re))));
+        } else {
+            let root = sqrt(re);
+            roots.push((root.clone(), Expr::int(0)));
+            roots.push((Expr::neg(root), Expr::int(0)));
+        }
+    }
+
+    roots
+}
+
+/// Count equal roots.
+fn group(roots: Vec<(Expr, Expr)>) -> Vec<Root> {
+    let mut grouped: Vec<Root> = Vec::new();
+
+    for (re, im) in roots {
+        match grouped
+            .iter_mut()
+            .find(|root| root.re == re && root.im == im)
+        {
+            Some(root) => root.multiplicity += 1,
+            None => grouped.push(Root {
+                re,
+                im,
+                multiplicity: 1,
+            }),
+        }
+    }
+
+    grouped
+}
+
+/// A particular solution of `a0 y + a1 y' + ... = forcing`, for terms `c x^j exp(α x)`
+/// times `sin(β x)` or `cos(β x)`. With `λ = α + i β` and the characteristic
+/// polynomial p, it is the real or imaginary part of `exp(λ x) Q(x)`, where
+/// `sum p^(k)(λ) / k! Q^(k) = x^j`.
+fn particular(p: &[Number], forcing: &Expr, x: &Symbol) -> Result<Expr, TypeErr> {
+    let terms = match forcing {
+        Expr::Call(Call { op: Op::Add, args }) => args.clone(),
+        _ => vec![forcing.clone()],
+    };
+    let var = Expr::Atom(Atom::Symbol(x.clone()));
+    let n = p.len() - 1;
+
+    let mut solution = Vec::with_capacity(terms.len());
+    for term in terms {
+        let (coeff, j, alpha, beta, trig) = forcing_term(&term, x).ok_or_else(|| {
+            TypeErr(format!(
+                "could not find a particular solution for `{}`",
+                term.tidy()
+            ))
+        })?;
+        let lambda = Complex(alpha.clone(), beta.clone());
+
+        // Taylor coefficients p^(k)(λ) / k!
+        let taylor: Vec<Complex> = (0..=n)
+            .map(|k| {
+                (k..=n).fold(Complex::real(Number::int(0)), |sum, i| {
+                    let binomial = Number::int(i as i128).binomial(Number::int(k as i128));
+                    let power =
+                        (k..i).fold(Complex::real(Number::int(1)), |power, _| power.mul(&lambda));
+                    sum.add(&Complex::real(p[i].clone() * binomial).mul(&power))
+                })
+            })
+            .collect();
+        // resonance, λ is a root of multiplicity s
+        let s = taylor
+            .iter()
+            .position(|coeff| !coeff.is_zero())
+            .expect("the leading coefficient is not zero");
+
+        // Q = x^s (q0 + q1 x + ... + qj x^j), from the highest power down
+        let falling = |m: usize, k: usize| {
+            Complex::real((m + 1..=m + k).fold(Number::int(1), |product, i| {
+                product * Number::int(i as i128)
+            }))
+        };
+        let mut q = vec![Complex::real(Number::int(0)); j + 1];
+        for m in (0..=j).rev() {
+            let mut rhs = Complex::real(Number::int(if m == j { 1 } else { 0 }));
+            for (k, coeff) in taylor.iter().enumerate().skip(s + 1) {
+                if let Some(known) = q.get(m + k - s) {
+                    let term = coeff.mul(known).mul(&falling(m, k));
+                    rhs = rhs.add(&Complex(-term.0, -term.1));
+                }
+            }
+            q[m] = rhs.div(&taylor[s].mul(&falling(m, s)));
+        }
+
+        let polynomial = |part: fn(&Complex) -> Number| {
+            Expr::add(
+                q.iter()
+                    .enumerate()
+                    .map(|(m, coeff)| {
+                        Expr::mul(vec![
+                            Expr::num(part(coeff)),
+                            Expr::pow(var.clone(), Expr::int((m + s) as i128)),
+                        ])
+                    })
+                    .collect(),
+            )
+        };
+        let (re, im) = (polynomial(|c| c.0.clone()), polynomial(|c| c.1.clone()));
+        let angle = Expr::mul(vec![Expr::num(beta), var.clone()]);
+        let sin = Expr::function(Symbol("sin".to_string()), angle.clone());
+        let cos = Expr::function(Symbol("cos".to_string()), angle);
+
+        // Re(exp(i β x) Q) = Re Q cos - Im Q sin, Im(exp(i β x) Q) = Re Q sin + Im Q cos
+        let oscillation = match trig {
+            None => re,
+            Some(false) => Expr::sub(Expr::mul(vec![re, cos]), Expr::mul(vec![im, sin])),
+            Some(true) => Expr::add(vec![Expr::mul(vec![re, sin]), Expr::mul(vec![im, cos])]),
+        };
+
+        solution.push(Expr::mul(vec![
+            coeff,
+            Expr::function(
+                Symbol("exp".to_string()),
+                Expr::mul(vec![Expr::num(alpha), var.clone()]),
+            ),
+            oscillation,
+        ]));
+    }
+
+    Ok(Expr::add(solution))
+}
+
+/// Split a term into `c x^j exp(α x)` and maybe `sin(β x)` or `cos(β x)`,
+/// as the coefficient, `j`, `α`, `β` and whether it is a sine.
+#[allow(clippy::type_complexity)]
+fn forcing_term(term: &Expr, x: &Symbol) -> Option<(Expr, usize, Number, Number, Option<bool>)> {
+    let factors = match term {
+        Expr::Call(Call { op: Op::Mul, args }) => args.clone(),
+        _ => vec![term.clone()],
+    };
+    let var = Expr::Atom(Atom::Symbol(x.clone()));
+    let slope = |u: &Expr| u.derive(x).ok()?.as_number();
+
+    let (mut coeff, mut j, mut alpha, mut beta, mut trig) =
+        (Vec::new(), 0, Number::int(0), Number::int(0), None);
+
+    for factor in factors {
+        if !factor.contains(x) {
+            coeff.push(factor);
+            continue;
+        }
+
+        match &factor {
+            Expr::Atom(_) => j += 1,
+            Expr::Call(Call { op: Op::Pow, args }) if args[0] == var => {
+                j += usize::try_from(args[1].as_number()?.as_int()?).ok()?
+            }
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) if args.len() == 1 => {
+                let u = &args[0];
+                let start = u.replace(&var, &Expr::int(0));
+
+                match &call.0[..] {
+                    "exp" => {
+                        alpha = alpha + slope(u)?;
+                        coeff.push(Expr::function(call.clone(), start));
+                    }
+                    "sin" | "cos" if trig.is_none() && start.is_zero() => {
+                        beta = slope(u)?;
+                        trig = Some(call.0 == "sin");
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    Some((Expr::mul(coeff), j, alpha, beta, trig))
+}
+
+/// `a1 y' + a0 y + rest = 0` is `y = (∫ Q μ + C1) / μ` with `μ = exp(∫ a0 / a1)` and `Q = -rest / a1`.
+fn first_order_linear(coeffs: &[Expr], rest: &Expr, x: &Symbol) -> Result<Expr, TypeErr> {
+    let exp = |x: Expr| Expr::function(Symbol("exp".to_string()), x);
+
+    let factor = exp(Expr::div(coeffs[0].clone(), coeffs[1].clone()).antiderivative(x)?);
+    let source = Expr::neg(Expr::div(rest.clone(), coeffs[1].clone()));
+    let integral = Expr::mul(vec![source, factor.clone()]).antiderivative(x)?;
+
+    Ok(Expr::div(Expr::add(vec![integral, constant()]), factor))
+}
+
+/// `y' = f(x) g(y)` is `∫ 1 / g(y) dy = ∫ f(x) dx + C1`.
+fn separable(expr: &Expr, ys: &[Symbol], x: &Symbol) -> Option<Expr> {
+    let (y, derivative) = (&ys[0], &ys[1]);
+    let mut split = expr.coeffs(derivative)?;
+    if split.len() != 2 {
+        return None;
+    }
+
+    let rate = Expr::neg(Expr::div(split.remove(0), split.remove(0)));
+    let factors = match &rate {
+        Expr::Call(Call { op: Op::Mul, args }) => args.clone(),
+        _ => vec![rate.clone()],
+    };
+    if factors
+        .iter()
+        .any(|factor| factor.contains(x) && factor.contains(y))
+    {
+        return None;
+    }
+    let (of_x, of_y): (Vec<Expr>, Vec<Expr>) =
+        factors.into_iter().partition(|factor| factor.contains(x));
+
+    let lhs = Expr::pow(Expr::mul(of_y), Expr::int(-1))
+        .antiderivative(y)
+        .ok()?;
+    let rhs = Expr::add(vec![Expr::mul(of_x).antiderivative(x).ok()?, constant()]);
+
+    Some(explicit(lhs, rhs, y))
+}
+
+/// `M + N y' = 0` with `∂M/∂y = ∂N/∂x` has a potential `F` with `F = C1`.
+fn exact(expr: &Expr, ys: &[Symbol], x: &Symbol) -> Option<Expr> {
+    let (y, derivative) = (&ys[0], &ys[1]);
+    let split = expr.coeffs(derivative)?;
+    if split.len() != 2 {
+        return None;
+    }
+    let (m, n) = (&split[0], &split[1]);
+
+    if !Expr::sub(m.derive(y).ok()?, n.derive(x).ok()?)
+        .expand()
+        .is_zero()
+    {
+        return None;
+    }
+
+    let potential = m.antiderivative(x).ok()?;
+    let rest = Expr::sub(n.clone(), potential.derive(y).ok()?).expand();
+    if rest.contains(x) {
+        return None;
+    }
+
+    let potential = Expr::add(vec![potential, rest.antiderivative(y).ok()?]);
+    Some(explicit(potential, constant(), y))
+}
+
+fn constant() -> Expr {
+    Expr::Atom(Atom::Symbol(Symbol("C1".to_string())))
+}
+
+/// `y = ...` if `lhs = rhs` can be solved for y, else the implicit equation.
+fn explicit(lhs: Expr, rhs: Expr, y: &Symbol) -> Expr {
+    match invert(&lhs, rhs.clone(), y) {
+        Some(solution) => Expr::Call(Call::new(
+            Op::Eq,
+            vec![Expr::Atom(Atom::Symbol(y.clone())), solution],
+        )),
+        None => Expr::Call(Call::new(Op::Eq, vec![lhs, rhs])),
+    }
+}
+
+/// Solve `lhs = rhs` for y, by undoing one function after the other.
+fn invert(lhs: &Expr, rhs: Expr, y: &Symbol) -> Option<Expr> {
+    if let Some(coeffs) = lhs.coeffs(y).filter(|coeffs| coeffs.len() == 2) {
+        if !coeffs[1].contains(y) {
+            return Some(Expr::div(
+                Expr::sub(rhs, coeffs[0].clone()),
+                coeffs[1].clone(),
+            ));
+        }
+    }
+
+    match lhs {
+        Expr::Call(Call {
+            op: op @ (Op::Add | Op::Mul),
+            args,
+        }) => {
+            let (with, without): (Vec<Expr>, Vec<Expr>) =
+                args.iter().cloned().partition(|arg| arg.contains(y));
+            match (&with[..], op) {
+                ([inner], Op::Add) => invert(inner, Expr::sub(rhs, Expr::add(without)), y),
+                ([inner], _) => invert(inner, Expr::div(rhs, Expr::mul(without)), y),
+                _ => None,
+            }
+        }
+        Expr::Call(Call { op: Op::Pow, args }) if !args[1].contains(y) => invert(
+            &args[0],
+            Expr::pow(rhs, Expr::pow(args[1].clone(), Expr::int(-1))),
+            y,
+        ),
+        Expr::Call(Call {
+            op: Op::Call(call),
+            args,
+        }) if args.len() == 1 => {
+            let inverse = match &call.0[..] {
+                "ln" => "exp",
+                "exp" => "ln",
+                "tan" => "atan",
+                "atan" => "tan",
+                "sinh" => "asinh",
+                "asinh" => "sinh",
+                _ => return None,
+            };
+            invert(
+                &args[0],
+                Expr::function(Symbol(inverse.to_string()), rhs),
+                y,
+            )
+        }
+        _ => None,
+    }
+}
+
+/// One step of the Dormand-Prince method and its error relative to the tolerance.
+fn dormand_prince(
+    rate: &impl Fn(f64, &[f64]) -> Option<Vec<f64>>,
+    t: f64,
+    y: &[f64],
+    h: f64,
+) -> Option<(Vec<f64>, f64)> {
+    let mut slopes = vec![rate(t, y)?];
+
+    for (stage, weights) in STAGES.iter().enumerate() {
+        let point: Vec<f64> = (0..y.len())
+            .map(|i| {
+                y[i] + h * weights
+                    .iter()
+                    .zip(&slopes)
+                    .map(|(weight, slope)| weight * slope[i])
+                    .sum::<f64>()
+            })
+            .collect();
+        slopes.push(rate(t + NODES[stage + 1] * h, &point)?);
+    }
+
+    // the last stage is evaluated at the solution of order 5
+    let next: Vec<f64> = (0..y.len())
+        .map(|i| {
+            y[i] + h * STAGES[5]
+                .iter()
+                .zip(&slopes)
+                .map(|(weight, slope)| weight * slope[i])
+                .sum::<f64>()
+        })
+        .collect();
+
+    let error = (0..y.len())
+        .map(|i| {
+            let error = h * ERROR
+                .iter()
+                .zip(&slopes)
+                .map(|(weight, slope)| weight * slope[i])
+                .sum::<f64>();
+            let scale = TOLERANCE * (1.0 + y[i].abs().max(next[i].abs()));
+            (error / scale).powi(2)
+        })
+        .sum::<f64>()
+        / y.len() as f64;
+
+    next.iter()
+        .all(|value| value.is_finite())
+        .then(|| (next, error.sqrt()))
+}
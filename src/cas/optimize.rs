@@ -0,0 +1,625 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::{number::Number, symbol::Symbol, Atom},
+        call::{op::Op, Call},
+        Expr, TypeErr,
+    },
+    ode::numeric_roots,
+};
+
+/// Steps of the golden-section search, which shrinks the bracket by 0.618 each.
+const MAX_GOLDEN: usize = 200;
+
+/// Steps of BFGS, before it gives up.
+const MAX_BFGS: usize = 500;
+
+/// How often a BFGS step is halved, until it decreases the objective enough.
+const MAX_BACKTRACK: usize = 60;
+
+/// Steps of the Nelder–Mead simplex per variable.
+const MAX_NELDER_MEAD: usize = 2000;
+
+/// How far Nelder–Mead may move from the start, relative to its size, which only
+/// happens for objectives without a lower bound.
+const MAX_DISTANCE: f64 = 1e10;
+
+/// Weights of the penalty for violated constraints, each from the optimum of the last one.
+const PENALTIES: [f64; 9] = [1.0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e8, 1e10];
+
+/// Largest violation of a constraint, that still counts as satisfied.
+const MAX_VIOLATION: f64 = 1e-6;
+
+/// Largest Newton step, relative to the scale of the point, that is already at a minimum.
+const MAX_NEWTON_STEP: f64 = 1e-3;
+
+/// Farthest distance to a minimum, relative to the scale of the point, where Newton steps
+/// would still arrive.
+const MAX_NEWTON_DISTANCE: f64 = 0.1;
+
+/// Local minima and maxima, each returned with the optimal value.
+impl Expr {
+    /// `minimize(p, x)` compares all exact critical points of a polynomial, or returns
+    /// the local minima of one without a smallest value,
+    /// `minimize(f, x, a, b)` searches the golden section in between `a` and `b`,
+    /// `minimize(f, (x; y), (1; 2))` runs BFGS from the guess with gradients of dual numbers
+    /// and constraints like `x + y = 1` or `x >= 0` after it are penalized.
+    /// The result is the list `point, value`, where point may be a list.
+    pub(crate) fn minimize(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        Expr::optimize("minimize", args, env)
+    }
+
+    pub(crate) fn maximize(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        Expr::optimize("maximize", args, env)
+    }
+
+    fn optimize(call: &str, args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (f, vars, rest) = match args {
+            [f, vars, rest @ ..] => (f.eval(env)?, vars.clone().list(), rest),
+            _ => {
+                return Err(TypeErr(format!(
+                    "call `{}` undefined on (expr, ...), expected (expr, symbols, numbers, constraints)",
+                    call
+                )))
+            }
+        };
+        let vars = vars
+            .iter()
+            .map(Expr::symbol)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // a maximum is a minimum of the negation
+        let sign = if call == "maximize" { -1 } else { 1 };
+        let objective = Expr::mul(vec![Expr::int(sign), f.clone()]);
+
+        let (mut guess, mut constraints) = (Vec::new(), Vec::new());
+        for arg in rest {
+            match arg {
+                Expr::Call(Call { op, args })
+                    if matches!(op, Op::Eq | Op::Less | Op::More | Op::LessEq | Op::MoreEq)
+                        && args.len() == 2 =>
+                {
+                    constraints.push(constraint(op, args[0].eval(env)?, args[1].eval(env)?));
+                }
+                _ => guess.extend(arg.eval(env)?.list()),
+            }
+        }
+        let guess = guess
+            .iter()
+            .map(|x| Ok(x.number(env)?.as_f64()))
+            .collect::<Result<Vec<_>, TypeErr>>()?;
+
+        if guess.is_empty() && constraints.is_empty() {
+            return match &vars[..] {
+                [var] => polynomial_optimum(&objective, var, sign, env),
+                _ => Err(TypeErr(format!(
+                    "call `{}` needs a guess for each variable",
+                    call
+                ))),
+            };
+        }
+
+        let problem = Problem {
+            objective,
+            vars: &vars,
+            constraints,
+        };
+        let at = match (&vars[..], &guess[..]) {
+            ([_], [a, b]) => vec![(a + b) / 2.0],
+            _ if guess.len() == vars.len() => guess.clone(),
+            _ => {
+                return Err(TypeErr(format!(
+                    "call `{}` expected a guess for each of {} variables or a bracket, found {} numbers",
+                    call,
+                    vars.len(),
+                    guess.len()
+                )))
+            }
+        };
+        if problem.value(&at, 1.0).is_nan() {
+            return Err(TypeErr(format!(
+                "`{}` has other variables than `{}` or unknown functions",
+                f.tidy(),
+                vars.iter()
+                    .map(|var| var.0.clone())
+                    .collect::<Vec<_>>()
+                    .join("`, `")
+            )));
+        }
+
+        let solve = |problem: &Problem, at: Vec<f64>, weight: f64, env: &mut Env| match &guess[..] {
+            [a, b] if vars.len() == 1 => {
+                golden(&|x| problem.value(&[x], weight), *a, *b).map(|x| vec![x])
+            }
+            _ => bfgs(problem, at.clone(), weight, env)
+                .or_else(|| nelder_mead(&|x| problem.value(x, weight), at)),
+        };
+
+        let mut point = Some(at);
+        let mut weight = 0.0;
+        if problem.constraints.is_empty() {
+            point = point.and_then(|at| solve(&problem, at, weight, env));
+        } else {
+            for penalty in PENALTIES.iter() {
+                weight = *penalty;
+                point = point.and_then(|at| solve(&problem, at, weight, env));
+            }
+        }
+
+        let optimum = if sign < 0 { "maximum" } else { "minimum" };
+        let point = point
+            .filter(|point| problem.violation(point) <= MAX_VIOLATION)
+            .ok_or_else(|| TypeErr(format!("could not find a {} of `{}`", optimum, f.tidy())))?;
+
+        // only the golden section stays in its bracket
+        if !matches!((&vars[..], &guess[..]), ([_], [_, _]))
+            && runs_off(&|x| problem.value(x, weight), &point)
+        {
+            return Err(TypeErr(format!(
+                "`{}` has no {}, it keeps {} away from the guess",
+                f.tidy(),
+                optimum,
+                if sign < 0 { "increasing" } else { "decreasing" }
+            )));
+        }
+
+        let value = sign as f64 * problem.value(&point, 0.0);
+        let mut point: Vec<_> = point
+            .into_iter()
+            .map(|x| Expr::num(Number::Float(x)))
+            .collect();
+
+        Ok(Expr::from_list(vec![
+            if point.len() == 1 {
+                point.remove(0)
+            } else {
+                Expr::from_list(point)
+            },
+            Expr::num(Number::Float(value)),
+        ]))
+    }
+}
+
+/// Objective and constraints `g = 0` or `g <= 0` in some variables.
+struct Problem<'a> {
+    objective: Expr,
+    vars: &'a [Symbol],
+    constraints: Vec<(Expr, bool)>,
+}
+
+impl Problem<'_> {
+    /// The objective plus the weighted squares of all violations.
+    fn penalized(&self, weight: f64) -> Expr {
+        let mut terms = vec![self.objective.clone()];
+
+        for (g, equal) in &self.constraints {
+            // max(g, 0) = (g + |g|) / 2
+            let violation = if *equal {
+                g.clone()
+            } else {
+                Expr::mul(vec![
+                    Expr::num(Number::ratio(1, 2)),
+                    Expr::add(vec![
+                        g.clone(),
+                        Expr::function(Symbol("abs".to_string()), g.clone()),
+                    ]),
+                ])
+            };
+            terms.push(Expr::mul(vec![
+                Expr::num(Number::Float(weight)),
+                Expr::pow(violation, Expr::int(2)),
+            ]));
+        }
+
+        Expr::add(terms)
+    }
+
+    fn value(&self, x: &[f64], weight: f64) -> f64 {
+        let values: Vec<_> = self.vars.iter().zip(x.iter().copied()).collect();
+        let f = |expr: &Expr| expr.float_with(&values).unwrap_or(f64::NAN);
+
+        self.constraints
+            .iter()
+            .fold(f(&self.objective), |sum, (g, equal)| {
+                let g = f(g);
+                let violation = if *equal { g } else { g.max(0.0) };
+                sum + weight * violation * violation
+            })
+    }
+
+    /// Largest violation of a constraint.
+    fn violation(&self, x: &[f64]) -> f64 {
+        let values: Vec<_> = self.vars.iter().zip(x.iter().copied()).collect();
+
+        self.constraints.iter().fold(0.0, |max, (g, equal)| {
+            let g = g.float_with(&values).unwrap_or(f64::NAN);
+            max.max(if *equal { g.abs() } else { g.max(0.0) })
+        })
+    }
+}
+
+/// `lhs op rhs` as `g = 0` or `g <= 0`.
+fn constraint(op: &Op, lhs: Expr, rhs: Expr) -> (Expr, bool) {
+    match op {
+        Op::Eq => (Expr::sub(lhs, rhs), true),
+        Op::Less | Op::LessEq => (Expr::sub(lhs, rhs), false),
+        _ => (Expr::sub(rhs, lhs), false),
+    }
+}
+
+/// Smallest value of a polynomial at the real roots of its derivative, which are exact
+/// if they are rational or of a quadratic factor. Equal minima are all returned.
+/// Without a smallest value, the local minima by the second derivative are returned,
+/// each with its own value.
+fn polynomial_optimum(
+    objective: &Expr,
+    var: &Symbol,
+    sign: i128,
+    env: &mut Env,
+) -> Result<Expr, TypeErr> {
+    let none = || {
+        TypeErr(format!(
+            "`{}` has no {} in `{}`",
+            Expr::mul(vec![Expr::int(sign), objective.clone()]).tidy(),
+            if sign < 0 { "maximum" } else { "minimum" },
+            var.0
+        ))
+    };
+    let coeffs = objective
+        .coeffs(var)
+        .and_then(|coeffs| {
+            coeffs
+                .iter()
+                .map(Expr::as_number)
+                .collect::<Option<Vec<_>>>()
+        })
+        .ok_or_else(|| {
+            TypeErr(format!(
+                "`{}` is no polynomial in `{}`, so it needs a guess",
+                Expr::mul(vec![Expr::int(sign), objective.clone()]).tidy(),
+                var.0
+            ))
+        })?;
+
+    let degree = coeffs.len() - 1;
+    if degree == 0 {
+        return Err(none());
+    }
+    // bounded below, only with an even degree and a positive leading coefficient
+    let bounded = degree % 2 == 0 && !coeffs[degree].is_negative();
+
+    let slope = objective.derive(var)?;
+    let curvature = slope.derive(var)?;
+    let slope = slope
+        .coeffs(var)
+        .and_then(|coeffs| {
+            coeffs
+                .iter()
+                .map(Expr::as_number)
+                .collect::<Option<Vec<_>>>()
+        })
+        .expect("derivative of a polynomial");
+
+    let x = Expr::Atom(Atom::Symbol(var.clone()));
+    let mut critical = Vec::new();
+    for root in numeric_roots(slope) {
+        if !root.im.is_zero() {
+            continue;
+        }
+
+        let mut at = |expr: &Expr| expr.replace(&x, &root.re).expand().eval(env);
+        if !bounded
+            && !at(&curvature)?
+                .float()
+                .is_some_and(|curvature| curvature > 0.0)
+        {
+            continue;
+        }
+        let value = at(objective)?;
+        let float = value.float().unwrap_or(f64::NAN);
+        critical.push((root.re, Expr::mul(vec![Expr::int(sign), value]), float));
+    }
+
+    let one_or_list = |mut exprs: Vec<Expr>| {
+        if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            Expr::from_list(exprs)
+        }
+    };
+
+    if !bounded {
+        if critical.is_empty() {
+            return Err(none());
+        }
+
+        let (points, values) = critical
+            .into_iter()
+            .map(|(point, value, _)| (point, value))
+            .unzip();
+        return Ok(Expr::from_list(vec![
+            one_or_list(points),
+            one_or_list(values),
+        ]));
+    }
+
+    let min = critical
+        .iter()
+        .map(|(_, _, float)| *float)
+        .fold(f64::INFINITY, f64::min);
+    let tolerance = 1e-12 * min.abs().max(1.0);
+
+    let mut points = Vec::new();
+    let mut value = Expr::nan();
+    for (point, exact, float) in critical {
+        if float <= min + tolerance && !points.contains(&point) {
+            points.push(point);
+            value = exact;
+        }
+    }
+
+    Ok(Expr::from_list(vec![one_or_list(points), value]))
+}
+
+/// Golden-section search for a local minimum in between `a` and `b`.
+fn golden(f: &impl Fn(f64) -> f64, a: f64, b: f64) -> Option<f64> {
+    let ratio = (5_f64.sqrt() - 1.0) / 2.0;
+    let (mut a, mut b) = (a.min(b), a.max(b));
+    let (mut c, mut d) = (b - ratio * (b - a), a + ratio * (b - a));
+    let (mut fc, mut fd) = (f(c), f(d));
+
+    for _ in 0..MAX_GOLDEN {
+        // at the minimum f is flat, so x is only known to the root of the epsilon
+        if b - a <= f64::EPSILON.sqrt() * (c.abs() + d.abs()).max(f64::EPSILON.sqrt()) {
+            break;
+        }
+
+        if fc < fd {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - ratio * (b - a);
+            fc = f(c);
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + ratio * (b - a);
+            fd = f(d);
+        }
+    }
+
+    let x = (a + b) / 2.0;
+    f(x).is_finite().then_some(x)
+}
+
+/// BFGS with the inverse Hessian approximated by the changes of the gradient,
+/// or None where a gradient is not finite.
+fn bfgs(problem: &Problem, mut x: Vec<f64>, weight: f64, env: &mut Env) -> Option<Vec<f64>> {
+    let n = x.len();
+    let penalized = problem.penalized(weight);
+    let gradient = |x: &[f64], env: &mut Env| {
+        let point: Vec<_> = x.iter().map(|x| Number::Float(*x)).collect();
+        penalized
+            .gradient_at(problem.vars, &point, env)
+            .ok()
+            .filter(|gradient| gradient.iter().all(|slope| slope.is_finite()))
+    };
+
+    let mut inverse = identity(n);
+    let mut fx = problem.value(&x, weight);
+    let mut gx = gradient(&x, env)?;
+
+    for _ in 0..MAX_BFGS {
+        if stationary(&x, fx, &gx) {
+            break;
+        }
+
+        let mut direction: Vec<f64> = (0..n)
+            .map(|i| -(0..n).map(|j| inverse[i][j] * gx[j]).sum::<f64>())
+            .collect();
+        // not a descent direction, so start over with the gradient
+        if dot(&direction, &gx) >= 0.0 {
+            inverse = identity(n);
+            direction = gx.iter().map(|g| -g).collect();
+        }
+
+        // backtrack until the decrease is sufficient, by the Armijo condition
+        let mut step = 1.0;
+        let mut next = x.clone();
+        let mut f_next = fx;
+        for _ in 0..MAX_BACKTRACK {
+            next = x
+                .iter()
+                .zip(&direction)
+                .map(|(x, d)| x + step * d)
+                .collect();
+            f_next = problem.value(&next, weight);
+            if f_next <= fx + 1e-4 * step * dot(&direction, &gx) {
+                break;
+            }
+            step /= 2.0;
+        }
+        if f_next >= fx || f_next.is_nan() {
+            break;
+        }
+
+        let g_next = gradient(&next, env)?;
+        let s: Vec<f64> = next.iter().zip(&x).map(|(a, b)| a - b).collect();
+        let y: Vec<f64> = g_next.iter().zip(&gx).map(|(a, b)| a - b).collect();
+        let sy = dot(&s, &y);
+
+        // H' = (I - ρ s yᵀ) H (I - ρ y sᵀ) + ρ s sᵀ, only with positive curvature
+        if sy > 0.0 {
+            let rho = 1.0 / sy;
+            let hy: Vec<f64> = (0..n)
+                .map(|i| (0..n).map(|j| inverse[i][j] * y[j]).sum())
+                .collect();
+            let yhy = dot(&y, &hy);
+
+            for i in 0..n {
+                for j in 0..n {
+                    inverse[i][j] +=
+                        rho * ((1.0 + rho * yhy) * s[i] * s[j] - hy[i] * s[j] - s[i] * hy[j]);
+                }
+            }
+        }
+
+        let converged = norm(&s) <= 4.0 * f64::EPSILON * norm(&next).max(1.0);
+        x = next;
+        fx = f_next;
+        gx = g_next;
+        if converged {
+            break;
+        }
+    }
+
+    // an unbounded objective runs off without ever getting flat
+    stationary(&x, fx, &gx).then_some(x)
+}
+
+/// Whether the gradient is about zero, relative to the scales of the point and the value.
+fn stationary(x: &[f64], fx: f64, gx: &[f64]) -> bool {
+    fx.is_finite() && norm(gx) * norm(x).max(1.0) <= f64::EPSILON.sqrt() * fx.abs().max(1.0)
+}
+
+/// Whether the function keeps decreasing beyond a point, where only its slope faded,
+/// like that of `exp(x)` towards `-inf`. Newton steps `f' / f''` towards a minimum shrink,
+/// even if it is flat like `x^6`, and add up to a short distance, but towards no minimum
+/// they stay, grow or add up to about the distance from zero, like for `exp(-x^2)`.
+fn runs_off(f: &impl Fn(&[f64]) -> f64, x: &[f64]) -> bool {
+    (0..x.len()).any(|i| {
+        let scale = x[i].abs().max(1.0);
+        // the second difference loses half of the digits of the first
+        let h = f64::EPSILON.powf(0.25) * scale;
+        let newton = |x: &[f64]| {
+            let at = |step: f64| {
+                let mut y = x.to_vec();
+                y[i] += step;
+                f(&y)
+            };
+            let (above, here, below) = (at(h), at(0.0), at(-h));
+            (above - below) / (2.0 * h) / ((above - 2.0 * here + below) / (h * h))
+        };
+
+        // a small step is at the minimum or only noise
+        let step = newton(x);
+        if step.abs() <= MAX_NEWTON_STEP * scale {
+            return false;
+        }
+
+        let mut next = x.to_vec();
+        next[i] -= step;
+        let shrink = newton(&next) / step;
+
+        // the steps add up like a geometric series
+        !(shrink < 1.0 && step.abs() / (1.0 - shrink) <= MAX_NEWTON_DISTANCE * scale)
+    })
+}
+
+/// Nelder–Mead simplex, which only needs values of the function.
+fn nelder_mead(f: &impl Fn(&[f64]) -> f64, start: Vec<f64>) -> Option<Vec<f64>> {
+    let n = start.len();
+    let mut simplex = vec![start.clone()];
+    for i in 0..n {
+        let mut vertex = start.clone();
+        vertex[i] += 0.05 * start[i].abs().max(1.0);
+        simplex.push(vertex);
+    }
+    let mut values: Vec<f64> = simplex.iter().map(|x| f(x)).collect();
+
+    for _ in 0..MAX_NELDER_MEAD * n {
+        // best vertex first, worst last
+        let mut order: Vec<usize> = (0..=n).collect();
+        order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+        simplex = order.iter().map(|i| simplex[*i].clone()).collect();
+        values = order.iter().map(|i| values[*i]).collect();
+
+        let (best, worst) = (values[0], values[n]);
+        let size = simplex[1..]
+            .iter()
+            .map(|vertex| distance(vertex, &simplex[0]))
+            .fold(0.0, f64::max);
+        if distance(&simplex[0], &start) > MAX_DISTANCE * norm(&start).max(1.0) {
+            return None;
+        }
+        if size <= f64::EPSILON.sqrt() * norm(&simplex[0]).max(1.0) {
+            return best.is_finite().then(|| simplex[0].clone());
+        }
+
+        let centroid: Vec<f64> = (0..n)
+            .map(|i| simplex[..n].iter().map(|vertex| vertex[i]).sum::<f64>() / n as f64)
+            .collect();
+        let towards = |t: f64| -> Vec<f64> {
+            centroid
+                .iter()
+                .zip(&simplex[n])
+                .map(|(c, w)| c + t * (w - c))
+                .collect()
+        };
+
+        let reflected = towards(-1.0);
+        let f_reflected = f(&reflected);
+        if f_reflected < best {
+            let expanded = towards(-2.0);
+            let f_expanded = f(&expanded);
+            if f_expanded < f_reflected {
+                simplex[n] = expanded;
+                values[n] = f_expanded;
+            } else {
+                simplex[n] = reflected;
+                values[n] = f_reflected;
+            }
+        } else if f_reflected < values[n - 1] {
+            simplex[n] = reflected;
+            values[n] = f_reflected;
+        } else {
+            let contracted = if f_reflected < worst {
+                towards(-0.5)
+            } else {
+                towards(0.5)
+            };
+            let f_contracted = f(&contracted);
+
+            if f_contracted < worst.min(f_reflected) {
+                simplex[n] = contracted;
+                values[n] = f_contracted;
+            } else {
+                // shrink everything towards the best vertex
+                for i in 1..=n {
+                    simplex[i] = simplex[0]
+                        .iter()
+                        .zip(&simplex[i])
+                        .map(|(b, x)| b + 0.5 * (x - b))
+                        .collect();
+                    values[i] = f(&simplex[i]);
+                }
+            }
+        }
+    }
+
+    // the simplex never contracted, so there might not even be a minimum
+    None
+}
+
+fn identity(n: usize) -> Vec<Vec<f64>> {
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+fn norm(x: &[f64]) -> f64 {
+    dot(x, x).sqrt()
+}
+
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b) * (a - b))
+        .sum::<f64>()
+        .sqrt()
+}
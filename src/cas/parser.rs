@@ -1,6 +1,7 @@
 use super::{
     env::Env,
     expr::{
+        atom::Atom,
         call::{op::Op, Call},
         Expr,
     },
@@ -48,6 +49,18 @@ impl Expr {
                 }
             }
 
+            // redefining a function as a symbol, but not a built-in one like `N := kg m / s^2`
+            Token::Op(Op::Call(call)) if lexer.peek() == Ok(&Token::Op(Op::Def)) => {
+                if lexer.is_builtin(&call) {
+                    Err(ParserErr::Panic(format!(
+                        "the built-in function `{}` can not be redefined",
+                        call
+                    )))
+                } else {
+                    Ok(Expr::Atom(Atom::Symbol(call)))
+                }
+            }
+
             Token::Op(op) => {
                 let right_bp = prefix_bp(&op).ok_or(ParserErr::Panic(format!(
                     "expected prefix op, but found `{}`",
@@ -64,10 +77,20 @@ impl Expr {
                     _ => Expr::Call(Call::new(op, vec![rhs])),
                 })
             }
-
-            token => Err(ParserErr::Panic(format!("unexpected token `{}`", token))),
         }?;
 
+        // a number with a unit is one quantity, so `3 m / 2 s` is a speed
+        if let Expr::Atom(Atom::Number(_)) = lhs {
+            if let Ok(Token::Atom(Atom::Symbol(symbol))) = lexer.peek() {
+                let symbol = symbol.clone();
+
+                if lexer.is_unit(&symbol) {
+                    let unit = Expr::parse_bp(lexer, QUANTITY_BP)?;
+                    lhs = Expr::Call(Call::new(Op::Mul, vec![lhs, unit]));
+                }
+            }
+        }
+
         loop {
             let op = match lexer.peek() {
                 Err(error) => match error {
@@ -80,10 +103,6 @@ impl Expr {
                 Ok(token) => match token {
                     Token::Op(Op::Open) | Token::Atom(_) => Ok(&Op::Mul),
                     Token::Op(op) => Ok(op),
-                    _ => Err(ParserErr::Panic(format!(
-                        "expected in- or postfix op, found `{}`",
-                        token
-                    ))),
                 },
             }?
             .clone();
@@ -93,7 +112,7 @@ impl Expr {
                     break;
                 }
 
-                lexer.token();
+                lexer.token().map_err(|error| error.parser_err())?;
                 lhs = Expr::Call(Call::new(op, vec![lhs]));
 
                 continue;
@@ -105,7 +124,7 @@ impl Expr {
                 }
 
                 if Ok(&Token::Op(op.clone())) == lexer.peek() {
-                    lexer.token();
+                    lexer.token().map_err(|error| error.parser_err())?;
                 }
 
                 let rhs = Expr::parse_bp(lexer, right_bp).map_err(|error| {
@@ -124,31 +143,39 @@ impl Expr {
     }
 }
 
+/// Binding of the unit of a quantity like `3 s^2`, which takes powers but not products.
+const QUANTITY_BP: u8 = 17;
+
 fn infix_bp(op: &Op) -> Option<(u8, u8)> {
     Some(match op {
         Op::Def => (2, 1),
         Op::List => (4, 3),
-        Op::Eq | Op::Neq | Op::Less | Op::More | Op::LessEq | Op::MoreEq => (5, 6),
-        Op::Mod => (8, 6),
-        Op::Add | Op::Sub => (9, 10),
-        Op::Mul | Op::Div => (11, 12),
-        Op::Pow => (14, 13),
-        Op::Child => (15, 16),
+        Op::If | Op::To => (5, 6),
+        Op::Rule => (8, 7),
+        Op::Eq | Op::Neq | Op::Less | Op::More | Op::LessEq | Op::MoreEq => (9, 10),
+        Op::Interval => (11, 12),
+        Op::Mod => (12, 10),
+        Op::Add | Op::Sub | Op::PlusMinus => (13, 14),
+        Op::Mul | Op::Div => (15, 16),
+        Op::Pow => (18, 17),
+        Op::Child => (19, 20),
         _ => return None,
     })
 }
 
 fn prefix_bp(op: &Op) -> Option<u8> {
     Some(match op {
-        Op::Add | Op::Sub => 13,
-        Op::Call(_) => 10,
+        Op::Add | Op::Sub => 17,
+        // `rule lhs -> rhs if condition, ...` takes whole rules
+        Op::Call(call) if call.0 == "rule" || call.0 == "unrule" => 3,
+        Op::Call(_) => 14,
         _ => return None,
     })
 }
 
 fn postfix_bp(op: &Op) -> Option<u8> {
     Some(match op {
-        Op::Fact => 15,
+        Op::Fact | Op::Prime => 19,
         _ => return None,
     })
 }
@@ -0,0 +1,131 @@
+use super::expr::{
+    atom::{symbol::Symbol, Atom},
+    call::{op::Op, Call},
+    Expr,
+};
+
+/// Polynomials as dense lists of coefficients `[c0, c1, ...]` for `c0 + c1 x + ...`.
+impl Expr {
+    /// Coefficients in a variable, if the expression is a polynomial in it.
+    pub(crate) fn coeffs(&self, var: &Symbol) -> Option<Vec<Expr>> {
+        if !self.contains(var) {
+            return Some(vec![self.clone()]);
+        }
+
+        let coeffs = match self {
+            Expr::Atom(Atom::Symbol(_)) => vec![Expr::int(0), Expr::int(1)],
+            Expr::Call(Call { op: Op::Add, args }) => {
+                let mut sum = Vec::new();
+                for arg in args {
+                    sum = add_coeffs(&sum, &arg.coeffs(var)?);
+                }
+                sum
+            }
+            Expr::Call(Call { op: Op::Mul, args }) => {
+                let mut product = vec![Expr::int(1)];
+                for arg in args {
+                    product = mul_coeffs(&product, &arg.coeffs(var)?);
+                }
+                product
+            }
+            Expr::Call(Call { op: Op::Pow, args }) => {
+                let exp = args[1].as_number()?.as_int().filter(|exp| *exp >= 0)?;
+                let base = args[0].coeffs(var)?;
+
+                let mut power = vec![Expr::int(1)];
+                for _ in 0..exp {
+                    power = mul_coeffs(&power, &base);
+                }
+                power
+            }
+            _ => return None,
+        };
+
+        Some(trim(coeffs))
+    }
+
+    /// Degree in a variable, if the expression is a polynomial in it.
+    pub(crate) fn degree(&self, var: &Symbol) -> Option<usize> {
+        Some(self.coeffs(var)?.len().saturating_sub(1))
+    }
+
+    /// Multiply out products and natural powers of sums, but not inside of function calls.
+    pub(crate) fn expand(&self) -> Expr {
+        match self {
+            Expr::Call(Call { op: Op::Add, args }) => {
+                Expr::add(args.iter().map(Expr::expand).collect())
+            }
+            Expr::Call(Call { op: Op::Mul, args }) => args
+                .iter()
+                .map(Expr::expand)
+                .fold(Expr::int(1), |product, factor| {
+                    distribute(&product, &factor)
+                }),
+            Expr::Call(Call { op: Op::Pow, args }) => {
+                let base = args[0].expand();
+                match args[1].as_number().and_then(|exp| exp.as_int()) {
+                    Some(exp)
+                        if exp > 1 && matches!(base, Expr::Call(Call { op: Op::Add, .. })) =>
+                    {
+                        (1..exp).fold(base.clone(), |power, _| distribute(&power, &base))
+                    }
+                    _ => Expr::pow(base, args[1].clone()),
+                }
+            }
+            _ => self.clone(),
+        }
+    }
+}
+
+/// Terms of a sum, or the expression itself.
+fn terms(expr: &Expr) -> Vec<Expr> {
+    match expr {
+        Expr::Call(Call { op: Op::Add, args }) => args.clone(),
+        _ => vec![expr.clone()],
+    }
+}
+
+/// Product of two expanded expressions, multiplied out.
+fn distribute(a: &Expr, b: &Expr) -> Expr {
+    let mut products = Vec::new();
+
+    for x in terms(a) {
+        for y in terms(b) {
+            products.push(Expr::mul(vec![x.clone(), y]));
+        }
+    }
+
+    Expr::add(products)
+}
+
+fn add_coeffs(a: &[Expr], b: &[Expr]) -> Vec<Expr> {
+    (0..a.len().max(b.len()))
+        .map(|i| {
+            Expr::add(vec![
+                a.get(i).cloned().unwrap_or_else(|| Expr::int(0)),
+                b.get(i).cloned().unwrap_or_else(|| Expr::int(0)),
+            ])
+        })
+        .collect()
+}
+
+fn mul_coeffs(a: &[Expr], b: &[Expr]) -> Vec<Expr> {
+    let mut product = vec![Vec::new(); (a.len() + b.len()).saturating_sub(1)];
+
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i + j].push(Expr::mul(vec![x.clone(), y.clone()]));
+        }
+    }
+
+    product.into_iter().map(Expr::add).collect()
+}
+
+/// Remove the zero coefficients of the highest powers.
+fn trim(mut coeffs: Vec<Expr>) -> Vec<Expr> {
+    while coeffs.last().is_some_and(Expr::is_zero) {
+        coeffs.pop();
+    }
+
+    coeffs
+}
@@ -0,0 +1,377 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::{symbol::Symbol, Atom},
+        call::{op::Op, Call},
+        Expr, TypeErr,
+    },
+};
+
+/// How often rules may be applied to one expression, in case they go in circles.
+const MAX_REWRITES: usize = 64;
+
+/// Values of the pattern variables of a match.
+type Bindings = Vec<(Symbol, Expr)>;
+
+/// A rule `lhs -> rhs` or `lhs -> rhs if condition` rewrites whatever matches the lhs.
+/// Symbols ending in an underscore like `a_` are pattern variables, all others match only
+/// themselves.
+/// Sums and products match in any order, at the top even only some of their terms.
+impl Expr {
+    /// `rule lhs -> rhs, ...` adds rules to the enviroment, which every eval applies.
+    pub(crate) fn rule(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let mut rules = env.rules().to_vec();
+
+        for arg in args {
+            rules.push(arg.eval(env)?.into_rule()?);
+        }
+
+        env.set_rules(rules);
+
+        Ok(Expr::Atom(Atom::Symbol(Symbol("Def".to_string()))))
+    }
+
+    /// `unrule lhs -> rhs, ...` removes these rules, `unrule lhs` every rule of the lhs.
+    pub(crate) fn unrule(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let mut rules = env.rules().to_vec();
+
+        for arg in args {
+            let before = rules.len();
+
+            match arg {
+                Expr::Call(Call {
+                    op: op @ (Op::Rule | Op::If),
+                    args,
+                }) if args.len() == 2 => {
+                    let rule = Expr::make_rule(op, &args[0], &args[1])?;
+                    rules.retain(|other| other != &rule);
+                }
+                lhs => {
+                    let lhs = lhs.canonical();
+                    rules.retain(
+                        |rule| !matches!(rule, Expr::Call(Call { args, .. }) if args[0] == lhs),
+                    );
+                }
+            }
+
+            if rules.len() == before {
+                return Err(TypeErr(format!("there is no rule `{}` to remove", arg)));
+            }
+        }
+
+        env.set_rules(rules);
+
+        Ok(Expr::Atom(Atom::Symbol(Symbol("Def".to_string()))))
+    }
+
+    /// `replace(expr, rules)` applies the rules everywhere in expr, until nothing changes.
+    pub(crate) fn replace_rules(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (expr, rules) = match args {
+            [expr, rules] => (expr.eval(env)?, rules.eval(env)?),
+            _ => {
+                return Err(TypeErr(
+                    "call `replace` undefined on (expr, ...), expected (expr, rules)".to_string(),
+                ))
+            }
+        };
+        let rules = rules
+            .list()
+            .into_iter()
+            .map(Expr::into_rule)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut expr = expr;
+        for _ in 0..MAX_REWRITES {
+            let next = expr.rewrite_all(&rules, env)?;
+            if next == expr {
+                break;
+            }
+            expr = next;
+        }
+
+        Ok(expr)
+    }
+
+    /// `subs(expr, x = a, y = b, ...)` replaces symbols or whole subexpressions all at once,
+    /// so `subs(x + y, x = y, y = x)` swaps them. The result is simplified.
+    pub(crate) fn subs(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (expr, equations) = match args.split_first() {
+            Some((expr, equations)) if !equations.is_empty() => (expr.eval(env)?, equations),
+            _ => {
+                return Err(TypeErr(
+                    "call `subs` undefined on (expr), expected (expr, equation, ...)".to_string(),
+                ))
+            }
+        };
+
+        let mut pairs = Vec::with_capacity(equations.len());
+        for equation in equations {
+            match equation {
+                Expr::Call(Call { op: Op::Eq, args }) if args.len() == 2 => {
+                    pairs.push((args[0].eval(env)?, args[1].eval(env)?))
+                }
+                _ => {
+                    return Err(TypeErr(format!(
+                        "expected equation `x = expr`, found `{}`",
+                        equation
+                    )))
+                }
+            }
+        }
+
+        expr.substitute_all(&pairs).eval(env)
+    }
+
+    /// Replace every occurence of the first of a pair by the second, without looking into
+    /// the replacements again.
+    pub(crate) fn substitute_all(&self, pairs: &[(Expr, Expr)]) -> Expr {
+        if let Some((_, to)) = pairs.iter().find(|(from, _)| from == self) {
+            return to.clone();
+        }
+
+        match self {
+            Expr::Call(Call { op, args }) => Expr::Call(Call::new(
+                op.clone(),
+                args.iter().map(|arg| arg.substitute_all(pairs)).collect(),
+            )),
+            _ => self.clone(),
+        }
+    }
+
+    /// The rule of `lhs -> rhs` or `(lhs -> rhs) if condition` with canonical sides.
+    /// It is not evaluated, so that neither definitions nor other rules change it.
+    pub(crate) fn make_rule(op: &Op, x: &Expr, y: &Expr) -> Result<Expr, TypeErr> {
+        let args = match (op, x) {
+            (Op::Rule, _) => vec![x.canonical(), y.canonical()],
+            (Op::If, Expr::Call(Call { op: Op::Rule, args })) if args.len() == 2 => {
+                vec![args[0].canonical(), args[1].canonical(), y.canonical()]
+            }
+            _ => {
+                return Err(TypeErr(format!(
+                    "op `{}` undefined on (expr, expr), expected (rule, condition)",
+                    op
+                )))
+            }
+        };
+
+        Ok(Expr::Call(Call::new(Op::Rule, args)))
+    }
+
+    fn into_rule(self) -> Result<Expr, TypeErr> {
+        match self {
+            Expr::Call(Call {
+                op: Op::Rule,
+                ref args,
+            }) if args.len() == 2 || args.len() == 3 => Ok(self),
+            _ => Err(TypeErr(format!("expected rule, found `{}`", self))),
+        }
+    }
+
+    /// Apply the rules of the enviroment to the root of an evaluated expression.
+    pub(crate) fn apply_rules(self, env: &mut Env) -> Result<Expr, TypeErr> {
+        // rules and lists of them are left alone
+        if matches!(
+            self,
+            Expr::Atom(_)
+                | Expr::Call(Call {
+                    op: Op::Rule | Op::List,
+                    ..
+                })
+        ) {
+            return Ok(self);
+        }
+
+        let rules = env.rules().to_vec();
+        if rules.is_empty() {
+            return Ok(self);
+        }
+
+        self.rewrite_root(&rules, env)
+    }
+
+    /// Rewrite the children first, then the root.
+    fn rewrite_all(&self, rules: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let expr = match self {
+            Expr::Call(Call { op, args }) => Expr::rebuild(
+                op,
+                args.iter()
+                    .map(|arg| arg.rewrite_all(rules, env))
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => self.clone(),
+        };
+
+        expr.rewrite_root(rules, env)
+    }
+
+    /// Apply the first matching rule to the root, as long as one matches.
+    fn rewrite_root(self, rules: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let mut expr = self;
+
+        for _ in 0..MAX_REWRITES {
+            match expr.rewrite(rules, env)? {
+                Some(next) => expr = next,
+                None => break,
+            }
+        }
+
+        Ok(expr)
+    }
+
+    fn rewrite(&self, rules: &[Expr], env: &mut Env) -> Result<Option<Expr>, TypeErr> {
+        for rule in rules {
+            let args = match rule {
+                Expr::Call(Call { args, .. }) => args,
+                _ => continue,
+            };
+
+            let mut bindings = Bindings::new();
+            let rest = match self.match_root(&args[0], &mut bindings) {
+                Some(rest) => rest,
+                None => continue,
+            };
+
+            if let Some(condition) = args.get(2) {
+                if !condition.substitute(&bindings).eval(env)?.is_true() {
+                    continue;
+                }
+            }
+
+            let value = args[1].substitute(&bindings).eval(env)?;
+
+            return Ok(Some(match (self, rest.is_empty()) {
+                (Expr::Call(Call { op, .. }), false) => {
+                    let mut args = rest;
+                    args.insert(0, value);
+                    Expr::rebuild(op, args)
+                }
+                _ => value,
+            }));
+        }
+
+        Ok(None)
+    }
+
+    /// Match the root against a pattern. A sum or product may match only some of its terms,
+    /// the others are returned.
+    fn match_root(&self, pattern: &Expr, bindings: &mut Bindings) -> Option<Vec<Expr>> {
+        if let (
+            Expr::Call(Call {
+                op: op @ (Op::Add | Op::Mul),
+                args: patterns,
+            }),
+            Expr::Call(Call { op: other, args }),
+        ) = (pattern, self)
+        {
+            if op == other && patterns.len() < args.len() {
+                let mut used = vec![false; args.len()];
+                if !match_unordered(patterns, args, &mut used, bindings) {
+                    return None;
+                }
+
+                return Some(
+                    args.iter()
+                        .zip(used)
+                        .filter(|(_, used)| !used)
+                        .map(|(arg, _)| arg.clone())
+                        .collect(),
+                );
+            }
+        }
+
+        if self.matches(pattern, bindings) {
+            Some(Vec::new())
+        } else {
+            None
+        }
+    }
+
+    fn matches(&self, pattern: &Expr, bindings: &mut Bindings) -> bool {
+        match pattern {
+            Expr::Atom(Atom::Symbol(var)) if is_pattern(var) => {
+                match bindings.iter().find(|(bound, _)| bound == var) {
+                    Some((_, value)) => value == self,
+                    None => {
+                        bindings.push((var.clone(), self.clone()));
+                        true
+                    }
+                }
+            }
+            Expr::Atom(_) => pattern == self,
+            Expr::Call(Call { op, args: patterns }) => match self {
+                Expr::Call(Call { op: other, args })
+                    if op == other && patterns.len() == args.len() =>
+                {
+                    if matches!(op, Op::Add | Op::Mul) {
+                        let mut used = vec![false; args.len()];
+                        match_unordered(patterns, args, &mut used, bindings)
+                    } else {
+                        patterns
+                            .iter()
+                            .zip(args)
+                            .all(|(pattern, arg)| arg.matches(pattern, bindings))
+                    }
+                }
+                _ => false,
+            },
+        }
+    }
+
+    /// Replace all pattern variables at once.
+    fn substitute(&self, bindings: &Bindings) -> Expr {
+        match self {
+            Expr::Atom(Atom::Symbol(var)) => bindings
+                .iter()
+                .find(|(bound, _)| bound == var)
+                .map(|(_, value)| value.clone())
+                .unwrap_or_else(|| self.clone()),
+            Expr::Call(Call { op, args }) => Expr::Call(Call::new(
+                op.clone(),
+                args.iter().map(|arg| arg.substitute(bindings)).collect(),
+            )),
+            _ => self.clone(),
+        }
+    }
+
+    fn is_true(&self) -> bool {
+        match self {
+            Expr::Atom(Atom::Symbol(Symbol(name))) => name == "true",
+            _ => self.as_number().is_some_and(|number| !number.is_zero()),
+        }
+    }
+}
+
+fn is_pattern(var: &Symbol) -> bool {
+    var.0.len() > 1 && var.0.ends_with('_')
+}
+
+/// Match every pattern to a different argument, trying all orders.
+fn match_unordered(
+    patterns: &[Expr],
+    args: &[Expr],
+    used: &mut Vec<bool>,
+    bindings: &mut Bindings,
+) -> bool {
+    let (pattern, patterns) = match patterns.split_first() {
+        Some(split) => split,
+        None => return true,
+    };
+
+    for i in 0..args.len() {
+        if used[i] {
+            continue;
+        }
+
+        let before = bindings.len();
+        if args[i].matches(pattern, bindings) {
+            used[i] = true;
+            if match_unordered(patterns, args, used, bindings) {
+                return true;
+            }
+            used[i] = false;
+        }
+        bindings.truncate(before);
+    }
+
+    false
+}
@@ -0,0 +1,711 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::{number::Number, symbol::Symbol, Atom},
+        call::{op::Op, Call},
+        Expr, TypeErr,
+    },
+};
+
+/// Truncated Laurent series `Σ coeffs[k] t^(val + k) + O(t^order)` in the offset `t = x - x0`.
+/// Every coefficient is a canonical expression, so they may contain other symbols.
+#[derive(Clone)]
+pub(crate) struct Series {
+    val: i64,
+    coeffs: Vec<Expr>,
+    order: i64,
+}
+
+/// Order of a series nothing is known about, e.g. after dividing by an order term.
+const UNKNOWN: i64 = -(1 << 32);
+
+/// Default number of terms of `series(expr, x, x0)`.
+const SERIES_ORDER: i64 = 6;
+
+/// How many more terms may be calculated internally to make up for cancellation.
+const MAX_EXTRA: i64 = 32;
+
+/// Largest order that may be asked for, as every term up to it is kept.
+const MAX_ORDER: i64 = 100;
+
+impl Series {
+    fn new(val: i64, mut coeffs: Vec<Expr>, order: i64) -> Series {
+        let len = (order - val).max(0) as usize;
+
+        coeffs.truncate(len);
+        coeffs.resize(len, Expr::int(0));
+
+        Series {
+            val: val.min(order),
+            coeffs,
+            order,
+        }
+    }
+
+    fn unknown() -> Series {
+        Series::new(UNKNOWN, Vec::new(), UNKNOWN)
+    }
+
+    fn constant(constant: Expr, order: i64) -> Series {
+        Series::new(0, vec![constant], order)
+    }
+
+    /// Coefficient of `t^exp`, which has to be below the order.
+    fn coeff(&self, exp: i64) -> Expr {
+        if exp < self.val || exp >= self.order {
+            Expr::int(0)
+        } else {
+            self.coeffs[(exp - self.val) as usize].clone()
+        }
+    }
+
+    /// Remove leading zeros, so the first coefficient is the leading one.
+    fn trim(mut self) -> Series {
+        let zeros = self
+            .coeffs
+            .iter()
+            .take_while(|coeff| coeff.is_zero())
+            .count();
+
+        self.coeffs.drain(..zeros);
+        self.val += zeros as i64;
+        self
+    }
+
+    fn truncate(self, order: i64) -> Series {
+        if order < self.order {
+            Series::new(self.val, self.coeffs, order)
+        } else {
+            self
+        }
+    }
+
+    fn add(&self, other: &Series) -> Series {
+        let val = self.val.min(other.val);
+        let order = self.order.min(other.order);
+
+        Series::new(
+            val,
+            (val..order)
+                .map(|exp| Expr::add(vec![self.coeff(exp), other.coeff(exp)]))
+                .collect(),
+            order,
+        )
+    }
+
+    fn scale(&self, factor: &Expr) -> Series {
+        Series::new(
+            self.val,
+            self.coeffs
+                .iter()
+                .map(|coeff| Expr::mul(vec![factor.clone(), coeff.clone()]))
+                .collect(),
+            self.order,
+        )
+    }
+
+    /// Multiply by `t^exp`.
+    fn shift(mut self, exp: i64) -> Series {
+        self.val += exp;
+        self.order += exp;
+        self
+    }
+
+    fn mul(&self, other: &Series) -> Series {
+        if self.order <= UNKNOWN / 2 || other.order <= UNKNOWN / 2 {
+            return Series::unknown();
+        }
+
+        // leading zeros would lower the order, `x O(x^2)` is `O(x^3)`
+        let (a, b) = (self.clone().trim(), other.clone().trim());
+        let val = a.val + b.val;
+        let order = (a.val + b.order).min(b.val + a.order);
+
+        Series::new(
+            val,
+            (val..order)
+                .map(|exp| {
+                    Expr::add(
+                        (a.val..=exp - b.val)
+                            .map(|i| Expr::mul(vec![a.coeff(i), b.coeff(exp - i)]))
+                            .collect(),
+                    )
+                })
+                .collect(),
+            order,
+        )
+    }
+
+    fn inverse(&self) -> Series {
+        let series = self.clone().trim();
+
+        let lead = match series.coeffs.first() {
+            Some(lead) => Expr::pow(lead.clone(), Expr::int(-1)),
+            // dividing by an order term tells nothing
+            None => return Series::unknown(),
+        };
+
+        let mut coeffs: Vec<Expr> = vec![lead.clone()];
+        for k in 1..series.coeffs.len() {
+            let sum = Expr::add(
+                (1..=k)
+                    .map(|j| Expr::mul(vec![series.coeffs[j].clone(), coeffs[k - j].clone()]))
+                    .collect(),
+            );
+            coeffs.push(Expr::neg(Expr::mul(vec![lead.clone(), sum])));
+        }
+
+        let len = coeffs.len() as i64;
+        Series::new(-series.val, coeffs, len - series.val)
+    }
+
+    fn pow_int(&self, exp: i64) -> Series {
+        if exp < 0 {
+            return self.inverse().pow_int(-exp);
+        }
+
+        let mut result: Option<Series> = None;
+        let mut base = self.clone();
+        let mut exp = exp;
+
+        while exp > 0 {
+            if exp % 2 == 1 {
+                result = Some(match result {
+                    Some(result) => result.mul(&base),
+                    None => base.clone(),
+                });
+            }
+            exp /= 2;
+            if exp > 0 {
+                base = base.mul(&base);
+            }
+        }
+
+        result.unwrap_or_else(|| Series::constant(Expr::int(1), self.order.max(0)))
+    }
+
+    /// Power with an exponent independent of `t`, by the binomial series.
+    fn pow(&self, exp: &Expr) -> Result<Series, TypeErr> {
+        if let Some(exp) = exp.as_number().and_then(|exp| exp.as_int()) {
+            return Ok(self.pow_int(exp as i64));
+        }
+
+        let series = self.clone().trim();
+        let lead = match series.coeffs.first() {
+            Some(lead) => lead.clone(),
+            None => return Ok(Series::unknown()),
+        };
+
+        // the leading power t^(val * exp) has to stay a Laurent series
+        let shift = match Expr::mul(vec![Expr::int(series.val as i128), exp.clone()])
+            .as_number()
+            .and_then(|shift| shift.as_int())
+        {
+            Some(shift) => shift as i64,
+            None => {
+                return Err(TypeErr(format!(
+                    "series has a branch point, exponent `{}` is not supported",
+                    exp
+                )))
+            }
+        };
+
+        // a0 t^v (1 + w)
+        let val = series.val;
+        let w = series
+            .shift(-val)
+            .scale(&Expr::pow(lead.clone(), Expr::int(-1)));
+        let w = w.add(&Series::constant(Expr::int(-1), w.order)).trim();
+
+        let mut binomial = Expr::int(1);
+        let mut coeffs = vec![Expr::int(1)];
+        for k in 1..w.order.max(1) {
+            binomial = Expr::mul(vec![
+                binomial,
+                Expr::add(vec![exp.clone(), Expr::int(1 - k as i128)]),
+                Expr::num(Number::ratio(1, k as i128)),
+            ]);
+            coeffs.push(binomial.clone());
+        }
+
+        Ok(w.compose(&coeffs)
+            .scale(&Expr::pow(lead, exp.clone()))
+            .shift(shift))
+    }
+
+    /// Split into the constant term and the rest, which has to vanish at `t = 0`.
+    fn split(&self) -> Result<(Expr, Series), TypeErr> {
+        let series = self.clone().trim();
+
+        if series.val < 0 {
+            return Err(TypeErr(
+                "series has an essential singularity, argument has a pole".to_string(),
+            ));
+        }
+
+        let constant = series.coeff(0);
+        let rest = series
+            .add(&Series::constant(Expr::neg(constant.clone()), series.order))
+            .trim();
+
+        Ok((constant, rest))
+    }
+
+    /// Σ coeffs[k] self^k of a series without a constant term.
+    fn compose(&self, coeffs: &[Expr]) -> Series {
+        let order = self.order;
+        let mut result = Series::constant(coeffs[0].clone(), order);
+        let mut power = Series::constant(Expr::int(1), order);
+
+        for (k, coeff) in coeffs.iter().enumerate().skip(1) {
+            if self.val <= 0 || k as i64 * self.val >= order {
+                break;
+            }
+
+            power = power.mul(self);
+            result = result.add(&power.scale(coeff));
+        }
+
+        result
+    }
+
+    /// `1/k!` for every power below the order.
+    fn factorials(order: i64) -> Vec<Number> {
+        let mut fact = Number::int(1);
+
+        (0..order.max(1))
+            .map(|k| {
+                if k > 0 {
+                    fact = fact.clone() * Number::int(k as i128);
+                }
+                Number::int(1) / fact.clone()
+            })
+            .collect()
+    }
+
+    /// Taylor coefficients of the even or odd part of exp,
+    /// with alternating signs for cos and sin.
+    fn taylor(order: i64, sign: i128, odd: bool) -> Vec<Expr> {
+        Series::factorials(order)
+            .into_iter()
+            .enumerate()
+            .map(
+                |(k, coeff)| match ((k % 2 == 1) == odd, sign < 0 && (k / 2) % 2 == 1) {
+                    (false, _) => Expr::int(0),
+                    (true, false) => Expr::num(coeff),
+                    (true, true) => Expr::num(-coeff),
+                },
+            )
+            .collect()
+    }
+
+    fn derive(&self) -> Series {
+        Series::new(
+            self.val - 1,
+            (self.val..self.order)
+                .map(|exp| Expr::mul(vec![Expr::int(exp as i128), self.coeff(exp)]))
+                .collect(),
+            self.order - 1,
+        )
+    }
+
+    fn integrate(&self) -> Result<Series, TypeErr> {
+        if !self.coeff(-1).is_zero() {
+            return Err(TypeErr("series has a logarithmic singularity".to_string()));
+        }
+
+        Ok(Series::new(
+            self.val + 1,
+            (self.val..self.order)
+                .map(|exp| match exp {
+                    -1 => Expr::int(0),
+                    _ => Expr::mul(vec![
+                        Expr::num(Number::ratio(1, exp as i128 + 1)),
+                        self.coeff(exp),
+                    ]),
+                })
+                .collect(),
+            self.order + 1,
+        ))
+    }
+
+    /// `f(self) = f(c) + ∫ f'(self) self' dt` for functions with an algebraic derivative.
+    fn antiderivative(&self, name: &str, derivative: Series) -> Result<Series, TypeErr> {
+        let (constant, _) = self.split()?;
+        let start = Expr::function(Symbol(name.to_string()), constant);
+        let integral = derivative.mul(&self.derive()).integrate()?;
+
+        Ok(integral.add(&Series::constant(start, integral.order)))
+    }
+
+    /// Compose a built-in function with the series.
+    fn function(&self, name: &str) -> Result<Series, TypeErr> {
+        let call = |name: &str, x: &Expr| Expr::function(Symbol(name.to_string()), x.clone());
+
+        if self.order <= 0 {
+            return Ok(if self.clone().trim().val < 0 {
+                Series::unknown()
+            } else {
+                Series::new(0, Vec::new(), 0)
+            });
+        }
+
+        // 1 ± self^2, for the derivatives of the inverse functions
+        let square = |sign: i128, one: i128| {
+            self.mul(self)
+                .scale(&Expr::int(sign))
+                .add(&Series::constant(Expr::int(one), self.order))
+        };
+
+        Ok(match name {
+            "exp" => {
+                let (c, u) = self.split()?;
+                let coeffs: Vec<Expr> = Series::factorials(self.order)
+                    .into_iter()
+                    .map(Expr::num)
+                    .collect();
+
+                u.compose(&coeffs).scale(&call("exp", &c))
+            }
+            "sin" | "cos" | "sinh" | "cosh" => {
+                let (c, u) = self.split()?;
+                let (sin, cos, sign) = match name {
+                    "sin" | "cos" => ("sin", "cos", -1),
+                    _ => ("sinh", "cosh", 1),
+                };
+
+                let even = u.compose(&Series::taylor(self.order, sign, false));
+                let odd = u.compose(&Series::taylor(self.order, sign, true));
+
+                // sin(c + u) = sin c cos u + cos c sin u
+                // cos(c + u) = cos c cos u - sin c sin u
+                if name == sin {
+                    even.scale(&call(sin, &c)).add(&odd.scale(&call(cos, &c)))
+                } else {
+                    even.scale(&call(cos, &c))
+                        .add(&odd.scale(&Expr::mul(vec![Expr::int(sign), call(sin, &c)])))
+                }
+            }
+            "tan" => self.function("sin")?.mul(&self.function("cos")?.inverse()),
+            "tanh" => self
+                .function("sinh")?
+                .mul(&self.function("cosh")?.inverse()),
+            "ln" | "lg" => {
+                let series = self.clone().trim();
+                let lead = match series.coeffs.first() {
+                    Some(lead) => lead.clone(),
+                    None => return Ok(Series::unknown()),
+                };
+
+                if series.val != 0 {
+                    return Err(TypeErr("series has a logarithmic singularity".to_string()));
+                }
+
+                let w = series
+                    .scale(&Expr::pow(lead.clone(), Expr::int(-1)))
+                    .add(&Series::constant(Expr::int(-1), series.order))
+                    .trim();
+
+                let mut coeffs = vec![Expr::int(0)];
+                for k in 1..self.order.max(1) {
+                    let sign = if k % 2 == 1 { 1 } else { -1 };
+                    coeffs.push(Expr::num(Number::ratio(sign, k as i128)));
+                }
+
+                let ln = w.compose(&coeffs);
+                let ln = ln.add(&Series::constant(call("ln", &lead), ln.order));
+
+                if name == "lg" {
+                    ln.scale(&Expr::pow(call("ln", &Expr::int(10)), Expr::int(-1)))
+                } else {
+                    ln
+                }
+            }
+            "sqrt" => self.pow(&Expr::num(Number::ratio(1, 2)))?,
+            "cbrt" => self.pow(&Expr::num(Number::ratio(1, 3)))?,
+            "atan" => self.antiderivative(name, square(1, 1).inverse())?,
+            "atanh" => self.antiderivative(name, square(-1, 1).inverse())?,
+            "asin" => {
+                self.antiderivative(name, square(-1, 1).pow(&Expr::num(Number::ratio(-1, 2)))?)?
+            }
+            "acos" => self.antiderivative(
+                name,
+                square(-1, 1)
+                    .pow(&Expr::num(Number::ratio(-1, 2)))?
+                    .scale(&Expr::int(-1)),
+            )?,
+            "asinh" => {
+                self.antiderivative(name, square(1, 1).pow(&Expr::num(Number::ratio(-1, 2)))?)?
+            }
+            "acosh" => {
+                self.antiderivative(name, square(1, -1).pow(&Expr::num(Number::ratio(-1, 2)))?)?
+            }
+            "abs" => {
+                let series = self.clone().trim();
+                match series.coeffs.first().and_then(Expr::as_number) {
+                    Some(lead) if lead.is_negative() => series.scale(&Expr::int(-1)),
+                    Some(_) => series,
+                    None => {
+                        return Err(TypeErr(
+                            "series of `abs` needs a numeric leading coefficient".to_string(),
+                        ))
+                    }
+                }
+            }
+            _ => return Err(TypeErr(format!("series of `{}` is undefined", name))),
+        })
+    }
+
+    /// Expand a canonical expression in `t = var - x0` up to `O(t^order)`.
+    fn of(expr: &Expr, var: &Symbol, x0: &Expr, order: i64) -> Result<Series, TypeErr> {
+        // order terms in other variables are constants, `O(x^2) + O(y^2)` stays apart
+        if !expr.contains(var) {
+            return Ok(Series::constant(expr.clone(), order));
+        }
+
+        Ok(match expr {
+            Expr::Atom(_) => Series::new(0, vec![x0.clone(), Expr::int(1)], order),
+            Expr::Call(Call { op: Op::Add, args }) => {
+                let mut sum = Series::of(&args[0], var, x0, order)?;
+                for arg in &args[1..] {
+                    sum = sum.add(&Series::of(arg, var, x0, order)?);
+                }
+                sum
+            }
+            Expr::Call(Call { op: Op::Mul, args }) => {
+                let mut product = Series::of(&args[0], var, x0, order)?;
+                for arg in &args[1..] {
+                    product = product.mul(&Series::of(arg, var, x0, order)?);
+                }
+                product
+            }
+            Expr::Call(Call { op: Op::Pow, args }) => {
+                let base = Series::of(&args[0], var, x0, order)?;
+
+                if args[1].contains(var) {
+                    // b^e = exp(e ln b)
+                    base.function("ln")?
+                        .mul(&Series::of(&args[1], var, x0, order)?)
+                        .function("exp")?
+                } else {
+                    base.pow(&args[1])?
+                }
+            }
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) if args.len() == 1 => {
+                let arg = Series::of(&args[0], var, x0, order)?;
+
+                if call.0 == "O" {
+                    let arg = arg.trim();
+                    Series::new(arg.val, Vec::new(), arg.val)
+                } else {
+                    arg.function(&call.0)?
+                }
+            }
+            _ => return Err(TypeErr(format!("series of `{}` is undefined", expr))),
+        })
+    }
+
+    /// Expand up to `O(t^order)`, calculating more terms where they cancel.
+    fn expand(expr: &Expr, var: &Symbol, x0: &Expr, order: i64) -> Result<Series, TypeErr> {
+        if order.abs() > MAX_ORDER {
+            return Err(TypeErr(format!(
+                "series order {} is out of range, at most {} terms are supported",
+                order, MAX_ORDER
+            )));
+        }
+
+        let mut extra = 0;
+        let mut last = None;
+
+        loop {
+            let series = Series::of(expr, var, x0, order + extra)?.truncate(order);
+
+            // an explicit order term might limit the precision
+            if series.order >= order || last == Some(series.order) || extra >= MAX_EXTRA {
+                if series.order <= UNKNOWN / 2 {
+                    return Err(TypeErr(format!(
+                        "could not expand `{}` into a series",
+                        expr
+                    )));
+                }
+
+                return Ok(series);
+            }
+
+            last = Some(series.order);
+            extra = extra * 2 + 2;
+        }
+    }
+
+    /// Expand as far as the order terms allow, which might be beyond their own orders,
+    /// like `O(x^5)` of `x^3 O(x^2)`.
+    fn absorb(expr: &Expr, var: &Symbol, x0: &Expr, mut order: i64) -> Result<Series, TypeErr> {
+        loop {
+            let series = Series::expand(expr, var, x0, order)?;
+
+            if series.order < order || order >= MAX_ORDER {
+                return Ok(series);
+            }
+
+            order = (order + order.abs().max(1)).min(MAX_ORDER);
+        }
+    }
+
+    /// Leading term `(c, v)` of `c t^v`, expanding further until a coefficient is nonzero.
+    pub(crate) fn leading(expr: &Expr, var: &Symbol, x0: &Expr) -> Result<(Expr, i64), TypeErr> {
+        let mut order = 1;
+
+        loop {
+            let series = Series::expand(expr, var, x0, order)?.trim();
+
+            if let Some(lead) = series.coeffs.first() {
+                return Ok((lead.clone(), series.val));
+            }
+            if order >= MAX_EXTRA {
+                return Err(TypeErr(format!("`{}` has no leading term", expr)));
+            }
+
+            order *= 2;
+        }
+    }
+
+    /// Back into an expression in `var`.
+    fn expr(&self, var: &Symbol, x0: &Expr) -> Expr {
+        let t = Expr::sub(Expr::Atom(Atom::Symbol(var.clone())), x0.clone());
+
+        let mut terms = Vec::with_capacity(self.coeffs.len() + 1);
+        for (exp, coeff) in (self.val..).zip(&self.coeffs) {
+            terms.push(Expr::mul(vec![
+                coeff.clone(),
+                Expr::pow(t.clone(), Expr::int(exp as i128)),
+            ]));
+        }
+
+        terms.push(Expr::Call(Call::new(
+            Op::Call(Symbol("O".to_string())),
+            vec![Expr::pow(t, Expr::int(self.order as i128))],
+        )));
+
+        Expr::add(terms)
+    }
+}
+
+impl Expr {
+    /// `series(expr, x, x0, n)` is the expansion of expr around `x = x0` up to `O((x - x0)^n)`.
+    pub(crate) fn series(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (expr, var, x0, order) = match args {
+            [expr, var] => (expr, var, Expr::int(0), SERIES_ORDER),
+            [expr, var, x0] => (expr, var, x0.eval(env)?, SERIES_ORDER),
+            [expr, var, x0, order] => (
+                expr,
+                var,
+                x0.eval(env)?,
+                order
+                    .eval(env)?
+                    .as_number()
+                    .and_then(|order| order.as_int())
+                    .ok_or_else(|| TypeErr(format!("series order `{}` is no integer", order)))?
+                    as i64,
+            ),
+            _ => return Err(TypeErr(
+                "call `series` undefined on (expr, ...), expected (expr, symbol, expr, integer)"
+                    .to_string(),
+            )),
+        };
+
+        let var = var.symbol()?;
+        let expr = expr.eval(env)?;
+
+        Ok(Series::expand(&expr, &var, &x0, order)?.expr(&var, &x0))
+    }
+
+    /// Let an order term swallow everything it dominates, e.g. `x^3 + O(x^2)` is `O(x^2)`,
+    /// one variable after the other.
+    pub(crate) fn order_terms(self) -> Result<Expr, TypeErr> {
+        let mut found = Vec::new();
+        self.find_orders(&mut found);
+
+        let mut expr = self;
+        for (var, x0, order) in found {
+            expr = Series::absorb(&expr, &var, &x0, order)?.expr(&var, &x0);
+        }
+
+        Ok(expr)
+    }
+
+    /// The argument of an order term, where inner order terms are their arguments,
+    /// so `O(x^2 O(x^3))` is `O(x^5)`.
+    pub(crate) fn order_arg(&self) -> Expr {
+        match self {
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) if call.0 == "O" && args.len() == 1 => args[0].order_arg(),
+            Expr::Call(Call { op, args }) => {
+                Expr::rebuild(op, args.iter().map(Expr::order_arg).collect())
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// The variable, point and order of the first order term in each variable.
+    fn find_orders(&self, found: &mut Vec<(Symbol, Expr, i64)>) {
+        match self.find_order() {
+            Some((var, x0, order)) => {
+                if found.iter().all(|(other, _, _)| *other != var) {
+                    found.push((var, x0, order));
+                }
+            }
+            None => {
+                if let Expr::Call(Call { args, .. }) = self {
+                    for arg in args {
+                        arg.find_orders(found);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The variable, point and order of an order term, like `O((x - x0)^n)`.
+    fn find_order(&self) -> Option<(Symbol, Expr, i64)> {
+        match self {
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) if call.0 == "O" && args.len() == 1 => {
+                let (base, order) = match &args[0] {
+                    Expr::Call(Call { op: Op::Pow, args }) => {
+                        (&args[0], args[1].as_number()?.as_int()? as i64)
+                    }
+                    base => (base, 1),
+                };
+
+                // the base has to be `x - x0`
+                let var = match base {
+                    Expr::Atom(Atom::Symbol(var)) => var.clone(),
+                    Expr::Call(Call { op: Op::Add, args }) => {
+                        args.iter().find_map(|arg| match arg {
+                            Expr::Atom(Atom::Symbol(var)) => Some(var.clone()),
+                            _ => None,
+                        })?
+                    }
+                    _ => return None,
+                };
+
+                let x0 = Expr::neg(Expr::sub(
+                    base.clone(),
+                    Expr::Atom(Atom::Symbol(var.clone())),
+                ));
+                if x0.contains(&var) {
+                    return None;
+                }
+
+                Some((var, x0, order))
+            }
+            _ => None,
+        }
+    }
+}
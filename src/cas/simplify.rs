@@ -0,0 +1,617 @@
+use {
+    super::{
+        env::{Def, Env},
+        expr::{
+            atom::{number::Number, symbol::Symbol, Atom},
+            call::{op::Op, Call},
+            Expr,
+        },
+    },
+    std::cmp::Ordering,
+};
+
+/// The canonical form of an expression knows no `-` or `/`: `a - b` is `a + -1 * b`
+/// and `a / b` is `a * b^-1`. Sums and products are flat, sorted by [`Expr::order`]
+/// and have their like terms collected. Numbers are only folded, if that is exact.
+impl Expr {
+    pub(crate) fn num(number: Number) -> Expr {
+        Expr::Atom(Atom::Number(number))
+    }
+
+    pub(crate) fn int(int: i128) -> Expr {
+        Expr::num(Number::int(int))
+    }
+
+    pub(crate) fn as_number(&self) -> Option<Number> {
+        match self {
+            Expr::Atom(Atom::Number(number)) => Some(number.clone()),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.as_number().is_some_and(|number| number.is_zero())
+    }
+
+    pub(crate) fn is_negative(&self) -> bool {
+        self.as_number().is_some_and(|number| number.is_negative())
+    }
+
+    /// Sum of terms.
+    pub(crate) fn add(terms: Vec<Expr>) -> Expr {
+        let terms = flatten(&Op::Add, terms);
+        if let Some(sum) = Expr::infinite_sum(&terms) {
+            return sum;
+        }
+
+        let mut number = Number::int(0);
+        let mut collected: Vec<(Expr, Number)> = Vec::new();
+
+        for term in terms {
+            if let Some(term) = term.as_number() {
+                number = number + term;
+                continue;
+            }
+
+            let (coef, rest) = term.split_coef();
+
+            // order terms absorb their coefficients and each other, `O(x) - O(x)` is `O(x)`
+            if matches!(&rest, Expr::Call(Call { op: Op::Call(call), .. }) if call.0 == "O") {
+                if !collected.iter().any(|(other, _)| other == &rest) {
+                    collected.push((rest, Number::int(1)));
+                }
+                continue;
+            }
+
+            match collected.iter_mut().find(|(other, _)| other == &rest) {
+                Some((_, other)) => *other = other.clone() + coef,
+                None => collected.push((rest, coef)),
+            }
+        }
+
+        collected.retain(|(_, coef)| !coef.is_zero());
+        collected.sort_by(|(a, _), (b, _)| a.order(b));
+
+        let mut args = Vec::with_capacity(collected.len() + 1);
+        if !number.is_zero() {
+            args.push(Expr::num(number.clone()));
+        }
+        for (rest, coef) in collected {
+            args.push(Expr::mul(vec![Expr::num(coef), rest]));
+        }
+
+        match args.len() {
+            0 => Expr::num(number),
+            1 => args.remove(0),
+            _ => Expr::Call(Call::new(Op::Add, args)),
+        }
+    }
+
+    /// Product of factors.
+    pub(crate) fn mul(factors: Vec<Expr>) -> Expr {
+        let factors = flatten(&Op::Mul, factors);
+        if let Some(product) = Expr::infinite_product(&factors) {
+            return product;
+        }
+
+        let mut number = Number::int(1);
+        let mut collected: Vec<(Expr, Expr)> = Vec::new();
+
+        for factor in factors {
+            let (base, exp) = match factor {
+                Expr::Atom(Atom::Number(factor)) => {
+                    number = number * factor;
+                    continue;
+                }
+                Expr::Call(Call {
+                    op: Op::Pow,
+                    mut args,
+                }) => {
+                    let exp = args.remove(1);
+                    (args.remove(0), exp)
+                }
+                // `exp a exp b = exp(a + b)` like any other power
+                Expr::Call(Call {
+                    op: Op::Call(Symbol(call)),
+                    mut args,
+                }) if call == "exp" => (Expr::e(), args.remove(0)),
+                factor => (factor, Expr::int(1)),
+            };
+
+            match collected.iter_mut().find(|(other, _)| other == &base) {
+                Some((_, other)) => *other = Expr::add(vec![other.clone(), exp]),
+                None => collected.push((base, exp)),
+            }
+        }
+
+        if number.is_zero() {
+            // `0 / 0` is undefined
+            if collected
+                .iter()
+                .any(|(base, exp)| base.is_zero() && exp.is_negative())
+            {
+                return Expr::nan();
+            }
+            return Expr::num(number);
+        }
+
+        let mut args = Vec::with_capacity(collected.len() + 1);
+        // a power might fall apart into a number or a new product
+        let mut changed = false;
+
+        for (base, exp) in collected {
+            match Expr::pow(base, exp) {
+                Expr::Atom(Atom::Number(factor)) => number = number * factor,
+                Expr::Call(Call {
+                    op: Op::Mul,
+                    args: factors,
+                }) => {
+                    changed = true;
+                    args.extend(factors);
+                }
+                factor => args.push(factor),
+            }
+        }
+
+        if changed {
+            args.push(Expr::num(number.clone()));
+            return Expr::mul(args);
+        }
+
+        args.sort_by(|a, b| a.order(b));
+        if !number.is_one() {
+            args.insert(0, Expr::num(number.clone()));
+        }
+
+        match args.len() {
+            0 => Expr::num(number),
+            1 => args.remove(0),
+            _ => Expr::Call(Call::new(Op::Mul, args)),
+        }
+    }
+
+    /// Power of a base.
+    pub(crate) fn pow(base: Expr, exp: Expr) -> Expr {
+        if let Some(number) = exp.as_number() {
+            if number == Number::int(0) {
+                return Expr::int(1);
+            }
+            if number == Number::int(1) {
+                return base;
+            }
+        }
+
+        if let Some(power) = Expr::constant_pow(&base, &exp) {
+            return power;
+        }
+        if let Some(power) = Expr::infinite_pow(&base, &exp) {
+            return power;
+        }
+
+        match (&base, &exp) {
+            (Expr::Atom(Atom::Number(x)), Expr::Atom(Atom::Number(y))) => {
+                if let Some(number) = fold(x.is_exact() && y.is_exact(), x.clone().pow(y.clone())) {
+                    return Expr::num(number);
+                }
+            }
+            (Expr::Atom(Atom::Number(x)), _) if *x == Number::int(1) => return base,
+            (Expr::Call(Call { op: Op::Pow, args }), Expr::Atom(Atom::Number(y)))
+                if y.as_int().is_some() =>
+            {
+                return Expr::pow(args[0].clone(), Expr::mul(vec![args[1].clone(), exp]));
+            }
+            (Expr::Call(Call { op: Op::Mul, args }), Expr::Atom(Atom::Number(y)))
+                if y.as_int().is_some() =>
+            {
+                return Expr::mul(
+                    args.iter()
+                        .map(|arg| Expr::pow(arg.clone(), exp.clone()))
+                        .collect(),
+                );
+            }
+            _ => {}
+        }
+
+        Expr::Call(Call::new(Op::Pow, vec![base, exp]))
+    }
+
+    pub(crate) fn neg(x: Expr) -> Expr {
+        Expr::mul(vec![Expr::int(-1), x])
+    }
+
+    pub(crate) fn sub(x: Expr, y: Expr) -> Expr {
+        Expr::add(vec![x, Expr::neg(y)])
+    }
+
+    pub(crate) fn div(x: Expr, y: Expr) -> Expr {
+        Expr::mul(vec![x, Expr::pow(y, Expr::int(-1))])
+    }
+
+    /// Built-in function of one argument, folded if the argument is a number.
+    pub(crate) fn function(call: Symbol, x: Expr) -> Expr {
+        if call.0 == "sqrt" {
+            return Expr::pow(x, Expr::num(Number::ratio(1, 2)));
+        }
+
+        if let Some(value) = Expr::infinite_function(&call.0, &x) {
+            return value;
+        }
+        if let Some(value) = Expr::exact_trig(&call.0, &x) {
+            return value;
+        }
+        if let Some(value) = Expr::exact_constant(&call.0, &x) {
+            return value;
+        }
+        if let Some(value) = Expr::exact_special(&call.0, &x) {
+            return value;
+        }
+        if call.0 == "exp" {
+            if let Some(value) = Expr::exp_of_logs(&x) {
+                return value;
+            }
+        }
+
+        if let (Some(number), Some(function)) = (x.as_number(), Expr::builtin_one(&call.0)) {
+            if number.is_interval() {
+                return Expr::num(number.enclose_one(&call.0));
+            }
+            if number.is_uncertain() {
+                let value = function(Number::Float(number.as_f64()));
+                return Expr::num(
+                    number
+                        .clone()
+                        .propagate(value, Expr::slopes(&call, &[number.as_f64()])[0]),
+                );
+            }
+            if number.is_dual() {
+                let value = function(Number::Float(number.as_f64()));
+                return Expr::num(
+                    number
+                        .clone()
+                        .chain(value, Expr::slopes(&call, &[number.as_f64()])[0]),
+                );
+            }
+
+            let value = function(number.clone());
+            if let Some(number) = fold(number.is_exact(), value.clone()) {
+                return Expr::num(number);
+            }
+            // a pole like `ln 0` is an infinity with a sign
+            if value.as_f64().is_infinite() {
+                return Expr::infinity(!value.is_negative());
+            }
+        }
+
+        Expr::Call(Call::new(Op::Call(call), vec![x]))
+    }
+
+    /// Operation on two numbers, only folded if that is exact.
+    pub(crate) fn fold_two(op: Op, x: Expr, y: Expr, call: fn(Number, Number) -> Number) -> Expr {
+        if let (Some(a), Some(b)) = (x.as_number(), y.as_number()) {
+            if let Some(number) = fold(a.is_exact() && b.is_exact(), call(a, b)) {
+                return Expr::num(number);
+            }
+        }
+
+        Expr::Call(Call::new(op, vec![x, y]))
+    }
+
+    /// Apply an op to already canonical arguments, without any enviroment.
+    pub(crate) fn rebuild(op: &Op, mut args: Vec<Expr>) -> Expr {
+        match (op, args.len()) {
+            (Op::Add, _) => Expr::add(args),
+            (Op::Mul, _) => Expr::mul(args),
+            (Op::Sub, 1) => Expr::neg(args.remove(0)),
+            (Op::Sub, 2) => Expr::sub(args.remove(0), args.remove(0)),
+            (Op::Div, 2) => Expr::div(args.remove(0), args.remove(0)),
+            (Op::Pow, 2) => Expr::pow(args.remove(0), args.remove(0)),
+            (Op::Mod, 2) => {
+                Expr::fold_two(Op::Mod, args.remove(0), args.remove(0), Number::modulus)
+            }
+            // invalid bounds are left for eval to report
+            (Op::Interval, 2) => Expr::hull(args[0].clone(), args[1].clone())
+                .unwrap_or_else(|_| Expr::Call(Call::new(Op::Interval, args))),
+            (Op::PlusMinus, 2) => Expr::plus_minus(args.remove(0), args.remove(0)),
+            (Op::Fact, 1) => match args[0].as_number() {
+                Some(_) => Expr::function(Symbol("fact".to_string()), args.remove(0)),
+                None => Expr::Call(Call::new(Op::Fact, args)),
+            },
+            (Op::Call(call), 1) if call.0 == "sqrt" || Expr::builtin_one(&call.0).is_some() => {
+                Expr::function(call.clone(), args.remove(0))
+            }
+            (Op::Call(call), 2) if call.0 == "root" => {
+                Expr::pow(args.remove(0), Expr::div(Expr::int(1), args.remove(0)))
+            }
+            (Op::Call(call), 2) if call.0 == "log" => {
+                Expr::logarithm(args.remove(0), args.remove(0))
+            }
+            (Op::Call(call), 2) if Expr::builtin_two(&call.0).is_some() => {
+                let function = Expr::builtin_two(&call.0).expect("checked by the guard");
+
+                match (args[0].as_number(), args[1].as_number()) {
+                    (Some(x), Some(y)) if x.is_interval() || y.is_interval() => {
+                        Expr::num(x.enclose_two(&call.0, y))
+                    }
+                    (Some(x), Some(y)) if x.is_uncertain() || y.is_uncertain() => {
+                        Expr::num(x.propagate_two(y, function))
+                    }
+                    (Some(x), Some(y)) if x.is_dual() || y.is_dual() => {
+                        let value = function(Number::Float(x.as_f64()), Number::Float(y.as_f64()));
+                        let slopes = Expr::slopes(call, &[x.as_f64(), y.as_f64()]);
+                        Expr::num(x.chain_two(y, value, (slopes[0], slopes[1])))
+                    }
+                    _ => Expr::fold_two(op.clone(), args.remove(0), args.remove(0), function),
+                }
+            }
+            _ => Expr::Call(Call::new(op.clone(), args)),
+        }
+    }
+
+    /// Canonical form of an unevaluated expression, without any enviroment.
+    pub(crate) fn canonical(&self) -> Expr {
+        match self {
+            Expr::Call(Call { op, args }) => {
+                Expr::rebuild(op, args.iter().map(Expr::canonical).collect())
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Split into numeric coefficient and the rest.
+    pub(crate) fn split_coef(&self) -> (Number, Expr) {
+        if let Expr::Call(Call { op: Op::Mul, args }) = self {
+            if let Some(coef) = args[0].as_number() {
+                let rest = if args.len() == 2 {
+                    args[1].clone()
+                } else {
+                    Expr::Call(Call::new(Op::Mul, args[1..].to_vec()))
+                };
+
+                return (coef, rest);
+            }
+        }
+
+        (Number::int(1), self.clone())
+    }
+
+    /// Does the expression depend on the symbol?
+    pub(crate) fn contains(&self, symbol: &Symbol) -> bool {
+        match self {
+            Expr::Atom(Atom::Symbol(other)) => other == symbol,
+            Expr::Atom(_) => false,
+            Expr::Call(Call { args, .. }) => args.iter().any(|arg| arg.contains(symbol)),
+        }
+    }
+
+    /// A symbol like `t`, `t1`, ... that does not occur in the expression.
+    pub(crate) fn fresh(&self, name: &str) -> Symbol {
+        std::iter::once(name.to_string())
+            .chain((1..).map(|i| format!("{}{}", name, i)))
+            .map(Symbol)
+            .find(|symbol| !self.contains(symbol))
+            .expect("there is always a fresh symbol")
+    }
+
+    /// Is there a call by this name anywhere in the expression?
+    pub(crate) fn calls(&self, name: &str) -> bool {
+        match self {
+            Expr::Atom(_) => false,
+            Expr::Call(Call { op, args }) => {
+                matches!(op, Op::Call(Symbol(call)) if call == name)
+                    || args.iter().any(|arg| arg.calls(name))
+            }
+        }
+    }
+
+    /// Replace every number and constant by its float.
+    pub(crate) fn approx(&self, env: &Env) -> Expr {
+        match self {
+            Expr::Atom(Atom::Number(number)) => Expr::num(number.approx()),
+            Expr::Atom(Atom::Symbol(symbol)) => {
+                match (env.get(symbol), Number::constant(&symbol.0)) {
+                    (Ok(Def::OSConst), Some(number)) => Expr::num(number),
+                    _ => self.clone(),
+                }
+            }
+            Expr::Call(Call { op, args }) => Expr::Call(Call::new(
+                op.clone(),
+                args.iter().map(|arg| arg.approx(env)).collect(),
+            )),
+        }
+    }
+
+    /// Replace every occurence of a subexpression and simplify again.
+    pub(crate) fn replace(&self, from: &Expr, to: &Expr) -> Expr {
+        if self == from {
+            return to.clone();
+        }
+
+        match self {
+            Expr::Call(Call { op, args }) => {
+                Expr::rebuild(op, args.iter().map(|arg| arg.replace(from, to)).collect())
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Total order of canonical expressions: numbers, symbols, then calls.
+    /// A power is sorted next to its base, so `x < x^2 < y`.
+    pub(crate) fn order(&self, other: &Expr) -> Ordering {
+        match (self, other) {
+            (Expr::Atom(Atom::Number(a)), Expr::Atom(Atom::Number(b))) => a
+                .as_f64()
+                .partial_cmp(&b.as_f64())
+                .unwrap_or(Ordering::Equal),
+            (Expr::Atom(Atom::Number(_)), _) => Ordering::Less,
+            (_, Expr::Atom(Atom::Number(_))) => Ordering::Greater,
+
+            // the order term always comes last
+            (
+                Expr::Call(Call {
+                    op: Op::Call(a), ..
+                }),
+                _,
+            ) if a.0 == "O" => {
+                if matches!(other, Expr::Call(Call { op: Op::Call(b), .. }) if b.0 == "O") {
+                    Ordering::Equal
+                } else {
+                    Ordering::Greater
+                }
+            }
+            (
+                _,
+                Expr::Call(Call {
+                    op: Op::Call(b), ..
+                }),
+            ) if b.0 == "O" => Ordering::Less,
+
+            (
+                Expr::Call(Call {
+                    op: Op::Pow,
+                    args: a,
+                }),
+                Expr::Call(Call {
+                    op: Op::Pow,
+                    args: b,
+                }),
+            ) => a[0].order(&b[0]).then_with(|| a[1].order(&b[1])),
+            (Expr::Call(Call { op: Op::Pow, args }), _) => args[0]
+                .order(other)
+                .then_with(|| args[1].order(&Expr::int(1))),
+            (_, Expr::Call(Call { op: Op::Pow, .. })) => other.order(self).reverse(),
+
+            (Expr::Atom(Atom::Symbol(a)), Expr::Atom(Atom::Symbol(b))) => a.0.cmp(&b.0),
+            (Expr::Atom(Atom::Symbol(_)), _) => Ordering::Less,
+            (_, Expr::Atom(Atom::Symbol(_))) => Ordering::Greater,
+
+            (Expr::Call(a), Expr::Call(b)) => format!("{:?}", a.op)
+                .cmp(&format!("{:?}", b.op))
+                .then_with(|| a.args.len().cmp(&b.args.len()))
+                .then_with(|| {
+                    a.args
+                        .iter()
+                        .zip(&b.args)
+                        .map(|(a, b)| a.order(b))
+                        .find(|order| *order != Ordering::Equal)
+                        .unwrap_or(Ordering::Equal)
+                }),
+        }
+    }
+
+    /// Turn the canonical form back into something readable: negative terms
+    /// get a `-` and negative powers go into a denominator.
+    pub(crate) fn tidy(&self) -> Expr {
+        match self {
+            Expr::Call(Call { op: Op::Mul, .. }) => {
+                let (coef, rest) = self.split_coef();
+                let (num, den) = match coef {
+                    Number::Ratio(num, den) => (
+                        num.checked_abs()
+                            .map_or(Number::Float((num as f64).abs()), Number::int),
+                        Number::int(den),
+                    ),
+                    Number::Float(float) => (Number::Float(float.abs()), Number::int(1)),
+                    Number::Interval(_, _) | Number::Uncertain(_, _) | Number::Dual(_, _)
+                        if coef.is_negative() =>
+                    {
+                        (-coef.clone(), Number::int(1))
+                    }
+                    Number::Interval(_, _) | Number::Uncertain(_, _) | Number::Dual(_, _) => {
+                        (coef.clone(), Number::int(1))
+                    }
+                    // the negation could wrap around
+                    Number::Fixed(_, _) => (coef.clone(), Number::int(1)),
+                };
+
+                let mut numerator = Vec::new();
+                let mut denominator = Vec::new();
+
+                if !num.is_one() {
+                    numerator.push(Expr::num(num));
+                }
+                if !den.is_one() {
+                    denominator.push(Expr::num(den));
+                }
+
+                for factor in flatten(&Op::Mul, vec![rest]) {
+                    match factor {
+                        Expr::Call(Call {
+                            op: Op::Pow,
+                            ref args,
+                        }) if args[1].as_number().is_some_and(|exp| exp.is_negative()) => {
+                            denominator
+                                .push(Expr::pow(args[0].clone(), Expr::neg(args[1].clone())).tidy())
+                        }
+                        factor => numerator.push(factor.tidy()),
+                    }
+                }
+
+                let product = |mut factors: Vec<Expr>| match factors.len() {
+                    0 => Expr::int(1),
+                    1 => factors.remove(0),
+                    _ => Expr::Call(Call::new(Op::Mul, factors)),
+                };
+
+                let expr = if denominator.is_empty() {
+                    product(numerator)
+                } else {
+                    Expr::Call(Call::new(
+                        Op::Div,
+                        vec![product(numerator), product(denominator)],
+                    ))
+                };
+
+                if coef.is_negative() {
+                    Expr::Call(Call::new(Op::Sub, vec![expr]))
+                } else {
+                    expr
+                }
+            }
+            Expr::Call(Call { op: Op::Pow, args }) => {
+                if args[1].as_number().is_some_and(|exp| exp.is_negative()) {
+                    Expr::Call(Call::new(
+                        Op::Div,
+                        vec![
+                            Expr::int(1),
+                            Expr::pow(args[0].clone(), Expr::neg(args[1].clone())).tidy(),
+                        ],
+                    ))
+                } else if args[1] == Expr::num(Number::ratio(1, 2)) {
+                    Expr::Call(Call::new(
+                        Op::Call(Symbol("sqrt".to_string())),
+                        vec![args[0].tidy()],
+                    ))
+                } else {
+                    Expr::Call(Call::new(Op::Pow, vec![args[0].tidy(), args[1].tidy()]))
+                }
+            }
+            Expr::Call(Call { op, args }) => {
+                Expr::Call(Call::new(op.clone(), args.iter().map(Expr::tidy).collect()))
+            }
+            _ => self.clone(),
+        }
+    }
+}
+
+/// Only fold into a number, if no exactness is lost.
+pub(crate) fn fold(exact: bool, number: Number) -> Option<Number> {
+    if number.is_exact() || !exact {
+        Some(number)
+    } else {
+        None
+    }
+}
+
+/// Pull the arguments of nested calls of the same op up.
+fn flatten(op: &Op, exprs: Vec<Expr>) -> Vec<Expr> {
+    let mut flat = Vec::with_capacity(exprs.len());
+
+    for expr in exprs {
+        match expr {
+            Expr::Call(Call { op: inner, args }) if &inner == op => flat.extend(flatten(op, args)),
+            expr => flat.push(expr),
+        }
+    }
+
+    flat
+}
@@ -0,0 +1,31 @@
+use super::expr::{
+    atom::{number::Number, symbol::Symbol},
+    Expr,
+};
+
+/// Special functions are numeric, apart from a few values in closed form.
+impl Expr {
+    /// `ζ(2n)` in powers of `π`, and the values of `W` and the complete elliptic integrals.
+    pub(crate) fn exact_special(call: &str, x: &Expr) -> Option<Expr> {
+        match call {
+            "zeta" => {
+                let s = x.as_number()?.as_int().filter(|s| *s > 0 && s % 2 == 0)?;
+                // ζ(2n) = (-1)^(n + 1) B(2n) 2^(2n - 1) / (2n)! π^(2n)
+                let coef = Number::bernoulli(s).abs() * Number::int(2).pow(Number::int(s - 1))
+                    / Number::int(s).fact();
+                coef.is_exact()
+                    .then(|| Expr::mul(vec![Expr::num(coef), Expr::pow(Expr::pi(), Expr::int(s))]))
+            }
+            "lambertw" if *x == Expr::e() => Some(Expr::int(1)),
+            "lambertw" if *x == Expr::neg(exp(Expr::int(-1))) => Some(Expr::int(-1)),
+            "ellipk" | "ellipe" if *x == Expr::int(0) => {
+                Some(Expr::mul(vec![Expr::num(Number::ratio(1, 2)), Expr::pi()]))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn exp(x: Expr) -> Expr {
+    Expr::function(Symbol("exp".to_string()), x)
+}
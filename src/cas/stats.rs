@@ -0,0 +1,822 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::{number::Number, symbol::Symbol},
+        call::{op::Op, Call},
+        Expr, TypeErr,
+    },
+};
+
+/// Steps of bisection, that find the quantile of a continuous distribution.
+const MAX_BISECTION: usize = 200;
+
+/// Depth of the continued fractions of the incomplete Gamma and Beta functions.
+const MAX_FRACTION: usize = 300;
+
+/// Terms of a discrete distribution, that are summed exactly, more are approximated.
+const MAX_SUM: i128 = 100;
+
+/// Terms of a tail of a discrete distribution, that are summed in floats.
+const MAX_TERMS: usize = 100_000;
+
+/// Distributions of `<name>pdf`, `<name>cdf` and `<name>quantile`.
+const DISTRIBUTIONS: [&str; 7] = [
+    "normal",
+    "binomial",
+    "poisson",
+    "uniform",
+    "exponential",
+    "t",
+    "chi2",
+];
+
+/// Statistics of samples, which are given as arguments or as lists.
+/// Variances are of the sample, so divided by `n - 1`.
+impl Expr {
+    /// Evaluated arguments, where lists are flattened into their elements.
+    pub(crate) fn values(args: &[Expr], env: &mut Env) -> Result<Vec<Expr>, TypeErr> {
+        let mut values = Vec::with_capacity(args.len());
+
+        for arg in args {
+            values.append(&mut arg.eval(env)?.list());
+        }
+
+        Ok(values)
+    }
+
+    pub(crate) fn mean(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        Ok(mean(&sample("mean", args, env, 1)?))
+    }
+
+    pub(crate) fn median(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let sorted = sorted("median", &sample("median", args, env, 1)?)?;
+        let middle = sorted.len() / 2;
+
+        Ok(Expr::num(if sorted.len() % 2 == 0 {
+            (sorted[middle - 1].clone() + sorted[middle].clone()) / Number::int(2)
+        } else {
+            sorted[middle].clone()
+        }))
+    }
+
+    /// The most frequent values, in the order they first appear.
+    pub(crate) fn mode(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let mut counts: Vec<(Expr, usize)> = Vec::new();
+
+        for value in sample("mode", args, env, 1)? {
+            match counts.iter_mut().find(|(other, _)| *other == value) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((value, 1)),
+            }
+        }
+
+        let most = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+        Ok(Expr::from_list(
+            counts
+                .into_iter()
+                .filter(|(_, count)| *count == most)
+                .map(|(value, _)| value)
+                .collect(),
+        ))
+    }
+
+    pub(crate) fn var(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let values = sample("var", args, env, 2)?;
+        Ok(covariance(&values, &values))
+    }
+
+    pub(crate) fn std(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let values = sample("std", args, env, 2)?;
+        Ok(sqrt(covariance(&values, &values)))
+    }
+
+    /// `quantile(data, p)` interpolates linearly between the sorted values.
+    pub(crate) fn quantile(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (p, data) = match args.split_last() {
+            Some((p, data)) if !data.is_empty() => (probability(&p.eval(env)?)?, data),
+            _ => {
+                return Err(TypeErr(
+                    "call `quantile` undefined on (expr, ...), expected (list, number)".to_string(),
+                ))
+            }
+        };
+        let sorted = sorted("quantile", &sample("quantile", data, env, 1)?)?;
+
+        // h = (n - 1) p, between the values at floor h and above
+        let h = Number::int(sorted.len() as i128 - 1) * p;
+        let index = (h.as_f64().floor() as usize).min(sorted.len() - 1);
+        let fraction = h - Number::int(index as i128);
+
+        Ok(Expr::num(match sorted.get(index + 1) {
+            Some(above) => {
+                sorted[index].clone() + fraction * (above.clone() - sorted[index].clone())
+            }
+            None => sorted[index].clone(),
+        }))
+    }
+
+    pub(crate) fn cov(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (xs, ys) = samples("cov", args, env)?;
+        Ok(covariance(&xs, &ys))
+    }
+
+    /// Pearson's correlation coefficient.
+    pub(crate) fn corr(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (xs, ys) = samples("corr", args, env)?;
+
+        Ok(Expr::div(
+            covariance(&xs, &ys),
+            sqrt(Expr::mul(vec![covariance(&xs, &xs), covariance(&ys, &ys)])),
+        ))
+    }
+
+    /// `linreg(xs, ys)` is the list `a, b` of the least squares line `y = a + b x`.
+    pub(crate) fn linreg(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (xs, ys) = samples("linreg", args, env)?;
+
+        let slope = Expr::div(covariance(&xs, &ys), covariance(&xs, &xs));
+        let intercept = Expr::sub(mean(&ys), Expr::mul(vec![slope.clone(), mean(&xs)]));
+
+        Ok(Expr::from_list(vec![intercept, slope]))
+    }
+
+    /// Whether a call is the density, distribution or quantile function of a distribution.
+    pub(crate) fn is_distribution(call: &str) -> bool {
+        distribution(call).is_some()
+    }
+
+    /// `<name>pdf(x, params)`, `<name>cdf(x, params)` and `<name>quantile(p, params)`.
+    pub(crate) fn distribution(call: &str, args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (name, kind) = distribution(call).expect("checked by `is_distribution`");
+
+        let mut args = args
+            .iter()
+            .map(|arg| arg.eval(env))
+            .collect::<Result<Vec<_>, _>>()?;
+        // default parameters of the standard distributions
+        match (name, args.len()) {
+            ("normal", 1) => args.extend(vec![Expr::int(0), Expr::int(1)]),
+            ("normal", 2) => args.push(Expr::int(1)),
+            ("uniform", 1) => args.extend(vec![Expr::int(0), Expr::int(1)]),
+            ("exponential", 1) => args.push(Expr::int(1)),
+            _ => (),
+        }
+
+        let arity = match name {
+            "normal" | "binomial" | "uniform" => 3,
+            _ => 2,
+        };
+        if args.len() != arity {
+            return Err(TypeErr(format!(
+                "call `{}` undefined on (expr, ...), expected {} arguments",
+                call, arity
+            )));
+        }
+
+        // parameters that are numbers, symbolic ones are kept
+        match name {
+            "normal" => check(call, &args[2], |sigma| sigma > 0.0, "positive σ")?,
+            "binomial" => {
+                check(
+                    call,
+                    &args[1],
+                    |n| n >= 0.0 && n.fract() == 0.0,
+                    "natural number n",
+                )?;
+                check(
+                    call,
+                    &args[2],
+                    |p| (0.0..=1.0).contains(&p),
+                    "probability p",
+                )?;
+            }
+            "poisson" => check(call, &args[1], |lambda| lambda > 0.0, "positive λ")?,
+            _ => (),
+        }
+        if let (Kind::Pdf, "binomial" | "poisson") = (kind, name) {
+            check(call, &args[0], |k| k.fract() == 0.0, "integer k")?;
+        }
+
+        let x = &args[0];
+        match (name, kind) {
+            ("normal", Kind::Pdf) => {
+                let (mu, sigma) = (&args[1], &args[2]);
+                // exp(-(x - μ)^2 / 2σ^2) / (σ sqrt 2π)
+                Ok(Expr::div(
+                    exp(Expr::neg(Expr::div(
+                        Expr::pow(Expr::sub(x.clone(), mu.clone()), Expr::int(2)),
+                        Expr::mul(vec![Expr::int(2), Expr::pow(sigma.clone(), Expr::int(2))]),
+                    ))),
+                    Expr::mul(vec![
+                        sigma.clone(),
+                        sqrt(Expr::mul(vec![Expr::int(2), Expr::pi()])),
+                    ]),
+                ))
+            }
+            ("normal", Kind::Cdf) => {
+                let (mu, sigma) = (&args[1], &args[2]);
+                // (1 + erf((x - μ) / (σ sqrt 2))) / 2
+                Ok(Expr::mul(vec![
+                    Expr::num(Number::ratio(1, 2)),
+                    Expr::add(vec![
+                        Expr::int(1),
+                        Expr::function(
+                            Symbol("erf".to_string()),
+                            Expr::div(
+                                Expr::sub(x.clone(), mu.clone()),
+                                Expr::mul(vec![sigma.clone(), sqrt(Expr::int(2))]),
+                            ),
+                        ),
+                    ]),
+                ]))
+            }
+            ("normal", Kind::Quantile) => {
+                let p = probability(x)?;
+                let z = if p == Number::ratio(1, 2) {
+                    Number::int(0)
+                } else {
+                    Number::Float(invert(normal_cdf, p.as_f64(), -40.0, 40.0))
+                };
+                Ok(Expr::add(vec![
+                    args[1].clone(),
+                    Expr::mul(vec![args[2].clone(), Expr::num(z)]),
+                ]))
+            }
+
+            ("binomial", Kind::Pdf) => Ok(binomial_pdf(x, &args[1], &args[2])),
+            ("binomial", Kind::Cdf) => {
+                let k = floor(call, x)?;
+                if k < 0 {
+                    return Ok(Expr::int(0));
+                }
+                if let Some(n) = args[1].as_number().and_then(|n| n.as_int()) {
+                    if k >= n {
+                        return Ok(Expr::int(1));
+                    }
+                }
+                if k < MAX_SUM {
+                    return Ok(Expr::add(
+                        (0..=k)
+                            .map(|i| binomial_pdf(&Expr::int(i), &args[1], &args[2]))
+                            .collect(),
+                    ));
+                }
+
+                let (n, p) = (integer(call, &args[1])?, number(call, &args[2])?.as_f64());
+                Ok(Expr::num(Number::Float(binomial_cdf(n, p, k))))
+            }
+            ("binomial", Kind::Quantile) => {
+                let (q, n) = (probability(x)?, integer(call, &args[1])?);
+                if n >= MAX_SUM {
+                    let p = number(call, &args[2])?.as_f64();
+                    let k = discrete_quantile(|k| binomial_cdf(n, p, k), q.as_f64(), n);
+                    return Ok(Expr::int(k));
+                }
+
+                let mut cdf = Number::int(0);
+                for k in 0..=n {
+                    cdf = cdf + number(call, &binomial_pdf(&Expr::int(k), &args[1], &args[2]))?;
+                    if cdf.as_f64() >= q.as_f64() {
+                        return Ok(Expr::int(k));
+                    }
+                }
+                Ok(Expr::int(n))
+            }
+
+            ("poisson", Kind::Pdf) => Ok(poisson_pdf(x, &args[1])),
+            ("poisson", Kind::Cdf) => {
+                let k = floor(call, x)?;
+                if k < 0 {
+                    return Ok(Expr::int(0));
+                }
+                if k < MAX_SUM {
+                    return Ok(Expr::add(
+                        (0..=k)
+                            .map(|i| poisson_pdf(&Expr::int(i), &args[1]))
+                            .collect(),
+                    ));
+                }
+
+                let lambda = number(call, &args[1])?.as_f64();
+                Ok(Expr::num(Number::Float(poisson_cdf(lambda, k))))
+            }
+            ("poisson", Kind::Quantile) => {
+                let (q, lambda) = (probability(x)?.as_f64(), number(call, &args[1])?.as_f64());
+                if q >= 1.0 {
+                    return Ok(Expr::inf());
+                }
+
+                // double the upper bound until it is above the quantile
+                let mut upper = (lambda as i128).max(1);
+                while poisson_cdf(lambda, upper) < q {
+                    upper = upper.checked_mul(2).ok_or_else(|| {
+                        TypeErr(format!("call `{}` found no quantile below `inf`", call))
+                    })?;
+                }
+                Ok(Expr::int(discrete_quantile(
+                    |k| poisson_cdf(lambda, k),
+                    q,
+                    upper,
+                )))
+            }
+
+            ("uniform", _) => {
+                let (a, b) = (number(call, &args[1])?, number(call, &args[2])?);
+                Ok(Expr::num(match kind {
+                    Kind::Pdf => {
+                        let x = number(call, x)?;
+                        if x.as_f64() < a.as_f64() || x.as_f64() > b.as_f64() {
+                            Number::int(0)
+                        } else {
+                            Number::int(1) / (b - a)
+                        }
+                    }
+                    Kind::Cdf => {
+                        let x = number(call, x)?;
+                        let cdf = (x - a.clone()) / (b - a);
+                        if cdf.as_f64() < 0.0 {
+                            Number::int(0)
+                        } else if cdf.as_f64() > 1.0 {
+                            Number::int(1)
+                        } else {
+                            cdf
+                        }
+                    }
+                    Kind::Quantile => a.clone() + probability(x)? * (b - a),
+                }))
+            }
+
+            ("exponential", Kind::Quantile) => {
+                let p = probability(x)?;
+                // -ln(1 - p) / λ
+                Ok(Expr::div(
+                    Expr::neg(Expr::function(
+                        Symbol("ln".to_string()),
+                        Expr::num(Number::int(1) - p),
+                    )),
+                    args[1].clone(),
+                ))
+            }
+            ("exponential", _) if x.is_negative() => Ok(Expr::int(0)),
+            ("exponential", _) => {
+                let decay = exp(Expr::neg(Expr::mul(vec![args[1].clone(), x.clone()])));
+                Ok(match kind {
+                    Kind::Pdf => Expr::mul(vec![args[1].clone(), decay]),
+                    _ => Expr::sub(Expr::int(1), decay),
+                })
+            }
+
+            ("t", Kind::Pdf) => {
+                let nu = &args[1];
+                let half = |x: Expr| Expr::mul(vec![Expr::num(Number::ratio(1, 2)), x]);
+                let gamma = |x: Expr| Expr::function(Symbol("gamma".to_string()), x);
+                // Γ((ν + 1) / 2) / (sqrt(ν π) Γ(ν / 2)) (1 + x^2 / ν)^(-(ν + 1) / 2)
+                Ok(Expr::mul(vec![
+                    gamma(half(Expr::add(vec![nu.clone(), Expr::int(1)]))),
+                    Expr::pow(
+                        Expr::mul(vec![
+                            sqrt(Expr::mul(vec![nu.clone(), Expr::pi()])),
+                            gamma(half(nu.clone())),
+                        ]),
+                        Expr::int(-1),
+                    ),
+                    Expr::pow(
+                        Expr::add(vec![
+                            Expr::int(1),
+                            Expr::div(Expr::pow(x.clone(), Expr::int(2)), nu.clone()),
+                        ]),
+                        Expr::neg(half(Expr::add(vec![nu.clone(), Expr::int(1)]))),
+                    ),
+                ]))
+            }
+            ("t", _) => {
+                let nu = number(call, &args[1])?.as_f64();
+                let cdf = |t: f64| {
+                    let tail = incomplete_beta(nu / 2.0, 0.5, nu / (nu + t * t)) / 2.0;
+                    if t > 0.0 {
+                        1.0 - tail
+                    } else {
+                        tail
+                    }
+                };
+
+                Ok(Expr::num(match kind {
+                    Kind::Cdf if x.is_zero() => Number::ratio(1, 2),
+                    Kind::Cdf => Number::Float(cdf(number(call, x)?.as_f64())),
+                    _ => match probability(x)? {
+                        half if half == Number::ratio(1, 2) => Number::int(0),
+                        p => Number::Float(invert(cdf, p.as_f64(), -1e10, 1e10)),
+                    },
+                }))
+            }
+
+            ("chi2", Kind::Pdf) if x.is_negative() => Ok(Expr::int(0)),
+            ("chi2", Kind::Pdf) => {
+                let half = Expr::mul(vec![Expr::num(Number::ratio(1, 2)), args[1].clone()]);
+                // x^(k/2 - 1) exp(-x/2) / (2^(k/2) Γ(k/2))
+                Ok(Expr::div(
+                    Expr::mul(vec![
+                        Expr::pow(x.clone(), Expr::sub(half.clone(), Expr::int(1))),
+                        exp(Expr::mul(vec![Expr::num(Number::ratio(-1, 2)), x.clone()])),
+                    ]),
+                    Expr::mul(vec![
+                        Expr::pow(Expr::int(2), half.clone()),
+                        Expr::function(Symbol("gamma".to_string()), half),
+                    ]),
+                ))
+            }
+            ("chi2", _) => {
+                let k = number(call, &args[1])?.as_f64();
+                let cdf = |x: f64| {
+                    if x <= 0.0 {
+                        0.0
+                    } else {
+                        incomplete_gamma(k / 2.0, x / 2.0)
+                    }
+                };
+
+                Ok(Expr::num(match kind {
+                    Kind::Cdf => Number::Float(cdf(number(call, x)?.as_f64())),
+                    _ => Number::Float(invert(cdf, probability(x)?.as_f64(), 0.0, 1e10)),
+                }))
+            }
+
+            _ => unreachable!("every distribution has all three functions"),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Pdf,
+    Cdf,
+    Quantile,
+}
+
+fn distribution(call: &str) -> Option<(&'static str, Kind)> {
+    let (rest, kind) = if let Some(rest) = call.strip_suffix("pdf") {
+        (rest, Kind::Pdf)
+    } else if let Some(rest) = call.strip_suffix("cdf") {
+        (rest, Kind::Cdf)
+    } else {
+        (call.strip_suffix("quantile")?, Kind::Quantile)
+    };
+
+    DISTRIBUTIONS
+        .iter()
+        .find(|name| **name == rest)
+        .map(|name| (*name, kind))
+}
+
+/// The values of a sample with at least `min` of them.
+fn sample(call: &str, args: &[Expr], env: &mut Env, min: usize) -> Result<Vec<Expr>, TypeErr> {
+    let values = Expr::values(args, env)?;
+
+    if values.len() < min {
+        return Err(TypeErr(format!(
+            "call `{}` needs at least {} values, found {}",
+            call,
+            min,
+            values.len()
+        )));
+    }
+
+    Ok(values)
+}
+
+/// Two samples of the same size, the first has to be a list, the second may be
+/// a list or the rest of the arguments, as a last list argument is flattened.
+fn samples(call: &str, args: &[Expr], env: &mut Env) -> Result<(Vec<Expr>, Vec<Expr>), TypeErr> {
+    let (xs, ys) = match args.split_first() {
+        Some((first, rest)) => (first.eval(env)?.list(), Expr::values(rest, env)?),
+        None => (Vec::new(), Vec::new()),
+    };
+
+    if xs.len() < 2 || xs.len() != ys.len() {
+        return Err(TypeErr(format!(
+            "call `{}` undefined on (expr, ...), expected two lists of the same length",
+            call
+        )));
+    }
+
+    Ok((xs, ys))
+}
+
+fn mean(values: &[Expr]) -> Expr {
+    Expr::div(Expr::add(values.to_vec()), Expr::int(values.len() as i128))
+}
+
+/// Sample covariance `sum (x - mean x) (y - mean y) / (n - 1)`.
+fn covariance(xs: &[Expr], ys: &[Expr]) -> Expr {
+    let (x_mean, y_mean) = (mean(xs), mean(ys));
+
+    Expr::div(
+        Expr::add(
+            xs.iter()
+                .zip(ys)
+                .map(|(x, y)| {
+                    Expr::mul(vec![
+                        Expr::sub(x.clone(), x_mean.clone()),
+                        Expr::sub(y.clone(), y_mean.clone()),
+                    ])
+                })
+                .collect(),
+        ),
+        Expr::int(xs.len() as i128 - 1),
+    )
+}
+
+fn sorted(call: &str, values: &[Expr]) -> Result<Vec<Number>, TypeErr> {
+    let mut numbers = values
+        .iter()
+        .map(|value| number(call, value))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    numbers.sort_by(|a, b| a.as_f64().total_cmp(&b.as_f64()));
+    Ok(numbers)
+}
+
+fn number(call: &str, expr: &Expr) -> Result<Number, TypeErr> {
+    expr.as_number()
+        .ok_or_else(|| TypeErr(format!("call `{}` expected number, found `{}`", call, expr)))
+}
+
+fn integer(call: &str, expr: &Expr) -> Result<i128, TypeErr> {
+    number(call, expr)?.as_int().ok_or_else(|| {
+        TypeErr(format!(
+            "call `{}` expected integer, found `{}`",
+            call, expr
+        ))
+    })
+}
+
+/// The largest integer below a number.
+fn floor(call: &str, expr: &Expr) -> Result<i128, TypeErr> {
+    let x = number(call, expr)?.as_f64().floor();
+    if x.is_nan() {
+        return Err(TypeErr(format!(
+            "call `{}` expected number, found `{}`",
+            call, expr
+        )));
+    }
+    // casts saturate, which is below and above every term
+    Ok(x as i128)
+}
+
+/// Checks a parameter, if it is a number.
+fn check(
+    call: &str,
+    expr: &Expr,
+    valid: impl Fn(f64) -> bool,
+    expected: &str,
+) -> Result<(), TypeErr> {
+    match expr.as_number() {
+        Some(number) if !valid(number.as_f64()) => Err(TypeErr(format!(
+            "call `{}` expected {}, found `{}`",
+            call, expected, expr
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn probability(expr: &Expr) -> Result<Number, TypeErr> {
+    match expr.as_number() {
+        Some(p) if (0.0..=1.0).contains(&p.as_f64()) => Ok(p),
+        _ => Err(TypeErr(format!("`{}` is no probability from 0 to 1", expr))),
+    }
+}
+
+fn exp(x: Expr) -> Expr {
+    Expr::function(Symbol("exp".to_string()), x)
+}
+
+fn sqrt(x: Expr) -> Expr {
+    Expr::pow(x, Expr::num(Number::ratio(1, 2)))
+}
+
+/// `binomial(n, k) p^k (1 - p)^(n - k)`, zero for integers outside of `0 .. n`.
+fn binomial_pdf(k: &Expr, n: &Expr, p: &Expr) -> Expr {
+    if let (Some(k), Some(n)) = (
+        k.as_number().and_then(|k| k.as_int()),
+        n.as_number().and_then(|n| n.as_int()),
+    ) {
+        if k < 0 || k > n {
+            return Expr::int(0);
+        }
+    }
+
+    Expr::mul(vec![
+        Expr::rebuild(
+            &Op::Call(Symbol("binomial".to_string())),
+            vec![n.clone(), k.clone()],
+        ),
+        Expr::pow(p.clone(), k.clone()),
+        Expr::pow(
+            Expr::sub(Expr::int(1), p.clone()),
+            Expr::sub(n.clone(), k.clone()),
+        ),
+    ])
+}
+
+/// `λ^k exp(-λ) / k!`, zero for negative integers.
+fn poisson_pdf(k: &Expr, lambda: &Expr) -> Expr {
+    if k.as_number()
+        .and_then(|k| k.as_int())
+        .is_some_and(|k| k < 0)
+    {
+        return Expr::int(0);
+    }
+
+    Expr::div(
+        Expr::mul(vec![
+            Expr::pow(lambda.clone(), k.clone()),
+            exp(Expr::neg(lambda.clone())),
+        ]),
+        Expr::Call(Call::new(Op::Fact, vec![k.clone()])).canonical(),
+    )
+}
+
+fn binomial_cdf(n: i128, p: f64, k: i128) -> f64 {
+    let ln_pdf = |i: i128| {
+        if i < 0 || i > n {
+            return f64::NEG_INFINITY;
+        }
+        let (i, n) = (i as f64, n as f64);
+        ln_gamma(n + 1.0) - ln_gamma(i + 1.0) - ln_gamma(n - i + 1.0)
+            + i * p.ln()
+            + (n - i) * (1.0 - p).ln()
+    };
+    let mode = (((n + 1) as f64 * p).floor() as i128).min(n);
+
+    discrete_cdf(ln_pdf, mode, k)
+}
+
+fn poisson_cdf(lambda: f64, k: i128) -> f64 {
+    let ln_pdf = |i: i128| {
+        if i < 0 {
+            return f64::NEG_INFINITY;
+        }
+        let i = i as f64;
+        i * lambda.ln() - lambda - ln_gamma(i + 1.0)
+    };
+
+    discrete_cdf(ln_pdf, lambda.floor() as i128, k)
+}
+
+/// `P(X <= k)` of a discrete distribution by the logarithm of its density,
+/// which rises up to the mode and falls after it.
+/// Only the tail away from the mode is summed, where the terms fall,
+/// until they vanish next to the sum.
+fn discrete_cdf(ln_pdf: impl Fn(i128) -> f64, mode: i128, k: i128) -> f64 {
+    let tail = |mut i: i128, step: i128| {
+        let mut sum = 0.0;
+        for _ in 0..MAX_TERMS {
+            let term = ln_pdf(i).exp();
+            sum += term;
+            if term <= sum * f64::EPSILON {
+                break;
+            }
+            i += step;
+        }
+        sum
+    };
+
+    if k < 0 {
+        0.0
+    } else if k < mode {
+        tail(k, -1)
+    } else {
+        1.0 - tail(k + 1, 1)
+    }
+}
+
+/// The smallest `k` from `0` to `upper` with `cdf(k) >= q`, by bisection.
+fn discrete_quantile(cdf: impl Fn(i128) -> f64, q: f64, upper: i128) -> i128 {
+    // cdf(lower) < q <= cdf(upper)
+    let (mut lower, mut upper) = (-1, upper);
+
+    while upper - lower > 1 {
+        let middle = lower + (upper - lower) / 2;
+        if cdf(middle) < q {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+
+    upper
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    Number::Float(-x / 2_f64.sqrt()).erfc().as_f64() / 2.0
+}
+
+/// Inverse of an increasing function with `cdf(lower) <= p <= cdf(upper)`, by bisection.
+fn invert(cdf: impl Fn(f64) -> f64, p: f64, lower: f64, upper: f64) -> f64 {
+    let (mut lower, mut upper) = (lower, upper);
+
+    for _ in 0..MAX_BISECTION {
+        let middle = (lower + upper) / 2.0;
+        if middle == lower || middle == upper {
+            break;
+        }
+
+        if cdf(middle) < p {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+
+    (lower + upper) / 2.0
+}
+
+fn ln_gamma(x: f64) -> f64 {
+    Number::Float(x).lgamma().as_f64()
+}
+
+/// Regularized lower incomplete Gamma function `P(a, x)`.
+fn incomplete_gamma(a: f64, x: f64) -> f64 {
+    let front = (a * x.ln() - x - ln_gamma(a)).exp();
+
+    if x < a + 1.0 {
+        // series sum x^n / (a (a + 1) ... (a + n))
+        let (mut sum, mut term, mut n) = (1.0 / a, 1.0 / a, a);
+        for _ in 0..MAX_FRACTION {
+            n += 1.0;
+            term *= x / n;
+            sum += term;
+            if term.abs() <= sum.abs() * f64::EPSILON {
+                break;
+            }
+        }
+        return front * sum;
+    }
+
+    // continued fraction of the upper function by Lentz's method
+    let tiny = f64::MIN_POSITIVE / f64::EPSILON;
+    let mut b = x + 1.0 - a;
+    let (mut c, mut d) = (1.0 / tiny, 1.0 / b);
+    let mut fraction = d;
+    for i in 1..MAX_FRACTION {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        d = if d.abs() < tiny { 1.0 / tiny } else { 1.0 / d };
+        c = b + an / c;
+        if c.abs() < tiny {
+            c = tiny;
+        }
+        let delta = d * c;
+        fraction *= delta;
+        if (delta - 1.0).abs() <= f64::EPSILON {
+            break;
+        }
+    }
+
+    1.0 - front * fraction
+}
+
+/// Regularized incomplete Beta function `I(x; a, b)`.
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    // the continued fraction converges fast below its mean
+    if x > (a + 1.0) / (a + b + 2.0) {
+        return 1.0 - incomplete_beta(b, a, 1.0 - x);
+    }
+
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp() / a;
+
+    // Lentz's method
+    let tiny = f64::MIN_POSITIVE / f64::EPSILON;
+    let clamp = |value: f64| if value.abs() < tiny { tiny } else { value };
+    let (mut c, mut d) = (1.0, 1.0 / clamp(1.0 - (a + b) * x / (a + 1.0)));
+    let mut fraction = d;
+
+    for m in 1..MAX_FRACTION {
+        let m = m as f64;
+
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / clamp(1.0 + even * d);
+        c = clamp(1.0 + even / c);
+        fraction *= d * c;
+
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / clamp(1.0 + odd * d);
+        c = clamp(1.0 + odd / c);
+        let delta = d * c;
+        fraction *= delta;
+
+        if (delta - 1.0).abs() <= f64::EPSILON {
+            break;
+        }
+    }
+
+    front * fraction
+}
@@ -0,0 +1,570 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::{number::Number, symbol::Symbol, Atom},
+        call::{op::Op, Call},
+        Expr, TypeErr,
+    },
+    series::Series,
+};
+
+/// Up to how many terms a sum or product with numeric bounds is just calculated.
+const MAX_TERMS: i128 = 1000;
+
+/// Sums and products over an index `k = a, a + 1, ..., b`.
+/// Empty ranges follow the convention `Σ(k = a..b) = -Σ(k = b + 1..a - 1)`.
+impl Expr {
+    /// `sum(expr, k, a, b)` is the sum of expr for `k` from `a` to `b`, where `b` may be `inf`.
+    pub(crate) fn sum(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (term, k, a, b) = Expr::bounds("sum", args, env)?;
+
+        if b.as_infinity() == Some(true) {
+            return term.infinite_sum_of(&k, &a);
+        }
+
+        Ok(term
+            .finite_sum(&k, &a, &b)
+            .unwrap_or_else(|| Expr::indexed("sum", term, k, a, b)))
+    }
+
+    /// `prod(expr, k, a, b)` is the product of expr for `k` from `a` to `b`, where `b` may be `inf`.
+    pub(crate) fn prod(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (term, k, a, b) = Expr::bounds("prod", args, env)?;
+
+        if b.as_infinity() == Some(true) {
+            return term.infinite_prod_of(&k, &a);
+        }
+
+        Ok(term
+            .finite_prod(&k, &a, &b)
+            .unwrap_or_else(|| Expr::indexed("prod", term, k, a, b)))
+    }
+
+    fn bounds(
+        name: &str,
+        args: &[Expr],
+        env: &mut Env,
+    ) -> Result<(Expr, Symbol, Expr, Expr), TypeErr> {
+        match args {
+            [term, k, a, b] => {
+                let k = k.symbol()?;
+
+                // the index is bound, so a definition of it must not leak in
+                let def = env.remove(k.clone());
+                let term = term.eval(env);
+                if let Some(def) = def {
+                    env.insert(k.clone(), def);
+                }
+
+                let a = integer_bound(name, a.eval(env)?)?;
+                let b = integer_bound(name, b.eval(env)?)?;
+
+                Ok((term?, k, a, b))
+            }
+            _ => Err(TypeErr(format!(
+                "call `{}` undefined on (expr, ...), expected (expr, symbol, expr, expr)",
+                name
+            ))),
+        }
+    }
+
+    /// A sum or product without a closed form stays as it is.
+    fn indexed(name: &str, term: Expr, k: Symbol, a: Expr, b: Expr) -> Expr {
+        Expr::Call(Call::new(
+            Op::Call(Symbol(name.to_string())),
+            vec![term, Expr::Atom(Atom::Symbol(k)), a, b],
+        ))
+    }
+
+    /// Closed form of a sum with finite bounds.
+    fn finite_sum(&self, k: &Symbol, a: &Expr, b: &Expr) -> Option<Expr> {
+        let x = Expr::Atom(Atom::Symbol(k.clone()));
+
+        if let (Some(a), Some(b)) = (as_int(a), as_int(b)) {
+            if b < a - 1 {
+                return Some(Expr::neg(self.finite_sum(
+                    k,
+                    &Expr::int(b + 1),
+                    &Expr::int(a - 1),
+                )?));
+            }
+            if b - a < MAX_TERMS {
+                return Some(Expr::add(
+                    (a..=b).map(|i| self.replace(&x, &Expr::int(i))).collect(),
+                ));
+            }
+        }
+
+        let count = Expr::add(vec![b.clone(), Expr::neg(a.clone()), Expr::int(1)]);
+        if !self.contains(k) {
+            return Some(Expr::mul(vec![self.clone(), count]));
+        }
+
+        if let Expr::Call(Call { op: Op::Add, args }) = self {
+            // linearity, unless only the whole sum telescopes
+            if let Some(terms) = args
+                .iter()
+                .map(|arg| arg.finite_sum(k, a, b))
+                .collect::<Option<Vec<_>>>()
+            {
+                return Some(Expr::add(terms));
+            }
+            return self.telescoping(k, a, b);
+        }
+
+        let (constant, rest) = self.split_constant(k);
+        if constant != Expr::int(1) {
+            return Some(Expr::mul(vec![constant, rest.finite_sum(k, a, b)?]));
+        }
+
+        if let Some(coeffs) = self.coeffs(k) {
+            // Faulhaber's formula for every power
+            let terms = coeffs
+                .iter()
+                .enumerate()
+                .map(|(p, coeff)| {
+                    Expr::mul(vec![
+                        coeff.clone(),
+                        Expr::sub(
+                            faulhaber(p, b),
+                            faulhaber(p, &Expr::sub(a.clone(), Expr::int(1))),
+                        ),
+                    ])
+                })
+                .collect();
+
+            return Some(Expr::add(terms));
+        }
+
+        if let Some((offset, ratio)) = self.geometric(k) {
+            return Some(if ratio == Expr::int(1) {
+                Expr::mul(vec![offset, count])
+            } else {
+                // (q^(b + 1) - q^a) / (q - 1)
+                Expr::mul(vec![
+                    offset,
+                    Expr::div(
+                        Expr::sub(
+                            Expr::pow(ratio.clone(), Expr::add(vec![b.clone(), Expr::int(1)])),
+                            Expr::pow(ratio.clone(), a.clone()),
+                        ),
+                        Expr::sub(ratio, Expr::int(1)),
+                    ),
+                ])
+            });
+        }
+
+        self.partial_fractions_sum(k, a, b)
+    }
+
+    /// Closed form of a product with finite bounds.
+    fn finite_prod(&self, k: &Symbol, a: &Expr, b: &Expr) -> Option<Expr> {
+        let x = Expr::Atom(Atom::Symbol(k.clone()));
+
+        if let (Some(a), Some(b)) = (as_int(a), as_int(b)) {
+            if b < a - 1 {
+                return Some(Expr::pow(
+                    self.finite_prod(k, &Expr::int(b + 1), &Expr::int(a - 1))?,
+                    Expr::int(-1),
+                ));
+            }
+            if b - a < MAX_TERMS {
+                return Some(Expr::mul(
+                    (a..=b).map(|i| self.replace(&x, &Expr::int(i))).collect(),
+                ));
+            }
+        }
+
+        let count = Expr::add(vec![b.clone(), Expr::neg(a.clone()), Expr::int(1)]);
+        if !self.contains(k) {
+            return Some(Expr::pow(self.clone(), count));
+        }
+
+        Some(match self {
+            Expr::Call(Call { op: Op::Mul, args }) => Expr::mul(
+                args.iter()
+                    .map(|arg| arg.finite_prod(k, a, b))
+                    .collect::<Option<_>>()?,
+            ),
+            Expr::Call(Call { op: Op::Pow, args }) if !args[1].contains(k) => {
+                Expr::pow(args[0].finite_prod(k, a, b)?, args[1].clone())
+            }
+            // a product of powers is a power of the sum
+            Expr::Call(Call { op: Op::Pow, args }) if !args[0].contains(k) => {
+                Expr::pow(args[0].clone(), args[1].finite_sum(k, a, b)?)
+            }
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) if call.0 == "exp" => Expr::function(call.clone(), args[0].finite_sum(k, a, b)?),
+            _ => {
+                // (m k + c) = m (k + s) for an integer shift s, which is a quotient of factorials
+                let coeffs = self.coeffs(k).filter(|coeffs| coeffs.len() == 2)?;
+                let (c, m) = (coeffs[0].as_number()?, coeffs[1].as_number()?);
+                let shift = Expr::num(c / m.clone());
+                as_int(&shift)?;
+
+                let fact = |x: Expr| Expr::rebuild(&Op::Fact, vec![x]);
+                Expr::mul(vec![
+                    Expr::pow(Expr::num(m), count),
+                    fact(Expr::add(vec![b.clone(), shift.clone()])),
+                    Expr::pow(
+                        fact(Expr::add(vec![a.clone(), shift, Expr::int(-1)])),
+                        Expr::int(-1),
+                    ),
+                ])
+            }
+        })
+    }
+
+    /// Split a product into the factors that do not depend on `k` and the rest.
+    fn split_constant(&self, k: &Symbol) -> (Expr, Expr) {
+        let factors = match self {
+            Expr::Call(Call { op: Op::Mul, args }) => args.clone(),
+            _ => vec![self.clone()],
+        };
+
+        let (constant, rest): (Vec<Expr>, Vec<Expr>) =
+            factors.into_iter().partition(|factor| !factor.contains(k));
+
+        (Expr::mul(constant), Expr::mul(rest))
+    }
+
+    /// Write the term as `offset * ratio^k`, if it is geometric.
+    fn geometric(&self, k: &Symbol) -> Option<(Expr, Expr)> {
+        let factors = match self {
+            Expr::Call(Call { op: Op::Mul, args }) => args.clone(),
+            _ => vec![self.clone()],
+        };
+
+        let (mut offset, mut ratio) = (Vec::new(), Vec::new());
+        for factor in factors {
+            match factor {
+                // exp(β + α k) = exp(β) exp(α)^k
+                Expr::Call(Call {
+                    op: Op::Call(ref call),
+                    ref args,
+                }) if call.0 == "exp" => {
+                    let coeffs = args[0].coeffs(k).filter(|coeffs| coeffs.len() <= 2)?;
+                    offset.push(Expr::function(call.clone(), coeffs[0].clone()));
+                    ratio.push(Expr::function(
+                        call.clone(),
+                        coeffs.get(1).cloned().unwrap_or_else(|| Expr::int(0)),
+                    ));
+                }
+                // r^(β + α k) = r^β (r^α)^k
+                Expr::Call(Call {
+                    op: Op::Pow,
+                    ref args,
+                }) if !args[0].contains(k) => {
+                    let coeffs = args[1].coeffs(k).filter(|coeffs| coeffs.len() <= 2)?;
+                    offset.push(Expr::pow(args[0].clone(), coeffs[0].clone()));
+                    ratio.push(Expr::pow(
+                        args[0].clone(),
+                        coeffs.get(1).cloned().unwrap_or_else(|| Expr::int(0)),
+                    ));
+                }
+                factor if !factor.contains(k) => offset.push(factor),
+                _ => return None,
+            }
+        }
+
+        Some((Expr::mul(offset), Expr::mul(ratio)))
+    }
+
+    /// `Σ g(k) - g(k + c)` is `g(a) + ... + g(a + c - 1) - g(b + 1) - ... - g(b + c)`.
+    fn telescoping(&self, k: &Symbol, a: &Expr, b: &Expr) -> Option<Expr> {
+        let args = match self {
+            Expr::Call(Call { op: Op::Add, args }) if args.len() == 2 => args,
+            _ => return None,
+        };
+        let x = Expr::Atom(Atom::Symbol(k.clone()));
+
+        for (g, h) in [(&args[0], &args[1]), (&args[1], &args[0])] {
+            for c in 1..=3 {
+                let shifted = g.replace(&x, &Expr::add(vec![x.clone(), Expr::int(c)]));
+                if &Expr::neg(shifted) != h {
+                    continue;
+                }
+
+                let at = |bound: &Expr, j: i128| {
+                    g.replace(&x, &Expr::add(vec![bound.clone(), Expr::int(j)]))
+                };
+                let mut terms = Vec::with_capacity(2 * c as usize);
+                for j in 0..c {
+                    terms.push(at(a, j));
+                    terms.push(Expr::neg(at(b, j + 1)));
+                }
+
+                return Some(Expr::add(terms));
+            }
+        }
+
+        None
+    }
+
+    /// A rational term `N(k) / ((k + c1) ... (k + cn))` with integer distances between
+    /// the poles telescopes after a partial fraction decomposition `Σ A_i / (k + c_i)`.
+    fn partial_fractions_sum(&self, k: &Symbol, a: &Expr, b: &Expr) -> Option<Expr> {
+        let factors = match self {
+            Expr::Call(Call { op: Op::Mul, args }) => args.clone(),
+            _ => vec![self.clone()],
+        };
+
+        let (mut num, mut shifts) = (Vec::new(), Vec::<Number>::new());
+        let mut scale = Number::int(1);
+
+        for factor in factors {
+            match factor {
+                Expr::Call(Call {
+                    op: Op::Pow,
+                    ref args,
+                }) if args[1] == Expr::int(-1) => {
+                    let coeffs = args[0].coeffs(k).filter(|coeffs| coeffs.len() == 2)?;
+                    let (c, m) = (coeffs[0].as_number()?, coeffs[1].as_number()?);
+
+                    scale = scale * m.clone();
+                    shifts.push(c / m);
+                }
+                factor => num.push(factor),
+            }
+        }
+
+        let num = Expr::mul(num);
+        // without the cancellation `Σ A_i = 0` it is harmonic and diverges
+        if shifts.len() < 2 || num.degree(k)? + 1 >= shifts.len() {
+            return None;
+        }
+
+        let lowest = shifts.iter().cloned().min_by(|a, b| {
+            a.as_f64()
+                .partial_cmp(&b.as_f64())
+                .expect("shifts are exact")
+        })?;
+        let mut distances = Vec::with_capacity(shifts.len());
+        for shift in &shifts {
+            distances.push((shift.clone() - lowest.clone()).as_int()?);
+        }
+        for (i, distance) in distances.iter().enumerate() {
+            if distances[..i].contains(distance) {
+                return None;
+            }
+        }
+
+        let x = Expr::Atom(Atom::Symbol(k.clone()));
+        let lowest = Expr::num(lowest);
+        let mut terms = Vec::new();
+
+        for (i, (shift, distance)) in shifts.iter().zip(&distances).enumerate() {
+            // A_i = N(-c_i) / Π(c_j - c_i)
+            let mut den = scale.clone();
+            for (j, other) in shifts.iter().enumerate() {
+                if i != j {
+                    den = den * (other.clone() - shift.clone());
+                }
+            }
+            let coeff = Expr::div(num.replace(&x, &Expr::num(-shift.clone())), Expr::num(den));
+
+            for j in 0..*distance {
+                let pole = |bound: &Expr, j: i128| {
+                    Expr::pow(
+                        Expr::add(vec![bound.clone(), lowest.clone(), Expr::int(j)]),
+                        Expr::int(-1),
+                    )
+                };
+                terms.push(Expr::mul(vec![coeff.clone(), pole(b, j + 1)]));
+                terms.push(Expr::neg(Expr::mul(vec![coeff.clone(), pole(a, j)])));
+            }
+        }
+
+        Some(Expr::add(terms))
+    }
+
+    /// Sum up to `inf`: the limit of a closed form or a convergence test.
+    fn infinite_sum_of(&self, k: &Symbol, a: &Expr) -> Result<Expr, TypeErr> {
+        let n = Expr::add(vec![self.clone(), a.clone()]).fresh("n");
+
+        if let Some(sum) = self.finite_sum(k, a, &Expr::Atom(Atom::Symbol(n.clone()))) {
+            if let Ok(limit) = sum.limit_of(&n, &Expr::inf(), None) {
+                if limit.is_finite() || limit.as_infinity().is_some() {
+                    return Ok(limit);
+                }
+            }
+        }
+
+        let x = Expr::Atom(Atom::Symbol(k.clone()));
+        let divergent = || Err(TypeErr(format!("sum of `{}` diverges", self)));
+
+        // the terms have to vanish
+        if !self.limit_of(k, &Expr::inf(), None)?.is_zero() {
+            return divergent();
+        }
+
+        // compare with Σ 1/k^p, which converges for p > 1
+        let t = self.fresh("t");
+        let inverse = Expr::pow(Expr::Atom(Atom::Symbol(t.clone())), Expr::int(-1));
+        if let Ok((coeff, p)) = Series::leading(&self.replace(&x, &inverse), &t, &Expr::int(0)) {
+            return if p > 1 {
+                Ok(Expr::indexed(
+                    "sum",
+                    self.clone(),
+                    k.clone(),
+                    a.clone(),
+                    Expr::inf(),
+                ))
+            } else {
+                Ok(Expr::mul(vec![coeff, Expr::inf()]))
+            };
+        }
+
+        // ratio test
+        let next = self.replace(&x, &Expr::add(vec![x.clone(), Expr::int(1)]));
+        let ratio = Expr::div(next.unfold_facts(k), self.unfold_facts(k))
+            .limit_of(k, &Expr::inf(), None)?
+            .float()
+            .map(f64::abs);
+
+        match ratio {
+            Some(ratio) if ratio < 1.0 => Ok(Expr::indexed(
+                "sum",
+                self.clone(),
+                k.clone(),
+                a.clone(),
+                Expr::inf(),
+            )),
+            Some(ratio) if ratio > 1.0 => divergent(),
+            _ => Err(TypeErr(format!(
+                "could not decide, if the sum of `{}` converges",
+                self
+            ))),
+        }
+    }
+
+    /// Product up to `inf`, which converges like `Σ (term - 1)`.
+    fn infinite_prod_of(&self, k: &Symbol, a: &Expr) -> Result<Expr, TypeErr> {
+        let n = Expr::add(vec![self.clone(), a.clone()]).fresh("n");
+
+        if let Some(prod) = self.finite_prod(k, a, &Expr::Atom(Atom::Symbol(n.clone()))) {
+            if let Ok(limit) = prod.limit_of(&n, &Expr::inf(), None) {
+                if limit.is_finite() || limit.as_infinity().is_some() {
+                    return Ok(limit);
+                }
+            }
+        }
+
+        // a divergent sum is an error or infinite
+        let sum = Expr::sub(self.clone(), Expr::int(1)).infinite_sum_of(k, a)?;
+        if sum.as_infinity().is_none() && !sum.is_nan() {
+            Ok(Expr::indexed(
+                "prod",
+                self.clone(),
+                k.clone(),
+                a.clone(),
+                Expr::inf(),
+            ))
+        } else {
+            Err(TypeErr(format!("product of `{}` diverges", self)))
+        }
+    }
+}
+
+impl Expr {
+    /// `(k + c)!` as `(k + c) (k + c - 1) ... (k + 1) k!`, so quotients of factorials cancel.
+    fn unfold_facts(&self, k: &Symbol) -> Expr {
+        match self {
+            Expr::Call(Call { op, args }) => {
+                let args: Vec<Expr> = args.iter().map(|arg| arg.unfold_facts(k)).collect();
+                let fact = match op {
+                    Op::Fact => true,
+                    Op::Call(call) => call.0 == "fact",
+                    _ => false,
+                };
+
+                if let (true, [arg]) = (fact, &args[..]) {
+                    if let Some(coeffs) = arg.coeffs(k).filter(|coeffs| coeffs.len() == 2) {
+                        if let (Some(c), true) = (as_int(&coeffs[0]), coeffs[1] == Expr::int(1)) {
+                            let x = Expr::Atom(Atom::Symbol(k.clone()));
+                            let mut factors =
+                                vec![Expr::Call(Call::new(op.clone(), vec![x.clone()]))];
+                            for i in 1..=c {
+                                factors.push(Expr::add(vec![x.clone(), Expr::int(i)]));
+                            }
+
+                            return Expr::mul(factors);
+                        }
+                    }
+                }
+
+                Expr::rebuild(op, args)
+            }
+            _ => self.clone(),
+        }
+    }
+}
+
+fn as_int(expr: &Expr) -> Option<i128> {
+    expr.as_number()?.as_int()
+}
+
+/// A numeric bound has to be an integer, a float like `2.0` is taken as one.
+fn integer_bound(name: &str, bound: Expr) -> Result<Expr, TypeErr> {
+    match bound.as_number() {
+        Some(Number::Float(float)) if float.is_finite() && float.fract() == 0.0 => {
+            Ok(Expr::int(float as i128))
+        }
+        Some(number) if number.as_int().is_none() => Err(TypeErr(format!(
+            "call `{}` expected integer bounds, found `{:?}`",
+            name, number
+        ))),
+        _ => Ok(bound),
+    }
+}
+
+/// `1^p + 2^p + ... + n^p` as a polynomial in `n`.
+fn faulhaber(p: usize, n: &Expr) -> Expr {
+    let bernoulli = bernoulli(p);
+    let mut terms = Vec::with_capacity(p + 1);
+
+    for (j, b) in bernoulli.iter().enumerate() {
+        terms.push(Expr::mul(vec![
+            Expr::num(binomial(p + 1, j) * b.clone() / Number::int(p as i128 + 1)),
+            Expr::pow(n.clone(), Expr::int((p + 1 - j) as i128)),
+        ]));
+    }
+
+    Expr::add(terms)
+}
+
+/// Bernoulli numbers `B_0, ..., B_m` with `B_1 = 1/2`.
+fn bernoulli(m: usize) -> Vec<Number> {
+    let mut numbers: Vec<Number> = Vec::with_capacity(m + 1);
+
+    for i in 0..=m {
+        let mut sum = Number::int(0);
+        for (j, b) in numbers.iter().enumerate() {
+            sum = sum + binomial(i + 1, j) * b.clone();
+        }
+
+        numbers.push(if i == 0 {
+            Number::int(1)
+        } else {
+            -sum / Number::int(i as i128 + 1)
+        });
+    }
+
+    if m >= 1 {
+        numbers[1] = Number::ratio(1, 2);
+    }
+
+    numbers
+}
+
+fn binomial(n: usize, k: usize) -> Number {
+    let mut result = Number::int(1);
+
+    for i in 0..k {
+        result = result * Number::int((n - i) as i128) / Number::int(i as i128 + 1);
+    }
+
+    result
+}
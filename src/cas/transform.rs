@@ -0,0 +1,607 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::{number::Number, symbol::Symbol, Atom},
+        call::{op::Op, Call},
+        Expr, TypeErr,
+    },
+    ode::numeric_roots,
+};
+
+/// A term `coeff var^power exp(exponent) trig(slope var)` and the factors, which are none of these.
+struct Term {
+    coeff: Expr,
+    power: Number,
+    exponent: Expr,
+    trig: Option<(String, Expr)>,
+    rest: Vec<Expr>,
+}
+
+/// Integral transforms from tables, which are extended to sums, shifts,
+/// modulations and multiplications with powers by their rules.
+impl Expr {
+    /// `laplace(f, t, s)` is `∫ f exp(-s t)` from `0` to `inf`, of sums of
+    /// `t^n exp(a t)` times `sin`, `cos`, `sinh` or `cosh` of `b t`.
+    pub(crate) fn laplace(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (f, t, s) = transform_args("laplace", args, env)?;
+        by_terms(&f.expand(), |term| laplace_term(term, &t, &s))
+            .ok_or_else(|| TypeErr(format!("could not find the Laplace transform of `{}`", f)))
+    }
+
+    /// `ilaplace(F, s, t)` is the inverse Laplace transform of a proper rational function,
+    /// by partial fractions of the real and complex roots of its denominator.
+    pub(crate) fn ilaplace(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (f, s, t) = transform_args("ilaplace", args, env)?;
+        by_terms(&f, |term| ilaplace_term(term, &s, &t)).ok_or_else(|| {
+            TypeErr(format!(
+                "could not find the inverse Laplace transform of `{}`",
+                f
+            ))
+        })
+    }
+
+    /// `fourier(f, t, ω)` is `∫ f exp(-i ω t)` over all `t`, of `exp(-a t^2)`, `exp(-a abs(t))`
+    /// or `1 / (t^2 + a^2)` times powers of `t`, `exp(c t)` and a `sin`, `cos`, `sinh` or `cosh`.
+    pub(crate) fn fourier(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (f, t, w) = transform_args("fourier", args, env)?;
+        by_terms(&f.expand(), |term| fourier_term(term, &t, &w))
+            .ok_or_else(|| TypeErr(format!("could not find the Fourier transform of `{}`", f)))
+    }
+
+    /// `ifourier(F, ω, t)` is `∫ F exp(i ω t) / (2 π)` over all `ω`, the inverse of `fourier`.
+    pub(crate) fn ifourier(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (f, w, t) = transform_args("ifourier", args, env)?;
+        let x = Expr::Atom(Atom::Symbol(t.clone()));
+
+        let transform =
+            by_terms(&f.expand(), |term| fourier_term(term, &w, &t)).ok_or_else(|| {
+                TypeErr(format!(
+                    "could not find the inverse Fourier transform of `{}`",
+                    f
+                ))
+            })?;
+
+        Ok(Expr::div(
+            transform.replace(&x, &Expr::neg(x.clone())),
+            Expr::mul(vec![Expr::int(2), Expr::pi()]),
+        ))
+    }
+
+    /// `ztransform(f, n, z)` is `Σ f z^-n` from `n = 0` to `inf`, of sums of
+    /// `n^k a^n` times `sin`, `cos`, `sinh` or `cosh` of `b n`.
+    pub(crate) fn ztransform(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let (f, n, z) = transform_args("ztransform", args, env)?;
+        by_terms(&f.expand(), |term| ztransform_term(term, &n, &z))
+            .ok_or_else(|| TypeErr(format!("could not find the Z-transform of `{}`", f)))
+    }
+}
+
+fn transform_args(
+    name: &str,
+    args: &[Expr],
+    env: &mut Env,
+) -> Result<(Expr, Symbol, Symbol), TypeErr> {
+    match args {
+        [f, from, to] => Ok((f.eval(env)?, from.symbol()?, to.symbol()?)),
+        _ => Err(TypeErr(format!(
+            "call `{}` undefined on (expr, ...), expected (expr, symbol, symbol)",
+            name
+        ))),
+    }
+}
+
+/// Transforms are linear.
+fn by_terms(f: &Expr, transform: impl Fn(&Expr) -> Option<Expr>) -> Option<Expr> {
+    match f {
+        Expr::Call(Call { op: Op::Add, args }) => Some(Expr::add(
+            args.iter().map(transform).collect::<Option<_>>()?,
+        )),
+        _ => transform(f),
+    }
+}
+
+/// Split a term into its constant, power, exponential and trigonometric factors.
+fn decompose(term: &Expr, var: &Symbol) -> Option<Term> {
+    let factors = match term {
+        Expr::Call(Call { op: Op::Mul, args }) => args.clone(),
+        _ => vec![term.clone()],
+    };
+    let x = Expr::Atom(Atom::Symbol(var.clone()));
+
+    let (mut coeff, mut power, mut exponent, mut trig, mut rest) =
+        (Vec::new(), Number::int(0), Vec::new(), None, Vec::new());
+
+    for factor in factors {
+        if !factor.contains(var) {
+            coeff.push(factor);
+            continue;
+        }
+
+        match &factor {
+            factor if *factor == x => power = power + Number::int(1),
+            Expr::Call(Call { op: Op::Pow, args }) if args[0] == x => {
+                power = power + args[1].as_number()?
+            }
+            // a^u = exp(u ln a)
+            Expr::Call(Call { op: Op::Pow, args }) if !args[0].contains(var) => {
+                exponent.push(Expr::mul(vec![
+                    args[1].clone(),
+                    Expr::function(Symbol("ln".to_string()), args[0].clone()),
+                ]))
+            }
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) if call.0 == "exp" && args.len() == 1 => exponent.push(args[0].clone()),
+            _ => match (trig_slope(&factor, var), &trig) {
+                (Some(found), None) => trig = Some(found),
+                _ => rest.push(factor),
+            },
+        }
+    }
+
+    Some(Term {
+        coeff: Expr::mul(coeff),
+        power,
+        exponent: Expr::add(exponent).expand(),
+        trig,
+        rest,
+    })
+}
+
+/// The name and `b` of `sin(b x)`, `cos(b x)`, `sinh(b x)` or `cosh(b x)`.
+fn trig_slope(factor: &Expr, var: &Symbol) -> Option<(String, Expr)> {
+    match factor {
+        Expr::Call(Call {
+            op: Op::Call(call),
+            args,
+        }) if matches!(&call.0[..], "sin" | "cos" | "sinh" | "cosh") && args.len() == 1 => {
+            let x = Expr::Atom(Atom::Symbol(var.clone()));
+            let slope = args[0].derive(var).ok()?;
+
+            (args[0].replace(&x, &Expr::int(0)).is_zero() && !slope.contains(var))
+                .then(|| (call.0.clone(), slope))
+        }
+        _ => None,
+    }
+}
+
+/// Constant and linear coefficient of an exponent `c0 + c1 x`.
+fn linear_exponent(exponent: &Expr, var: &Symbol) -> Option<(Expr, Expr)> {
+    let mut coeffs = exponent.coeffs(var)?.into_iter();
+    let start = coeffs.next().unwrap_or_else(|| Expr::int(0));
+    let rate = coeffs.next().unwrap_or_else(|| Expr::int(0));
+
+    coeffs.next().is_none().then_some((start, rate))
+}
+
+fn exp(x: Expr) -> Expr {
+    Expr::function(Symbol("exp".to_string()), x)
+}
+
+/// `n!`, folded into a number.
+fn fact(n: usize) -> Expr {
+    Expr::function(Symbol("fact".to_string()), Expr::int(n as i128))
+}
+
+/// `t^n exp(a t)` is `n! / (s - a)^(n + 1)`, a sine `b / ((s - a)^2 + b^2)`
+/// and a cosine `(s - a) / ((s - a)^2 + b^2)`, where `t^n` derives `n` times.
+fn laplace_term(term: &Expr, t: &Symbol, s: &Symbol) -> Option<Expr> {
+    let Term {
+        coeff,
+        power,
+        exponent,
+        trig,
+        rest,
+    } = decompose(term, t)?;
+    let (start, rate) = linear_exponent(&exponent, t)?;
+    if !rest.is_empty() {
+        return None;
+    }
+
+    let x = Expr::Atom(Atom::Symbol(s.clone()));
+    let square = |b: &Expr, sign: i128| {
+        Expr::add(vec![
+            Expr::pow(x.clone(), Expr::int(2)),
+            Expr::mul(vec![Expr::int(sign), Expr::pow(b.clone(), Expr::int(2))]),
+        ])
+    };
+
+    let transform = match (trig, power.as_int()) {
+        (None, Some(n)) if n >= 0 => {
+            Expr::div(fact(n as usize), Expr::pow(x.clone(), Expr::int(n + 1)))
+        }
+        // Γ(p + 1) / s^(p + 1)
+        (None, None) if power.as_f64() > -1.0 => {
+            let next = Expr::num(power + Number::int(1));
+            Expr::div(
+                Expr::function(Symbol("gamma".to_string()), next.clone()),
+                Expr::pow(x.clone(), next),
+            )
+        }
+        (Some((name, b)), Some(n)) if n >= 0 => {
+            let (num, den) = match &name[..] {
+                "sin" => (b.clone(), square(&b, 1)),
+                "cos" => (x.clone(), square(&b, 1)),
+                "sinh" => (b.clone(), square(&b, -1)),
+                _ => (x.clone(), square(&b, -1)),
+            };
+            // t f is -F'
+            power_rule(num, den, s, n as usize, Expr::int(1))?
+        }
+        _ => return None,
+    };
+
+    Some(Expr::mul(vec![
+        coeff,
+        exp(start),
+        transform.replace(&x, &Expr::sub(x.clone(), rate)),
+    ]))
+}
+
+/// Inverse of a term `c N(s) / D(s)` with `deg N < deg D`, where a root `r` of `D` of
+/// multiplicity `m` has the residues `A_k = G^(m-k)(r) / (m-k)!` of `G = (s - r)^m N / D`
+/// for `A_k t^(k-1) exp(r t) / (k-1)!`, and a simple pair of complex roots `a ± i b`
+/// contributes `2 exp(a t) (Re A cos(b t) - Im A sin(b t))` with `A = N / D'` at `a + i b`.
+fn ilaplace_term(term: &Expr, s: &Symbol, t: &Symbol) -> Option<Expr> {
+    let factors = match term {
+        Expr::Call(Call { op: Op::Mul, args }) => args.clone(),
+        _ => vec![term.clone()],
+    };
+    let (coeff, factors): (Vec<Expr>, Vec<Expr>) =
+        factors.into_iter().partition(|factor| !factor.contains(s));
+    let (x, time) = (
+        Expr::Atom(Atom::Symbol(s.clone())),
+        Expr::Atom(Atom::Symbol(t.clone())),
+    );
+
+    // s^-p is t^(p - 1) / Γ(p)
+    if let [Expr::Call(Call { op: Op::Pow, args })] = &factors[..] {
+        if let (true, Some(p)) = (args[0] == x, args[1].as_number()) {
+            let p = -p;
+            return (!p.is_negative() && !p.is_zero()).then(|| {
+                Expr::mul(vec![
+                    Expr::mul(coeff.clone()),
+                    Expr::pow(time.clone(), Expr::num(p.clone() - Number::int(1))),
+                    Expr::pow(
+                        Expr::function(Symbol("gamma".to_string()), Expr::num(p)),
+                        Expr::int(-1),
+                    ),
+                ])
+            });
+        }
+    }
+
+    let (mut num, mut den) = (Vec::new(), Vec::new());
+    for factor in factors {
+        match factor {
+            Expr::Call(Call { op: Op::Pow, args })
+                if args[1].as_number().is_some_and(|n| n.is_negative()) =>
+            {
+                den.push(Expr::pow(args[0].clone(), Expr::neg(args[1].clone())))
+            }
+            factor => num.push(factor),
+        }
+    }
+    let num = Expr::mul(num).expand().coeffs(s)?;
+    let den = Expr::mul(den)
+        .expand()
+        .coeffs(s)?
+        .iter()
+        .map(Expr::as_number)
+        .collect::<Option<Vec<_>>>()?;
+    // polynomials have no inverse without the delta distribution
+    if num.len() >= den.len() {
+        return None;
+    }
+
+    let den_expr: Vec<Expr> = den.iter().cloned().map(Expr::num).collect();
+    let derivative: Vec<Expr> = den
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(k, coeff)| Expr::num(coeff.clone() * Number::int(k as i128)))
+        .collect();
+    let mut terms = Vec::new();
+
+    for root in numeric_roots(den) {
+        if root.im.is_zero() {
+            let mut rest = den_expr.clone();
+            for _ in 0..root.multiplicity {
+                rest = deflate(&rest, &root.re);
+            }
+
+            let mut g = Expr::div(polynomial(&num, &x), polynomial(&rest, &x));
+            let growth = exp(Expr::mul(vec![root.re.clone(), time.clone()]));
+            for j in 0..root.multiplicity {
+                let k = root.multiplicity - j;
+                terms.push(Expr::mul(vec![
+                    Expr::div(g.replace(&x, &root.re), fact(j)),
+                    Expr::div(
+                        Expr::pow(time.clone(), Expr::int(k as i128 - 1)),
+                        fact(k - 1),
+                    ),
+                    growth.clone(),
+                ]));
+                g = g.derive(s).ok()?;
+            }
+        } else if root.multiplicity == 1 {
+            let at = (root.re.clone(), root.im.clone());
+            let (re, im) = complex_div(horner(&num, &at), horner(&derivative, &at));
+            let angle = Expr::mul(vec![root.im.clone(), time.clone()]);
+
+            terms.push(Expr::mul(vec![
+                Expr::int(2),
+                exp(Expr::mul(vec![root.re.clone(), time.clone()])),
+                Expr::sub(
+                    Expr::mul(vec![
+                        re,
+                        Expr::function(Symbol("cos".to_string()), angle.clone()),
+                    ]),
+                    Expr::mul(vec![im, Expr::function(Symbol("sin".to_string()), angle)]),
+                ),
+            ]));
+        } else {
+            return None;
+        }
+    }
+
+    Some(Expr::mul(vec![Expr::mul(coeff), Expr::add(terms)]))
+}
+
+/// `num / den` after applying `-scale d/dx` a number of times, where the numerator
+/// is expanded and the denominator stays a power of `den`.
+fn power_rule(num: Expr, den: Expr, var: &Symbol, times: usize, scale: Expr) -> Option<Expr> {
+    let slope = den.derive(var).ok()?;
+    let mut num = num;
+
+    // (N / D^j)' = (N' D - j N D') / D^(j + 1)
+    for j in 1..=times {
+        num = Expr::neg(Expr::mul(vec![
+            scale.clone(),
+            Expr::sub(
+                Expr::mul(vec![num.derive(var).ok()?, den.clone()]),
+                Expr::mul(vec![Expr::int(j as i128), num, slope.clone()]),
+            ),
+        ]))
+        .expand();
+    }
+
+    Some(Expr::div(num, Expr::pow(den, Expr::int(times as i128 + 1))))
+}
+
+/// `c0 + c1 x + ...` of coefficients.
+fn polynomial(coeffs: &[Expr], x: &Expr) -> Expr {
+    Expr::add(
+        coeffs
+            .iter()
+            .enumerate()
+            .map(|(k, coeff)| {
+                Expr::mul(vec![
+                    coeff.clone(),
+                    Expr::pow(x.clone(), Expr::int(k as i128)),
+                ])
+            })
+            .collect(),
+    )
+}
+
+/// Divide a polynomial by `x - root`, dropping the remainder.
+fn deflate(p: &[Expr], root: &Expr) -> Vec<Expr> {
+    let mut quotient = vec![Expr::int(0); p.len() - 1];
+    quotient[p.len() - 2] = p[p.len() - 1].clone();
+
+    for k in (1..p.len() - 1).rev() {
+        quotient[k - 1] = Expr::add(vec![
+            p[k].clone(),
+            Expr::mul(vec![root.clone(), quotient[k].clone()]),
+        ]);
+    }
+
+    quotient
+}
+
+/// Value `(re, im)` of a polynomial at a complex point.
+fn horner(p: &[Expr], (a, b): &(Expr, Expr)) -> (Expr, Expr) {
+    p.iter()
+        .rev()
+        .fold((Expr::int(0), Expr::int(0)), |(re, im), coeff| {
+            (
+                Expr::add(vec![
+                    coeff.clone(),
+                    Expr::mul(vec![re.clone(), a.clone()]),
+                    Expr::neg(Expr::mul(vec![im.clone(), b.clone()])),
+                ]),
+                Expr::add(vec![
+                    Expr::mul(vec![re, b.clone()]),
+                    Expr::mul(vec![im, a.clone()]),
+                ]),
+            )
+        })
+}
+
+/// `(a + i b) / (c + i d) = ((a c + b d) + i (b c - a d)) / (c^2 + d^2)`
+fn complex_div((a, b): (Expr, Expr), (c, d): (Expr, Expr)) -> (Expr, Expr) {
+    let norm = Expr::add(vec![
+        Expr::pow(c.clone(), Expr::int(2)),
+        Expr::pow(d.clone(), Expr::int(2)),
+    ]);
+
+    (
+        Expr::div(
+            Expr::add(vec![
+                Expr::mul(vec![a.clone(), c.clone()]),
+                Expr::mul(vec![b.clone(), d.clone()]),
+            ])
+            .expand(),
+            norm.clone(),
+        ),
+        Expr::div(
+            Expr::sub(Expr::mul(vec![b, c]), Expr::mul(vec![a, d])).expand(),
+            norm,
+        ),
+    )
+}
+
+/// A kernel `exp(-a t^2)`, `exp(-a abs(t))` or `1 / (t^2 + a^2)` is transformed by the table
+/// and then modulated: `exp(c t)` shifts `ω` by `i c`, a cosine averages the shifts by `±b`
+/// and `t^n` is `i^n` times the `n`-th derivative.
+fn fourier_term(term: &Expr, t: &Symbol, w: &Symbol) -> Option<Expr> {
+    let Term {
+        coeff,
+        power,
+        exponent,
+        trig,
+        rest,
+    } = decompose(term, t)?;
+    let n = power.as_int().filter(|n| *n >= 0)?;
+
+    let (x, omega) = (
+        Expr::Atom(Atom::Symbol(t.clone())),
+        Expr::Atom(Atom::Symbol(w.clone())),
+    );
+    let abs = |x: Expr| Expr::function(Symbol("abs".to_string()), x);
+    let sqrt = |x: Expr| Expr::pow(x, Expr::num(Number::ratio(1, 2)));
+    let square = |x: Expr| Expr::pow(x, Expr::int(2));
+
+    // the decay `abs(t)` is a variable of its own
+    let u = term.fresh("u");
+    let mut split = exponent
+        .replace(&abs(x.clone()), &Expr::Atom(Atom::Symbol(u.clone())))
+        .coeffs(&u)?
+        .into_iter();
+    let exponent = split.next().unwrap_or_else(|| Expr::int(0));
+    let decay = Expr::neg(split.next().unwrap_or_else(|| Expr::int(0)));
+    let mut exponent = exponent.coeffs(t)?.into_iter();
+    let start = exponent.next().unwrap_or_else(|| Expr::int(0));
+    let rate = exponent.next().unwrap_or_else(|| Expr::int(0));
+    let gauss = Expr::neg(exponent.next().unwrap_or_else(|| Expr::int(0)));
+    if split.next().is_some() || exponent.next().is_some() || decay.contains(t) {
+        return None;
+    }
+
+    let mut kernels = Vec::new();
+    if !gauss.is_zero() {
+        // sqrt(π / a) exp(-ω^2 / (4 a))
+        kernels.push(Expr::mul(vec![
+            sqrt(Expr::div(Expr::pi(), gauss.clone())),
+            exp(Expr::neg(Expr::div(
+                square(omega.clone()),
+                Expr::mul(vec![Expr::int(4), gauss]),
+            ))),
+        ]));
+    }
+    if !decay.is_zero() {
+        // 2 a / (a^2 + ω^2)
+        kernels.push(Expr::div(
+            Expr::mul(vec![Expr::int(2), decay.clone()]),
+            Expr::add(vec![square(decay), square(omega.clone())]),
+        ));
+    }
+    for factor in rest {
+        match factor {
+            // π exp(-a abs(ω)) / a for 1 / (t^2 + a^2)
+            Expr::Call(Call { op: Op::Pow, args }) if args[1] == Expr::int(-1) => {
+                match &args[0].coeffs(t)?[..] {
+                    [c0, c1, c2] if c1.is_zero() => {
+                        let a = sqrt(Expr::div(c0.clone(), c2.clone()));
+                        kernels.push(Expr::div(
+                            Expr::mul(vec![
+                                Expr::pi(),
+                                exp(Expr::neg(Expr::mul(vec![a.clone(), abs(omega.clone())]))),
+                            ]),
+                            Expr::mul(vec![c2.clone(), a]),
+                        ));
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    let mut transform = match &kernels[..] {
+        [kernel] => kernel.clone(),
+        _ => return None,
+    };
+    let shifted = |f: &Expr, shift: Expr| f.replace(&omega, &Expr::add(vec![omega.clone(), shift]));
+    let i_times = |x: Expr| Expr::mul(vec![Expr::i(), x]);
+
+    if !rate.is_zero() {
+        transform = shifted(&transform, i_times(rate));
+    }
+
+    let half = Expr::num(Number::ratio(1, 2));
+    transform = match trig {
+        None => transform,
+        // cos(b t) = (exp(i b t) + exp(-i b t)) / 2
+        Some((name, b)) => {
+            let shift = match &name[..] {
+                "sin" | "cos" => b,
+                _ => i_times(b),
+            };
+            let (below, above) = (
+                shifted(&transform, Expr::neg(shift.clone())),
+                shifted(&transform, shift),
+            );
+
+            match &name[..] {
+                "cos" | "cosh" => Expr::mul(vec![half, Expr::add(vec![below, above])]),
+                "sin" => Expr::mul(vec![half, Expr::i(), Expr::sub(above, below)]),
+                _ => Expr::mul(vec![half, Expr::sub(above, below)]),
+            }
+        }
+    };
+
+    for _ in 0..n {
+        transform = i_times(transform.derive(w).ok()?);
+    }
+
+    Some(Expr::mul(vec![coeff, exp(start), transform]))
+}
+
+/// `a^n` is `z / (z - a)`, times a sine `a z sin(b) / (z^2 - 2 a z cos(b) + a^2)` and times
+/// a cosine `z (z - a cos(b)) / (z^2 - 2 a z cos(b) + a^2)`, where `n f` is `-z F'`.
+fn ztransform_term(term: &Expr, n: &Symbol, z: &Symbol) -> Option<Expr> {
+    let Term {
+        coeff,
+        power,
+        exponent,
+        trig,
+        rest,
+    } = decompose(term, n)?;
+    let (start, rate) = linear_exponent(&exponent, n)?;
+    let k = power.as_int().filter(|k| *k >= 0)?;
+    if !rest.is_empty() {
+        return None;
+    }
+
+    let (x, a) = (Expr::Atom(Atom::Symbol(z.clone())), exp(rate));
+    let (num, den) = match trig {
+        None => (x.clone(), Expr::sub(x.clone(), a)),
+        Some((name, b)) => {
+            let (sine, cosine) = match &name[..] {
+                "sin" | "cos" => ("sin", "cos"),
+                _ => ("sinh", "cosh"),
+            };
+            let cos = Expr::function(Symbol(cosine.to_string()), b.clone());
+            let den = Expr::add(vec![
+                Expr::pow(x.clone(), Expr::int(2)),
+                Expr::mul(vec![Expr::int(-2), a.clone(), x.clone(), cos.clone()]),
+                Expr::pow(a.clone(), Expr::int(2)),
+            ]);
+
+            if name == sine {
+                let sin = Expr::function(Symbol(sine.to_string()), b);
+                (Expr::mul(vec![a, x.clone(), sin]), den)
+            } else {
+                let shift = Expr::sub(x.clone(), Expr::mul(vec![a, cos]));
+                (Expr::mul(vec![x.clone(), shift]).expand(), den)
+            }
+        }
+    };
+    // n f is -z F'
+    let transform = power_rule(num, den, z, k as usize, x)?;
+
+    Some(Expr::mul(vec![coeff, exp(start), transform]))
+}
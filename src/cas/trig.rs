@@ -0,0 +1,465 @@
+use super::{
+    env::Env,
+    expr::{
+        atom::{
+            number::{gcd, Number},
+            symbol::Symbol,
+            Atom,
+        },
+        call::{op::Op, Call},
+        Expr, TypeErr,
+    },
+};
+
+/// Denominators of the multiples of `π`, which inverse functions are tried with.
+const DENOMINATORS: [i128; 7] = [1, 2, 3, 4, 6, 8, 12];
+
+/// Exact values at rational multiples of `π` and rewriting of trigonometric expressions.
+impl Expr {
+    pub(crate) fn pi() -> Expr {
+        Expr::Atom(Atom::Symbol(Symbol("π".to_string())))
+    }
+
+    /// `trigexpand(expr)` expands functions of sums and multiples, so `sin(2x) = 2 sin x cos x`.
+    pub(crate) fn trigexpand(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        Ok(Expr::trig_arg("trigexpand", args, env)?
+            .trig_expand()
+            .expand())
+    }
+
+    /// `trigreduce(expr)` turns powers and products into sums, so `sin(x)^2 = (1 - cos 2x) / 2`.
+    pub(crate) fn trigreduce(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        Ok(Expr::trig_arg("trigreduce", args, env)?.trig_reduce())
+    }
+
+    /// `trigsimp(expr)` is the smallest form using `sin(x)^2 + cos(x)^2 = 1` and `tan = sin / cos`.
+    pub(crate) fn trigsimp(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        let expr = Expr::trig_arg("trigsimp", args, env)?;
+        let sines = expr.pythagoras("cos", "sin").expand();
+        let cosines = expr.pythagoras("sin", "cos").expand();
+
+        Ok(vec![sines, cosines, expr.trig_reduce()]
+            .into_iter()
+            .map(|candidate| candidate.tangents())
+            .fold(expr.tangents(), |best, candidate| {
+                if candidate.size() < best.size() {
+                    candidate
+                } else {
+                    best
+                }
+            }))
+    }
+
+    fn trig_arg(call: &str, args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        match args {
+            [expr] => expr.eval(env),
+            _ => Err(TypeErr(format!(
+                "call `{}` undefined on (expr, ...), expected (expr)",
+                call
+            ))),
+        }
+    }
+
+    /// Exact value of a trigonometric function, or a simpler argument by symmetry.
+    pub(crate) fn exact_trig(call: &str, x: &Expr) -> Option<Expr> {
+        let function = |call: &str, x: Expr| Expr::function(Symbol(call.to_string()), x);
+
+        match call {
+            "sin" | "cos" | "tan" => {}
+            "asin" => return Expr::inverse_trig(x, -6, 6, sin_pi),
+            "acos" => return Expr::inverse_trig(x, 0, 12, cos_pi),
+            "atan" => return Expr::inverse_trig(x, -5, 5, tan_pi),
+            _ => return None,
+        }
+
+        if let Some(Number::Ratio(num, den)) = x.pi_multiple() {
+            return match call {
+                "sin" => sin_pi(num, den),
+                "cos" => cos_pi(num, den),
+                _ => tan_pi(num, den),
+            };
+        }
+
+        // sine and tangent are odd, cosine is even
+        let (coef, rest) = x.split_coef();
+        if coef.is_negative() && x.as_number().is_none() {
+            let positive = Expr::mul(vec![Expr::num(-coef), rest]);
+            return Some(match call {
+                "cos" => function(call, positive),
+                _ => Expr::neg(function(call, positive)),
+            });
+        }
+
+        // shifts by multiples of `π/2`
+        if let Expr::Call(Call { op: Op::Add, args }) = x {
+            let (shifts, rest): (Vec<Expr>, Vec<Expr>) = args
+                .iter()
+                .cloned()
+                .partition(|arg| arg.pi_multiple().is_some());
+            let quarters = Expr::add(shifts).pi_multiple()? * Number::int(2);
+            let quarters = quarters.as_int()?.rem_euclid(4);
+            let x = Expr::add(rest);
+
+            return Some(match (call, quarters) {
+                (_, 0) => return None,
+                ("tan", 2) => function("tan", x),
+                ("tan", _) => Expr::neg(Expr::pow(function("tan", x), Expr::int(-1))),
+                ("sin", 1) | ("cos", 3) => function("cos", x),
+                ("sin", 3) | ("cos", 1) => Expr::neg(function("sin", x)),
+                (_, _) => Expr::neg(function(call, x)),
+            });
+        }
+
+        None
+    }
+
+    /// The rational `q` of `q π`.
+    fn pi_multiple(&self) -> Option<Number> {
+        match self {
+            Expr::Atom(Atom::Symbol(Symbol(name))) if name == "π" => Some(Number::int(1)),
+            Expr::Call(Call { op: Op::Mul, args }) if args.len() == 2 && args[1] == Expr::pi() => {
+                args[0].as_number().filter(Number::is_exact)
+            }
+            _ => self.as_number().filter(Number::is_zero),
+        }
+    }
+
+    /// The multiple `q π` in a range of `q`, whose value is x.
+    fn inverse_trig(
+        x: &Expr,
+        from: i128,
+        to: i128,
+        value: fn(i128, i128) -> Option<Expr>,
+    ) -> Option<Expr> {
+        x.float()?;
+
+        // the ranges are given in twelfths
+        DENOMINATORS.iter().find_map(|den| {
+            (from * den / 12..=to * den / 12)
+                .filter(|num| gcd(*num, *den) == 1 || *num == 0)
+                .find(|num| value(*num, *den).as_ref() == Some(x))
+                .map(|num| Expr::mul(vec![Expr::num(Number::ratio(num, *den)), Expr::pi()]))
+        })
+    }
+
+    /// Expand functions of sums and integer multiples, but nothing else.
+    fn trig_expand(&self) -> Expr {
+        let expr = match self {
+            Expr::Call(Call { op, args }) => {
+                Expr::rebuild(op, args.iter().map(Expr::trig_expand).collect())
+            }
+            _ => return self.clone(),
+        };
+
+        let (call, x) = match &expr {
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) if args.len() == 1 => (call.0.as_str(), &args[0]),
+            _ => return expr,
+        };
+
+        // split the angle into `a + b` or a multiple `n a`
+        let (a, b) = match x {
+            Expr::Call(Call { op: Op::Add, args }) => {
+                (args[0].clone(), Expr::add(args[1..].to_vec()))
+            }
+            Expr::Call(Call { op: Op::Mul, args }) => match args[0].as_number() {
+                Some(n) if n.as_int().is_some_and(|n| n > 1) => {
+                    let a = Expr::mul(args[1..].to_vec());
+                    let n = n.as_int().expect("checked to be an integer");
+                    let (sin_na, cos_na) = multiple_angle(n, a);
+
+                    return match call {
+                        "sin" => sin_na,
+                        "cos" => cos_na,
+                        "tan" => Expr::div(sin_na, cos_na),
+                        _ => expr,
+                    };
+                }
+                _ => return expr,
+            },
+            _ => return expr,
+        };
+
+        let (sin_a, cos_a) = (sin(a.clone()).trig_expand(), cos(a).trig_expand());
+        let (sin_b, cos_b) = (sin(b.clone()).trig_expand(), cos(b).trig_expand());
+
+        let sin_sum = || {
+            Expr::add(vec![
+                Expr::mul(vec![sin_a.clone(), cos_b.clone()]),
+                Expr::mul(vec![cos_a.clone(), sin_b.clone()]),
+            ])
+        };
+        let cos_sum = || {
+            Expr::sub(
+                Expr::mul(vec![cos_a.clone(), cos_b.clone()]),
+                Expr::mul(vec![sin_a.clone(), sin_b.clone()]),
+            )
+        };
+
+        match call {
+            "sin" => sin_sum(),
+            "cos" => cos_sum(),
+            "tan" => Expr::div(sin_sum(), cos_sum()),
+            _ => expr,
+        }
+    }
+
+    /// Replace products of sines and cosines by sums, term by term.
+    fn trig_reduce(&self) -> Expr {
+        let expr = match self {
+            Expr::Call(Call { op, args }) => {
+                Expr::rebuild(op, args.iter().map(Expr::trig_reduce).collect())
+            }
+            _ => return self.clone(),
+        };
+
+        match expr.expand() {
+            Expr::Call(Call { op: Op::Add, args }) => {
+                Expr::add(args.iter().map(Expr::reduce_product).collect())
+            }
+            term => term.reduce_product(),
+        }
+    }
+
+    fn reduce_product(&self) -> Expr {
+        let factors = match self {
+            Expr::Call(Call { op: Op::Mul, args }) => args.clone(),
+            _ => vec![self.clone()],
+        };
+
+        let (mut trig, mut rest) = (Vec::new(), Vec::new());
+        for factor in factors {
+            match &factor {
+                Expr::Call(Call { op: Op::Pow, args }) if args[0].sine_or_cosine().is_some() => {
+                    match args[1].as_number().and_then(|exp| exp.as_int()) {
+                        Some(exp) if exp > 0 => {
+                            trig.extend((0..exp).map(|_| args[0].clone()));
+                        }
+                        _ => rest.push(factor),
+                    }
+                }
+                _ if factor.sine_or_cosine().is_some() => trig.push(factor),
+                _ => rest.push(factor),
+            }
+        }
+
+        if trig.len() < 2 {
+            return self.clone();
+        }
+
+        let (first, second) = (trig.remove(0), trig.remove(0));
+        let ((f, a), (g, b)) = (
+            first.sine_or_cosine().expect("sorted as a sine or cosine"),
+            second.sine_or_cosine().expect("sorted as a sine or cosine"),
+        );
+        let (diff, sum) = (Expr::sub(a.clone(), b.clone()), Expr::add(vec![a, b]));
+
+        // product-to-sum identities
+        let sum = match (f, g) {
+            ("sin", "sin") => Expr::sub(cos(diff), cos(sum)),
+            ("cos", "cos") => Expr::add(vec![cos(diff), cos(sum)]),
+            ("sin", _) => Expr::add(vec![sin(sum), sin(diff)]),
+            _ => Expr::sub(sin(sum), sin(diff)),
+        };
+
+        rest.extend(trig);
+        rest.push(Expr::num(Number::ratio(1, 2)));
+        rest.push(sum);
+
+        Expr::mul(rest).trig_reduce()
+    }
+
+    /// Name and argument of a sine or cosine.
+    fn sine_or_cosine(&self) -> Option<(&'static str, Expr)> {
+        match self {
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) if args.len() == 1 => match &call.0[..] {
+                "sin" => Some(("sin", args[0].clone())),
+                "cos" => Some(("cos", args[0].clone())),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Replace even powers of one function by powers of `1 - other^2`.
+    fn pythagoras(&self, from: &str, to: &str) -> Expr {
+        match self {
+            Expr::Call(Call { op: Op::Pow, args }) => {
+                let exp = args[1].as_number().and_then(|exp| exp.as_int());
+
+                match (args[0].sine_or_cosine(), exp) {
+                    (Some((call, x)), Some(exp)) if call == from && exp >= 2 => {
+                        let other = Expr::function(Symbol(to.to_string()), x);
+                        Expr::mul(vec![
+                            Expr::pow(
+                                Expr::sub(Expr::int(1), Expr::pow(other, Expr::int(2))),
+                                Expr::int(exp / 2),
+                            ),
+                            Expr::pow(args[0].clone(), Expr::int(exp % 2)),
+                        ])
+                    }
+                    _ => Expr::pow(args[0].pythagoras(from, to), args[1].clone()),
+                }
+            }
+            Expr::Call(Call { op, args }) => Expr::rebuild(
+                op,
+                args.iter().map(|arg| arg.pythagoras(from, to)).collect(),
+            ),
+            _ => self.clone(),
+        }
+    }
+
+    /// Combine `sin(x)^n cos(x)^-n` of a product into `tan(x)^n`.
+    fn tangents(&self) -> Expr {
+        let args = match self {
+            Expr::Call(Call { op, args }) if *op != Op::Mul => {
+                return Expr::rebuild(op, args.iter().map(Expr::tangents).collect())
+            }
+            Expr::Call(Call { args, .. }) => args.iter().map(Expr::tangents).collect::<Vec<_>>(),
+            _ => return self.clone(),
+        };
+
+        // the sines and cosines with their integer exponent
+        let power = |factor: &Expr| match factor {
+            Expr::Call(Call { op: Op::Pow, args }) => {
+                Some((args[0].sine_or_cosine()?, args[1].as_number()?.as_int()?))
+            }
+            _ => Some((factor.sine_or_cosine()?, 1)),
+        };
+
+        let mut factors = args.clone();
+        for sine in &args {
+            let (x, n) = match power(sine) {
+                Some((("sin", x), n)) => (x, n),
+                _ => continue,
+            };
+
+            let cosine = factors
+                .iter()
+                .position(|factor| power(factor) == Some((("cos", x.clone()), -n)));
+
+            if let Some(cosine) = cosine {
+                factors.remove(cosine);
+                let sine = factors
+                    .iter()
+                    .position(|factor| factor == sine)
+                    .expect("the sine is still a factor");
+                factors[sine] = Expr::pow(tan(x), Expr::int(n));
+            }
+        }
+
+        Expr::mul(factors)
+    }
+
+    /// Number of nodes in the tree.
+    fn size(&self) -> usize {
+        match self {
+            Expr::Atom(_) => 1,
+            Expr::Call(Call { args, .. }) => 1 + args.iter().map(Expr::size).sum::<usize>(),
+        }
+    }
+}
+
+fn sin(x: Expr) -> Expr {
+    Expr::function(Symbol("sin".to_string()), x)
+}
+
+fn cos(x: Expr) -> Expr {
+    Expr::function(Symbol("cos".to_string()), x)
+}
+
+fn tan(x: Expr) -> Expr {
+    Expr::function(Symbol("tan".to_string()), x)
+}
+
+/// `sin(n a)` and `cos(n a)` in powers of `sin a` and `cos a` by de Moivre's formula
+/// `cos(n a) + i sin(n a) = (cos a + i sin a)^n`, so odd powers of the sine go into the sine.
+fn multiple_angle(n: i128, a: Expr) -> (Expr, Expr) {
+    let (sin_a, cos_a) = (sin(a.clone()).trig_expand(), cos(a).trig_expand());
+    let (mut sin_terms, mut cos_terms) = (Vec::new(), Vec::new());
+
+    // the binomials are symmetric, so the outer ones stay exact even if the middle ones overflow
+    let mut binomials = vec![Number::int(1)];
+    for k in 0..n / 2 {
+        binomials.push(binomials[k as usize].clone() * Number::int(n - k) / Number::int(k + 1));
+    }
+
+    for k in 0..=n {
+        let binomial = binomials[k.min(n - k) as usize].clone();
+        let term = Expr::mul(vec![
+            Expr::num(if k % 4 < 2 { binomial } else { -binomial }),
+            Expr::pow(cos_a.clone(), Expr::int(n - k)),
+            Expr::pow(sin_a.clone(), Expr::int(k)),
+        ]);
+
+        if k % 2 == 0 {
+            cos_terms.push(term);
+        } else {
+            sin_terms.push(term);
+        }
+    }
+
+    (Expr::add(sin_terms), Expr::add(cos_terms))
+}
+
+fn sqrt(x: i128) -> Expr {
+    Expr::pow(Expr::int(x), Expr::num(Number::ratio(1, 2)))
+}
+
+/// `cos(num/den π)`, if it is known exactly.
+fn cos_pi(num: i128, den: i128) -> Option<Expr> {
+    // reduce to the first quadrant
+    let mut num = num.rem_euclid(2 * den);
+    if num > den {
+        num = 2 * den - num;
+    }
+    let negative = 2 * num > den;
+    if negative {
+        num = den - num;
+    }
+
+    let gcd = gcd(num, den) as i128;
+    let half = Number::ratio(1, 2);
+    let quarter = Number::ratio(1, 4);
+
+    let value = match (num / gcd, den / gcd) {
+        (0, _) => Expr::int(1),
+        (1, 2) => Expr::int(0),
+        (1, 3) => Expr::num(half),
+        (1, 4) => Expr::mul(vec![Expr::num(half), sqrt(2)]),
+        (1, 6) => Expr::mul(vec![Expr::num(half), sqrt(3)]),
+        (1, 12) => Expr::mul(vec![Expr::num(quarter), Expr::add(vec![sqrt(6), sqrt(2)])]),
+        (5, 12) => Expr::mul(vec![Expr::num(quarter), Expr::sub(sqrt(6), sqrt(2))]),
+        (1, 5) => Expr::mul(vec![
+            Expr::num(quarter),
+            Expr::add(vec![Expr::int(1), sqrt(5)]),
+        ]),
+        (2, 5) => Expr::mul(vec![Expr::num(quarter), Expr::sub(sqrt(5), Expr::int(1))]),
+        (1, 8) => Expr::mul(vec![
+            Expr::num(half.clone()),
+            Expr::pow(Expr::add(vec![Expr::int(2), sqrt(2)]), Expr::num(half)),
+        ]),
+        (3, 8) => Expr::mul(vec![
+            Expr::num(half.clone()),
+            Expr::pow(Expr::sub(Expr::int(2), sqrt(2)), Expr::num(half)),
+        ]),
+        _ => return None,
+    };
+
+    Some(if negative { Expr::neg(value) } else { value })
+}
+
+/// `sin(num/den π) = cos((1/2 - num/den) π)`.
+fn sin_pi(num: i128, den: i128) -> Option<Expr> {
+    cos_pi(den - 2 * num, 2 * den)
+}
+
+/// `tan(num/den π)`, which has a pole like `1/0` at odd multiples of `π/2`.
+fn tan_pi(num: i128, den: i128) -> Option<Expr> {
+    Some(Expr::div(sin_pi(num, den)?, cos_pi(num, den)?).expand())
+}
@@ -0,0 +1,27 @@
+use super::expr::{
+    atom::number::Number,
+    call::{op::Op, Call},
+    Expr,
+};
+
+/// Measurements `x ± u` with a standard uncertainty.
+impl Expr {
+    /// Number with a new independent uncertainty, which stays unevaluated for variables.
+    pub(crate) fn plus_minus(x: Expr, u: Expr) -> Expr {
+        match (x.as_number(), u.as_number()) {
+            (Some(x), Some(u)) => Expr::num(x + Number::uncertain(0.0, u.as_f64())),
+            _ => Expr::Call(Call::new(Op::PlusMinus, vec![x, u])),
+        }
+    }
+
+    /// Whether the expression takes a measurement.
+    pub(crate) fn measures(&self) -> bool {
+        match self {
+            Expr::Call(Call {
+                op: Op::PlusMinus, ..
+            }) => true,
+            Expr::Call(Call { args, .. }) => args.iter().any(Expr::measures),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use super::{
+    env::{Def, Env},
+    expr::{
+        atom::{symbol::Symbol, Atom},
+        call::{op::Op, Call},
+        Expr, TypeErr,
+    },
+};
+
+/// Prefixes of the SI, as powers of ten.
+const PREFIXES: [(&str, i128); 25] = [
+    ("Q", 30),
+    ("R", 27),
+    ("Y", 24),
+    ("Z", 21),
+    ("E", 18),
+    ("P", 15),
+    ("T", 12),
+    ("G", 9),
+    ("M", 6),
+    ("k", 3),
+    ("h", 2),
+    ("da", 1),
+    ("d", -1),
+    ("c", -2),
+    ("m", -3),
+    ("μ", -6),
+    ("u", -6),
+    ("n", -9),
+    ("p", -12),
+    ("f", -15),
+    ("a", -18),
+    ("z", -21),
+    ("y", -24),
+    ("r", -27),
+    ("q", -30),
+];
+
+/// Built-in functions of one argument, that are also defined on quantities with units.
+const DIMENSIONAL: [&str; 9] = [
+    "abs", "signum", "ceil", "floor", "round", "trunc", "fract", "sqrt", "cbrt",
+];
+
+/// Quantities with units, loaded by `load(units)` from `units_env.txt`.
+/// Base units stay symbols, all other units are defined in terms of them.
+impl Expr {
+    /// `load(name)` adds the definitions of `name_env.txt`.
+    pub(crate) fn load(args: &[Expr], env: &mut Env) -> Result<Expr, TypeErr> {
+        match args {
+            [Expr::Atom(Atom::Symbol(name))] => {
+                let path = format!("{}_env.txt", name.0);
+                env.import(&path)
+                    .map_err(|error| TypeErr(format!("can not load `{}`, {}", path, error)))?;
+
+                Ok(Expr::Atom(Atom::Symbol(Symbol("Def".to_string()))))
+            }
+            _ => Err(TypeErr(
+                "call `load` undefined on (expr, ...), expected (symbol)".to_string(),
+            )),
+        }
+    }
+
+    /// An undefined symbol like `km` as a prefix of a defined unit.
+    pub(crate) fn prefixed(symbol: &Symbol, env: &Env) -> Option<Expr> {
+        PREFIXES.iter().find_map(|(prefix, power)| {
+            let unit = Symbol(symbol.0.strip_prefix(prefix)?.to_string());
+
+            Expr::is_unit(&unit, env, 0).then(|| {
+                Expr::mul(vec![
+                    Expr::pow(Expr::int(10), Expr::int(*power)),
+                    Expr::Atom(Atom::Symbol(unit)),
+                ])
+            })
+        })
+    }
+
+    /// A base unit, or a definition in terms of base units.
+    pub(crate) fn is_unit(symbol: &Symbol, env: &Env, depth: usize) -> bool {
+        match env.get(symbol) {
+            Ok(Def::OSUnit) => true,
+            // definitions could refer to each other
+            Ok(Def::Expr(expr)) if depth < 8 => expr
+                .symbols()
+                .iter()
+                .any(|symbol| Expr::is_unit(symbol, env, depth + 1)),
+            _ => false,
+        }
+    }
+
+    fn symbols(&self) -> Vec<Symbol> {
+        match self {
+            Expr::Atom(Atom::Symbol(symbol)) => vec![symbol.clone()],
+            Expr::Call(Call { args, .. }) => args.iter().flat_map(Expr::symbols).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The product of base units of an evaluated quantity, `1` if it has none,
+    /// and `None` if it is unknown, like for a variable.
+    pub(crate) fn dimension(&self, env: &Env) -> Option<Expr> {
+        match self {
+            Expr::Atom(Atom::Number(_)) => Some(Expr::int(1)),
+            Expr::Atom(Atom::Symbol(symbol)) => match env.get(symbol) {
+                Ok(Def::OSUnit) => Some(self.clone()),
+                Ok(Def::OSConst) => Some(Expr::int(1)),
+                _ => None,
+            },
+            Expr::Call(Call { op: Op::Mul, args }) => {
+                let dimensions: Option<Vec<Expr>> =
+                    args.iter().map(|arg| arg.dimension(env)).collect();
+                Some(Expr::mul(dimensions?))
+            }
+            Expr::Call(Call { op: Op::Pow, args }) => {
+                match (args[0].dimension(env)?, args[1].as_number()) {
+                    (base, Some(exp)) => Some(Expr::pow(base, Expr::num(exp))),
+                    (base, None) if base == Expr::int(1) => Some(base),
+                    _ => None,
+                }
+            }
+            Expr::Call(Call { op: Op::Add, args }) => {
+                let first = args[0].dimension(env)?;
+                args[1..]
+                    .iter()
+                    .all(|arg| arg.dimension(env).as_ref() == Some(&first))
+                    .then_some(first)
+            }
+            Expr::Call(Call {
+                op: Op::Call(call),
+                args,
+            }) if call.0 == "abs" => args[0].dimension(env),
+            // functions are only defined on plain numbers
+            Expr::Call(Call {
+                op: Op::Call(_),
+                args,
+            }) => args
+                .iter()
+                .all(|arg| arg.dimension(env) == Some(Expr::int(1)))
+                .then(|| Expr::int(1)),
+            _ => None,
+        }
+    }
+
+    /// Terms of a sum with the same dimension, so `m + s` is an error.
+    pub(crate) fn commensurable(terms: Vec<Expr>, env: &Env) -> Result<Vec<Expr>, TypeErr> {
+        let dimensions: Vec<(&Expr, Expr)> = terms
+            .iter()
+            .filter_map(|term| Some((term, term.dimension(env)?)))
+            .collect();
+
+        for pair in dimensions.windows(2) {
+            let ((x, dx), (y, dy)) = (&pair[0], &pair[1]);
+
+            if Expr::div(dx.clone(), dy.clone()) != Expr::int(1) {
+                return Err(TypeErr(format!(
+                    "dimensions of `{}` and `{}` do not match, found `{}` and `{}`",
+                    x, y, dx, dy
+                )));
+            }
+        }
+
+        Ok(terms)
+    }
+
+    /// Functions other than these are only defined on plain numbers, so `sin(3 m)` is an error.
+    pub(crate) fn dimensionless(call: &Symbol, x: Expr, env: &Env) -> Result<Expr, TypeErr> {
+        match x.dimension(env) {
+            Some(dimension) if dimension != Expr::int(1) && !DIMENSIONAL.contains(&&call.0[..]) => {
+                Err(TypeErr(format!(
+                    "call `{}` undefined on `{}` of dimension `{}`",
+                    call, x, dimension
+                )))
+            }
+            _ => Ok(x),
+        }
+    }
+
+    /// `quantity to unit` or `quantity in unit` is a multiple of the unit.
+    pub(crate) fn convert(quantity: &Expr, unit: &Expr, env: &mut Env) -> Result<Expr, TypeErr> {
+        let (value, base) = (quantity.eval(env)?, unit.eval(env)?);
+
+        match (value.dimension(env), base.dimension(env)) {
+            (_, None) => Err(TypeErr(format!(
+                "`{}` is no unit of a known dimension",
+                unit
+            ))),
+            (None, _) => Err(TypeErr(format!(
+                "can not convert `{}` of unknown dimension to `{}`",
+                quantity, unit
+            ))),
+            (Some(from), Some(to)) if Expr::div(from.clone(), to.clone()) != Expr::int(1) => {
+                Err(TypeErr(format!(
+                    "can not convert `{}` of dimension `{}` to `{}` of dimension `{}`",
+                    quantity, from, unit, to
+                )))
+            }
+            // the unit stays as written, until it is evaluated again
+            _ => Ok(Expr::mul(vec![Expr::div(value, base), unit.canonical()])),
+        }
+    }
+}
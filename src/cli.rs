@@ -2,7 +2,7 @@ use {
     super::cas::{
         env::{Def, Env},
         expr::{
-            atom::{symbol::Symbol, Atom},
+            atom::{number::Number, symbol::Symbol, Atom},
             Expr,
         },
     },
@@ -45,6 +45,11 @@ impl REPL {
                     // restart the loop if the input could not be handled
                     continue;
                 }
+                // end of input, e.g. a piped file
+                Ok(0) => {
+                    println!();
+                    break;
+                }
                 _ => {}
             }
 
@@ -68,17 +73,37 @@ impl REPL {
                 }
             };
 
-            // try to eval to a number
+            // the exact result, unless it is just an inexact number
+            if !matches!(
+                result,
+                Expr::Atom(Atom::Number(
+                    Number::Float(_)
+                        | Number::Interval(_, _)
+                        | Number::Uncertain(_, _)
+                        | Number::Dual(_, _)
+                ))
+            ) {
+                println!("\u{1b}[31;1m|=\u{1b}[0m {}", result.tidy());
+            }
+
+            // try to eval to a number, if the exact result is not already an integer
             match result.number(&mut repl.env) {
-                Ok(number) => println!("\u{1b}[91;1m|≈\u{1b}[0m {}", number),
-                Err(_) => { /* println!("\u{1b}[31;1m|=\u{1b}[0m {}", &result) */ }
+                Ok(number)
+                    if !matches!(
+                        result,
+                        Expr::Atom(Atom::Number(Number::Ratio(_, 1) | Number::Fixed(_, _)))
+                    ) =>
+                {
+                    println!("\u{1b}[91;1m|≈\u{1b}[0m {}", number)
+                }
+                _ => {}
             }
 
             // free line
             println!();
 
             // implements ans to copy a result
-            if &result != &Expr::Atom(Atom::Symbol(Symbol(String::from("ans")))) {
+            if result != Expr::Atom(Atom::Symbol(Symbol(String::from("ans")))) {
                 repl.env
                     .insert(Symbol("ans".to_string()), Def::Expr(result));
             }
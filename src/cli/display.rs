@@ -17,7 +17,7 @@ impl fmt::Display for LexerErr {
 
         match self {
             // maybe before: "could not parse token: "
-            LexerErr::Panic { msg, at } => {
+            LexerErr::Panic { msg, .. } => {
                 write!(f, "{}", msg)
             }
             LexerErr::EOF => write!(f, "reached `{}`", &Op::Call(Symbol(String::from("EOF")))),
@@ -110,13 +110,29 @@ impl fmt::Debug for Call {
 
 impl fmt::Display for Number {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "\u{1b}[91m{}\u{1b}[0m", self.0)
+        write!(f, "\u{1b}[91m{:?}\u{1b}[0m", self)
     }
 }
 
 impl fmt::Debug for Number {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        match self {
+            Number::Ratio(num, 1) => write!(f, "{}", num),
+            Number::Ratio(num, den) => write!(f, "{}/{}", num, den),
+            Number::Float(float) => write!(f, "{}", float),
+            Number::Interval(lower, upper) => write!(f, "[{} .. {}]", lower, upper),
+            Number::Uncertain(value, _) => write!(f, "({} ± {})", value, self.deviation()),
+            Number::Dual(value, tangent) => write!(f, "({} + {}ε)", value, tangent),
+            Number::Fixed(n, width) => {
+                let sign = if width.signed { "i" } else { "u" };
+
+                if width.saturating {
+                    write!(f, "saturating({}{}{})", n, sign, width.bits)
+                } else {
+                    write!(f, "{}{}{}", n, sign, width.bits)
+                }
+            }
+        }
     }
 }
 
@@ -150,6 +166,9 @@ impl fmt::Debug for Op {
                 Op::Div => "/",
                 Op::Pow => "^",
                 Op::Fact => "!",
+                Op::Prime => "'",
+                Op::Interval => "..",
+                Op::PlusMinus => "±",
                 Op::Mod => "%",
                 Op::Eq => "=",
                 Op::Neq => "!=",
@@ -158,6 +177,9 @@ impl fmt::Debug for Op {
                 Op::More => ">",
                 Op::LessEq => "<=",
                 Op::MoreEq => ">=",
+                Op::Rule => "->",
+                Op::If => "if",
+                Op::To => "to",
                 Op::List => ";",
                 Op::Open => "(",
                 Op::Close => ")",
@@ -1,10 +1,10 @@
 //! # `Taschenrechner` – [*Computer-Algebra-System*](https://en.wikipedia.org/wiki/Computer_algebra_system)
 //!
 //! > A **`CAS`** is an advanced symbolic calculator. It can *evaluate,
-//! simplify, differentiate, integrate and solve* algebraic expressions.
+//! > simplify, differentiate, integrate and solve* algebraic expressions.
 //!
-//! ```
-//! use cas::prelude::*;
+//! ```no_run
+//! use taschenrechner::prelude::*;
 //!
 //! fn main() {
 //!     REPL::start();
@@ -30,8 +30,9 @@
 //!
 //! #### Eval
 //!
-//! The current eval is not very sophisticated. It is functional, but can't
-//! simplify any expressions, if a subexpression remains unresolved.
+//! Eval is exact: fractions stay fractions and unresolved symbols stay symbolic,
+//! while sums, products and powers are brought into a canonical form. Numbers are
+//! only approximated on the `|≈` line of the REPL, or by `N(expr, digits)`.
 //!
 //! ### REPL
 //!
@@ -41,9 +42,33 @@
 //!
 //! (for a full complete list see [`default_env.txt`](https://github.com/m4dh0rs3/cas/blob/master/default_env.txt))
 //!
-//! - `π` (pi), `τ` (tau), `e` (eurler's number), `inf` (infinity), `nan` (not a number)
+//! - `π` (pi), `τ` (tau), `e` (eurler's number), `i` (imaginary unit), `inf` (infinity), `nan` (not a number), which all stay exact, so `e^(iπ) = -1`
 //! - trigonometric functions: `sin`, `cos`, `tan`, their inverse and hyperbole
 //! - `abs`, `ceil`, `floor`, `trunc`, `fract`
+//! - `series(expr, x, x0, n)` Taylor and Laurent series up to an order term `O((x - x0)^n)`
+//! - `sum(expr, k, a, b)` and `prod(expr, k, a, b)` in closed form where possible, `b` may be `inf` if it converges
+//! - `diff(expr, x, n)` the `n`-th derivative, `limit(expr, x, x0, dir)` limits, also at `inf` and one-sided for `dir = 1` (above) or `dir = -1` (below)
+//! - `rule lhs -> rhs if cond` adds a rewrite rule which every evaluation applies and `unrule lhs` removes it, `replace(expr, lhs -> rhs)` applies rules only there; pattern variables like `a_` match any subexpression
+//! - `subs(expr, x = a, y = b)` substitutes symbols or subexpressions simultaneously and simplifies
+//! - `trigexpand`, `trigreduce` and `trigsimp` rewrite trigonometric expressions, which are exact at rational multiples of `π`, like `sin(π/6) = 1/2`
+//! - `expandlog` splits logarithms of positive products and powers, `combinelog` merges them again where all but one are positive; `log(x, b)` is `ln x / ln b` unless that is exact
+//! - `assume(x > 0)` or `assume(n, integer)` records what is known about a symbol, rules and `expandlog` consult it, `refine(expr)` simplifies again and `is(expr, positive)` asks
+//! - `isprime`, `nextprime`, `factorint(n)` (pairs `p, k`), `gcd`, `lcm`, `egcd`, `powmod(b, e, m)`, `modinv`, `totient`, `divisors` and `crt(r1, m1, r2, m2, ...)` on exact integers up to `i128`, `factorint(1)` is `1, 1`; `a % m` has the sign of `m`
+//! - `n!` or `fact`, `gamma`, `lgamma` and `beta` continue to real numbers, `gamma(1/2) = sqrt π`; `binomial`, `perm`, `multinomial`, `stirling1` (unsigned), `stirling2`, `bell`, `catalan` and `fibonacci` are exact on integers that fit into `i128`, beyond that they are floats like `34!` or `inf` like `100000!`
+//! - special functions `erf`, `erfc`, `digamma`, `zeta`, `polylog(s, z)`, `lambertw`, `besselj(n, x)`, `bessely(n, x)`, `airyai`, `airybi` and their derivatives `airyaiprime`, `airybiprime`, and the elliptic integrals `ellipk(m)`, `ellipe(m)`, `ellipf(φ, m)`, `ellipe(φ, m)`; `diff` knows their derivatives
+//! - statistics `mean`, `median`, `mode`, `var`, `std`, `quantile(data, p)`, `cov(xs, ys)`, `corr(xs, ys)` and `linreg(xs, ys)` (list `a, b` of `y = a + b x`) over lists or arguments, `sum` and `prod` of lists, and `pdf`, `cdf` and `quantile` of the `normal`, `binomial`, `poisson`, `uniform`, `exponential`, `t` and `chi2` distributions, as in `normalcdf(x, μ, σ)`
+//! - numeric roots with `nsolve(f, x, guess)` by Newton's method, `nsolve(f, x, a, b)` by Brent's method in between `a` and `b`, and `nsolve(p, x)` for all real roots of a polynomial, each as the list `root, residual`
+//! - numeric integrals `nintegrate(f, x, a, b)` by adaptive Gauss-Kronrod or tanh-sinh quadrature, also to `inf`, as the list `integral, error`, where the error of a divergent integral is `inf`
+//! - ordinary differential equations with derivatives `y'`, solved in closed form by `dsolve(y'' + y = 0, y(x))` or numerically by `odesolve(f, y, y0, t, t0, t1, n)` as a table of `n + 1` rows `t, y`
+//! - integral transforms `laplace(f, t, s)`, `ilaplace(F, s, t)` by partial fractions, `fourier(f, t, ω)`, `ifourier(F, ω, t)` and `ztransform(f, n, z)` from tables with shifts, modulations and powers
+//! - interval arithmetic `[a .. b]` or `interval(a, b)` with outward rounding, propagated through arithmetic and built-in functions, where functions without a known enclosure give `[-inf .. inf]` and results without real numbers like `sqrt([-4 .. -1])` are errors
+//! - physical units after `load(units)` from `units_env.txt`, with SI base and derived units, `hr` for the hour, prefixes like `km` or `μs`, quantities like `3 m / 2 s` where a number binds its unit, conversion to known units by `5 kg * 9.81 m/s^2 to newton` or `in`, which are names again without units, and errors for sums of different dimensions like `m + s` and functions of quantities like `sin(3 m)`
+//! - physical constants of CODATA like `c`, `h`, `hbar`, `G`, `k_B`, `N_A`, `e_charge`, `mu0` and `eps0` with units and standard uncertainties, after `load(physics)` from `physics_env.txt` or `Env::import` into an existing enviroment
+//! - numbers with a standard uncertainty `3.2 ± 0.1`, propagated to first order through arithmetic and built-in functions, where a defined measurement is correlated with itself, so `x - x` is `0 ± 0`
+//! - exact integer literals `0xFF`, `0o17` and `0b1011`, output in other bases by `base(n, b)`, `hex`, `oct` and `bin`, and bitwise `and`, `or`, `xor`, `shl` and `shr` in two's complement
+//! - integers of fixed width `u8(x)` up to `i64(x)`, which wrap around or after `saturating(x)` saturate, where mixed widths are promoted to the wider one and division by zero is an error, with two's complement digits by `hex` or `bin`, and the bits of sign, exponent and mantissa of a float by `ieee(x)`
+//! - `gradient(f, (x; y), (1; 2))` by forward-mode automatic differentiation without symbolic expansion, where internal dual numbers carry exact derivatives through arithmetic and built-in functions, and central differences stand in for derivatives like that of `zeta` which are not known
+//! - local optima `minimize(f, x, a, b)` by golden-section search, `minimize(f, (x; y), (1; 2))` by BFGS with gradients of dual numbers or else Nelder–Mead, constraints like `x + y = 1` or `x >= 0` after the guess by penalties, and `minimize(p, x)` at the exact critical points of a polynomial or its local minima if it is unbounded, each as the list `point, value`, and `maximize` likewise; an error says where a function like `exp(x)` has no optimum
 //!
 //! ### How to build see [Cargo for Rust](https://doc.rust-lang.org/cargo/guide/working-on-an-existing-project.html)
 //!
@@ -69,7 +94,7 @@ mod cli;
 pub mod prelude {
     pub use crate::{
         cas::{
-            env::{Def, Env},
+            env::{Assumption, Def, Env},
             expr::{
                 atom::{number::Number, symbol::Symbol, Atom},
                 Expr, TypeErr,
@@ -1 +1,772 @@
+use crate::prelude::*;
 
+/// Eval every line in the same environment, like the REPL does.
+fn run(lines: &[&str]) -> Result<(Expr, Env), String> {
+    let mut env = Env::default();
+    let mut result = Expr::Atom(Atom::Symbol(Symbol("nan".to_string())));
+
+    for line in lines {
+        let expr = Expr::parse(line, &env).map_err(|error| error.to_string())?;
+        result = expr.eval(&mut env).map_err(|error| error.to_string())?;
+    }
+
+    Ok((result, env))
+}
+
+/// The last result as the REPL shows it on the `|=` line.
+fn eval(lines: &[&str]) -> Result<String, String> {
+    run(lines).map(|(result, _)| format!("{:?}", result.tidy()))
+}
+
+/// The last result approximated to a float.
+fn approx(lines: &[&str]) -> f64 {
+    let (result, mut env) = run(lines).unwrap_or_else(|error| panic!("{}", error));
+    float(&result, &mut env)
+}
+
+/// The elements of a list result approximated to floats.
+fn approx_list(lines: &[&str]) -> Vec<f64> {
+    let (result, mut env) = run(lines).unwrap_or_else(|error| panic!("{}", error));
+    result
+        .list()
+        .iter()
+        .map(|element| float(element, &mut env))
+        .collect()
+}
+
+fn float(expr: &Expr, env: &mut Env) -> f64 {
+    match expr.number(env) {
+        Ok(number) => number.as_f64(),
+        Err(error) => panic!("{}", error),
+    }
+}
+
+/// Is the error message about this?
+fn fails(lines: &[&str], message: &str) -> bool {
+    match eval(lines) {
+        Err(error) => error.contains(message),
+        Ok(_) => false,
+    }
+}
+
+#[test]
+fn series_order_is_capped() {
+    assert!(fails(&["series(sin x, x, 0, 100000000)"], "out of range"));
+    assert!(fails(&["x^2 + O(x^100000000)"], "out of range"));
+    assert_eq!(
+        eval(&["series(sin x, x, 0, 4)"]),
+        Ok("(+ x (- (/ (^ x 3) 6)) (O (^ x 4)))".to_string())
+    );
+}
+
+#[test]
+fn order_terms_absorb_and_multiply() {
+    assert_eq!(eval(&["O(x^2) - O(x^2)"]), Ok("(O (^ x 2))".to_string()));
+    assert_eq!(eval(&["2 * O(x^2)"]), Ok("(O (^ x 2))".to_string()));
+    assert_eq!(eval(&["x * O(x^2)"]), Ok("(O (^ x 3))".to_string()));
+    assert_eq!(eval(&["O(x^2) * O(x^3)"]), Ok("(O (^ x 5))".to_string()));
+    assert_eq!(
+        eval(&["(O(x^2)) * (O(x^3))"]),
+        Ok("(O (^ x 5))".to_string())
+    );
+    assert_eq!(
+        eval(&["O(x^2) + O(y^2)"]),
+        Ok("(+ (O (^ x 2)) (O (^ y 2)))".to_string())
+    );
+    assert_eq!(
+        eval(&["x + y^3 + O(x^2) + O(y^2)"]),
+        Ok("(+ x (O (^ x 2)) (O (^ y 2)))".to_string())
+    );
+}
+
+#[test]
+fn sum_bounds_are_integers() {
+    assert!(fails(&["sum(k, k, 1, 2.5)"], "integer bounds"));
+    assert!(fails(&["prod(k, k, 1/2, 4)"], "integer bounds"));
+    assert_eq!(eval(&["sum(k, k, 1, 3.0)"]), Ok("6".to_string()));
+}
+
+#[test]
+fn bound_variables_shadow_constants() {
+    assert_eq!(
+        eval(&["sum(i^2, i, 1, n)"]),
+        Ok("(+ (/ n 6) (/ (^ n 2) 2) (/ (^ n 3) 3))".to_string())
+    );
+    assert_eq!(eval(&["diff(i^2, i)"]), Ok("(* 2 i)".to_string()));
+    assert_eq!(
+        eval(&["sum(e^k, e, 1, 3)"]),
+        Ok("(+ 1 (^ 2 k) (^ 3 k))".to_string())
+    );
+    assert_eq!(eval(&["k := 5", "diff(k^2, k)"]), Ok("(* 2 k)".to_string()));
+    assert_eq!(eval(&["e^(i π)"]), Ok("-1".to_string()));
+    assert_eq!(
+        eval(&["diff(x^2 + i x, x)"]),
+        Ok("(+ i (* 2 x))".to_string())
+    );
+}
+
+#[test]
+fn limits_of_indeterminate_forms() {
+    assert_eq!(eval(&["limit((sin x)/x, x, 0)"]), Ok("1".to_string()));
+    assert_eq!(
+        eval(&["limit((1 - cos x)/x^2, x, 0)"]),
+        Ok("1/2".to_string())
+    );
+    assert_eq!(eval(&["limit((1 + 1/x)^x, x, inf)"]), Ok("e".to_string()));
+    assert_eq!(
+        eval(&["limit((x^2 + 1)/(2x^2 - x), x, inf)"]),
+        Ok("1/2".to_string())
+    );
+    assert_eq!(eval(&["limit(exp(-x), x, inf)"]), Ok("0".to_string()));
+}
+
+#[test]
+fn limits_from_one_side() {
+    assert_eq!(eval(&["limit(1/x, x, 0, 1)"]), Ok("inf".to_string()));
+    assert_eq!(eval(&["limit(1/x, x, 0, -1)"]), Ok("(- inf)".to_string()));
+    assert!(fails(&["limit(1/x, x, 0)"], "does not exist"));
+}
+
+#[test]
+fn inf_is_symbolic() {
+    assert_eq!(eval(&["inf + 1"]), Ok("inf".to_string()));
+    assert_eq!(eval(&["1/inf"]), Ok("0".to_string()));
+    assert_eq!(eval(&["inf - inf"]), Ok("nan".to_string()));
+}
+
+#[test]
+fn only_marked_symbols_are_pattern_variables() {
+    assert_eq!(eval(&["replace(x + y, x -> 2)"]), Ok("(+ 2 y)".to_string()));
+    assert_eq!(
+        eval(&["replace(sin(3) + sin(4), sin(a_) -> a_^2)"]),
+        Ok("25".to_string())
+    );
+    assert_eq!(eval(&["(sin t)^2 + (cos t)^2"]), Ok("1".to_string()));
+}
+
+#[test]
+fn rules_can_be_removed() {
+    assert_eq!(
+        eval(&["rule sinh(a_) -> a_ + 1", "sinh(t)"]),
+        Ok("(+ 1 t)".to_string())
+    );
+    assert_eq!(
+        eval(&["rule sinh(a_) -> a_ + 1", "unrule sinh(a_)", "sinh(t)"]),
+        Ok("(sinh t)".to_string())
+    );
+    assert_eq!(
+        eval(&[
+            "unrule (sin a_)^2 + (cos a_)^2 -> 1",
+            "(sin t)^2 + (cos t)^2"
+        ]),
+        Ok("(+ (^ (cos t) 2) (^ (sin t) 2))".to_string())
+    );
+    assert!(fails(&["unrule sinh(a_)"], "no rule"));
+}
+
+#[test]
+fn rules_are_no_variable() {
+    assert_eq!(
+        eval(&["rules := 3", "(sin t)^2 + (cos t)^2"]),
+        Ok("1".to_string())
+    );
+    assert_eq!(
+        eval(&["rule sinh(a_) -> a_ + 1", "rules := 3", "sinh(t)"]),
+        Ok("(+ 1 t)".to_string())
+    );
+    assert_eq!(eval(&["rules := 3", "rules"]), Ok("3".to_string()));
+}
+
+#[test]
+fn subs_replaces_all_at_once() {
+    assert_eq!(
+        eval(&["subs(x - y, x = y, y = x)"]),
+        Ok("(+ (- x) y)".to_string())
+    );
+    assert_eq!(
+        eval(&["subs(x^2 - y, x = y, y = 3)"]),
+        Ok("(+ -3 (^ y 2))".to_string())
+    );
+}
+
+#[test]
+fn subs_replaces_subexpressions_and_simplifies() {
+    assert_eq!(
+        eval(&["subs(sin(x) + cos(x), sin(x) = s)"]),
+        Ok("(+ s (cos x))".to_string())
+    );
+    assert_eq!(
+        eval(&["subs(x^2 + sin(x), x = 2)"]),
+        Ok("(+ 4 (sin 2))".to_string())
+    );
+    assert!(fails(&["subs(x, 2)"], "expected equation"));
+    assert!(fails(&["subs(x + 1)"], "undefined"));
+}
+
+#[test]
+fn trigexpand_of_multiples_is_linear() {
+    assert_eq!(
+        eval(&["trigexpand(cos(3x))"]),
+        Ok("(+ (- (* 3 (cos x) (^ (sin x) 2))) (^ (cos x) 3))".to_string())
+    );
+
+    let expanded = approx(&["subs(trigexpand(sin(40 y)), y = 3/10)"]);
+    assert!((expanded - 12f64.sin()).abs() < 1e-9);
+}
+
+#[test]
+fn combinelog_needs_positive_arguments() {
+    assert_eq!(
+        eval(&["combinelog(ln(a) + ln(b))"]),
+        Ok("(+ (ln a) (ln b))".to_string())
+    );
+    assert_eq!(
+        eval(&["combinelog(ln(-1) + ln(-1))"]),
+        Ok("(* 2 (ln -1))".to_string())
+    );
+    assert_eq!(
+        eval(&["combinelog(2*ln(a))"]),
+        Ok("(* 2 (ln a))".to_string())
+    );
+    assert_eq!(
+        eval(&["assume(a > 0)", "combinelog(ln(a) + ln(b))"]),
+        Ok("(ln (* a b))".to_string())
+    );
+    assert_eq!(
+        eval(&["assume(a > 0)", "combinelog(2*ln(a) - ln(3))"]),
+        Ok("(ln (/ (^ a 2) 3))".to_string())
+    );
+}
+
+#[test]
+fn logarithms_expand_under_assumptions() {
+    assert_eq!(
+        eval(&["expandlog(ln(a*b))"]),
+        Ok("(ln (* a b))".to_string())
+    );
+    assert_eq!(
+        eval(&["assume(a > 0, b > 0)", "expandlog(ln(a*b))"]),
+        Ok("(+ (ln a) (ln b))".to_string())
+    );
+    assert_eq!(eval(&["exp(ln(x))"]), Ok("x".to_string()));
+    assert_eq!(eval(&["a^m * a^n"]), Ok("(^ a (+ m n))".to_string()));
+}
+
+#[test]
+fn logarithms_change_their_base() {
+    assert_eq!(eval(&["log(8, 2)"]), Ok("3".to_string()));
+    assert_eq!(eval(&["log(x, b)"]), Ok("(/ (ln x) (ln b))".to_string()));
+    // from base b to c and back
+    assert_eq!(eval(&["(log(x, b)) * (log(b, c))"]), eval(&["log(x, c)"]));
+    assert_eq!(eval(&["1/(log(b, x))"]), eval(&["log(x, b)"]));
+    assert_eq!(
+        eval(&["assume(b > 0)", "expandlog(log(x, b^2))"]),
+        Ok("(/ (ln x) (* 2 (ln b)))".to_string())
+    );
+}
+
+#[test]
+fn contradicting_assumptions_are_rejected() {
+    assert!(fails(&["assume(x > 0)", "assume(x < 0)"], "contradicts"));
+    assert!(fails(
+        &["assume(n, integer)", "assume(n > 0, n < 1)"],
+        "contradicts"
+    ));
+    assert!(fails(
+        &["assume(x > 0)", "assume(x, negative)"],
+        "contradicts"
+    ));
+    assert_eq!(
+        eval(&[
+            "assume(x > 0)",
+            "assume(x, none)",
+            "assume(x < 0)",
+            "is(x, negative)"
+        ]),
+        Ok("true".to_string())
+    );
+}
+
+#[test]
+fn number_theory_at_the_smallest_integer() {
+    assert!(fails(&["gcd((-2)^127, 0)"], "does not fit"));
+    assert!(fails(&["egcd((-2)^127, 0)"], "does not fit"));
+    assert!(fails(&["divisors((-2)^127)"], "does not fit"));
+    assert!(fails(&["lcm((-2)^127, 3)"], "does not fit"));
+    assert_eq!(eval(&["gcd((-2)^127, 6)"]), Ok("2".to_string()));
+    assert_eq!(
+        eval(&["egcd((-2)^127, 3)"]),
+        Ok("(; 1 (; 1 56713727820156410577229101238628035243))".to_string())
+    );
+    assert_eq!(approx(&["(-2)^127 / (-2)^127"]), 1.0);
+}
+
+#[test]
+fn factorint_of_one_is_the_empty_product() {
+    assert_eq!(eval(&["factorint(1)"]), Ok("(; 1 1)".to_string()));
+    assert_eq!(eval(&["factorint(-1)"]), Ok("(; -1 1)".to_string()));
+    assert_eq!(
+        eval(&["factorint(12)"]),
+        Ok("(; (; 2 2) (; 3 1))".to_string())
+    );
+}
+
+#[test]
+fn combinatorics_overflow_to_inf_instead_of_nan() {
+    let binomial = approx(&["binomial(200, 100)"]);
+    assert!((binomial / 9.054_851_465_610_328e58 - 1.0).abs() < 1e-12);
+
+    assert!((approx(&["170!"]) / 7.257_415_615_307_999e306 - 1.0).abs() < 1e-12);
+    assert_eq!(approx(&["100000!"]), f64::INFINITY);
+    assert_eq!(approx(&["perm(300, 150)"]), f64::INFINITY);
+    assert!(approx(&["catalan(200)"]).is_finite());
+}
+
+#[test]
+fn combinatorics_of_integral_floats() {
+    assert_eq!(approx(&["bell(5.0)"]), 52.0);
+    assert_eq!(approx(&["stirling2(5.0, 2)"]), 15.0);
+    assert_eq!(approx(&["stirling1(5.0, 2)"]), 50.0);
+    assert!((approx(&["bell(60)"]) / 9.769_393_074_670_077e59 - 1.0).abs() < 1e-12);
+    assert!((approx(&["stirling2(50, 25)"]) / 7.453_802_153_273_2e42 - 1.0).abs() < 1e-12);
+    assert_eq!(eval(&["bell(5.0)"]), Ok("52".to_string()));
+}
+
+#[test]
+fn falling_products_do_not_cancel() {
+    assert!((approx(&["perm(10^20, 3)"]) / 1e60 - 1.0).abs() < 1e-12);
+    assert!((approx(&["binomial(10^20, 3)"]) / (1e60 / 6.0) - 1.0).abs() < 1e-12);
+    assert_eq!(eval(&["binomial(5.0, 2)"]), Ok("10".to_string()));
+}
+
+#[test]
+fn stirling_triangles_are_bounded() {
+    assert_eq!(approx(&["stirling2(100000, 50000)"]), f64::INFINITY);
+    assert_eq!(approx(&["stirling1(100000000, 1)"]), f64::INFINITY);
+    assert_eq!(eval(&["stirling2(100000000, 1)"]), Ok("1".to_string()));
+    let near = approx(&["stirling2(100000000, 99999999)"]);
+    assert!((near / 4_999_999_950_000_000.0 - 1.0).abs() < 1e-12);
+
+    // along the diagonal `S(n, n - 2) = binomial(n, 3) + 3 binomial(n, 4)`
+    assert_eq!(
+        eval(&["stirling2(2000, 1998)"]),
+        Ok("1995336832500".to_string())
+    );
+    assert_eq!(eval(&["stirling1(10, 8)"]), Ok("870".to_string()));
+}
+
+#[test]
+fn quantiles_interpolate_at_float_probabilities() {
+    assert_eq!(approx(&["quantile((1;2;3;4;5), 0.25)"]), 2.0);
+    assert_eq!(approx(&["quantile((1;2;3;4;5), 0.5)"]), 3.0);
+    assert!((approx(&["quantile((10;20;30), 0.9)"]) - 28.0).abs() < 1e-12);
+    assert_eq!(eval(&["quantile((10;20;30), 1/4)"]), Ok("15".to_string()));
+    assert_eq!(eval(&["quantile((10;20;30), 1)"]), Ok("30".to_string()));
+}
+
+#[test]
+fn discrete_distributions_of_large_counts() {
+    assert_eq!(
+        eval(&["poissonquantile(0.5, 1000)"]),
+        Ok("1000".to_string())
+    );
+    assert_eq!(eval(&["poissonquantile(0.5, 3)"]), Ok("3".to_string()));
+    assert_eq!(approx(&["poissoncdf(100000000, 3)"]), 1.0);
+    assert_eq!(
+        eval(&["binomialcdf(100000000, 10, 1/2)"]),
+        Ok("1".to_string())
+    );
+    assert_eq!(eval(&["binomialcdf(2, 4, 1/2)"]), Ok("11/16".to_string()));
+    assert_eq!(
+        eval(&["binomialquantile(0.5, 1000, 0.3)"]),
+        Ok("300".to_string())
+    );
+
+    // the float tails agree with the exact sums below them
+    let exact = approx(&["poissoncdf(99, 100)"]);
+    let tail = approx(&["poissoncdf(100, 100)"]) - approx(&["poissonpdf(100, 100)"]);
+    assert!((exact - tail).abs() < 1e-12);
+    assert!(fails(&["poissoncdf(1000, l)"], "expected number"));
+}
+
+#[test]
+fn distribution_parameters_are_checked() {
+    assert!(fails(&["normalpdf(0, 0, -1)"], "positive σ"));
+    assert!(fails(&["normalquantile(0.5, 0, 0)"], "positive σ"));
+    assert!(fails(&["binomialpdf(3, 10, 1.5)"], "probability p"));
+    assert!(fails(&["binomialpdf(3, 2.5, 1/2)"], "natural number n"));
+    assert!(fails(&["poissonpdf(2.5, 3)"], "integer k"));
+    assert!(fails(&["poissonpdf(2, -3)"], "positive λ"));
+    assert_eq!(
+        eval(&["normalpdf(0, 0, s)"]),
+        Ok("(/ 1 (* s (sqrt (* 2 π))))".to_string())
+    );
+}
+
+#[test]
+fn elliptic_integrals_diverge_at_one() {
+    assert_eq!(approx(&["ellipk(1)"]), f64::INFINITY);
+    assert_eq!(approx(&["ellipf(π/2, 1)"]), f64::INFINITY);
+    assert_eq!(approx(&["ellipf(-π/2, 1)"]), -f64::INFINITY);
+    assert!((approx(&["ellipk(1/2)"]) - 1.854_074_677_301_372).abs() < 1e-12);
+}
+
+#[test]
+fn nsolve_finds_no_fake_roots() {
+    assert!(fails(&["nsolve(exp(x), x, 0)"], "could not find a root"));
+    assert!(fails(&["nsolve(x^2 + 1, x, 1)"], "could not find a root"));
+    assert!((approx_list(&["nsolve(x^2 - 2, x, 1)"])[0] - 2f64.sqrt()).abs() < 1e-12);
+    assert!(approx_list(&["nsolve(x^2, x, 1)"])[0].abs() < 1e-12);
+}
+
+#[test]
+fn divergent_integrals_have_no_error_bound() {
+    for integral in [
+        "nintegrate(1, x, 0, inf)",
+        "nintegrate(1/x, x, 0, 1)",
+        "nintegrate(1/x, x, -1, 1)",
+        "nintegrate(1/x, x, 1, inf)",
+    ] {
+        assert_eq!(approx_list(&[integral])[1], f64::INFINITY, "{}", integral);
+    }
+
+    let gauss = approx_list(&["nintegrate(exp(-x^2), x, -inf, inf)"]);
+    assert!((gauss[0] - std::f64::consts::PI.sqrt()).abs() < 1e-12);
+    assert!(gauss[1] < 1e-10);
+    let singular = approx_list(&["nintegrate(1/sqrt(x), x, 0, 1)"]);
+    assert!((singular[0] - 2.0).abs() < 1e-10);
+    assert!(singular[1] < 1e-10);
+    assert_eq!(approx_list(&["nintegrate(x, x, 1, 0)"]), vec![-0.5, 0.0]);
+}
+
+#[test]
+fn dsolve_keeps_biquadratic_roots_exact() {
+    let solution = eval(&["dsolve(y'''' + y = 0, y(x))"]).unwrap();
+    assert!(solution.contains("(exp (* (sqrt 1/2) x))"));
+    assert!(solution.contains("(sin (* (sqrt 1/2) x))"));
+    assert!(!solution.contains("0.707"));
+
+    assert_eq!(
+        eval(&["dsolve(y'''' + 2y'' + y = 0, y(x))"]),
+        Ok("(= y (+ (* C1 (cos x)) (* C2 (sin x)) (* C3 x (cos x)) (* C4 x (sin x))))".to_string())
+    );
+}
+
+#[test]
+fn laplace_transforms_from_tables_and_shifts() {
+    assert_eq!(eval(&["laplace(1, t, s)"]), Ok("(/ 1 s)".to_string()));
+    assert_eq!(
+        eval(&["laplace(t^2, t, s)"]),
+        Ok("(/ 2 (^ s 3))".to_string())
+    );
+    assert_eq!(
+        eval(&["laplace(sin(3 t), t, s)"]),
+        Ok("(/ 3 (+ 9 (^ s 2)))".to_string())
+    );
+    assert_eq!(
+        eval(&["laplace((exp(-t))*(cos(2*t)), t, s)"]),
+        Ok("(/ (+ 1 s) (+ 4 (^ (+ 1 s) 2)))".to_string())
+    );
+    assert!(fails(&["laplace(1/t, t, s)"], "Laplace transform"));
+}
+
+#[test]
+fn inverse_laplace_transforms_by_partial_fractions() {
+    assert_eq!(
+        eval(&["ilaplace(1/(s+2), s, t)"]),
+        Ok("(exp (- (* 2 t)))".to_string())
+    );
+    assert_eq!(
+        eval(&["ilaplace(1/(s*(s+1)), s, t)"]),
+        Ok("(+ 1 (- (exp (- t))))".to_string())
+    );
+    assert_eq!(
+        eval(&["ilaplace(laplace(t*exp(-t), t, s), s, t)"]),
+        Ok("(* t (exp (- t)))".to_string())
+    );
+}
+
+#[test]
+fn fourier_and_z_transforms() {
+    assert_eq!(
+        eval(&["fourier(exp(-x^2), x, w)"]),
+        Ok("(* (sqrt π) (exp (- (/ (^ w 2) 4))))".to_string())
+    );
+    assert_eq!(
+        eval(&["fourier(exp(-abs(x)), x, w)"]),
+        Ok("(/ 2 (+ 1 (^ w 2)))".to_string())
+    );
+    let back = approx(&["subs(ifourier(fourier(exp(-x^2), x, w), w, x), x = 0.7)"]);
+    assert!((back - (-0.49f64).exp()).abs() < 1e-12);
+
+    assert_eq!(
+        eval(&["ztransform(2^n, n, z)"]),
+        Ok("(/ z (+ -2 z))".to_string())
+    );
+    assert_eq!(
+        eval(&["ztransform(n, n, z)"]),
+        Ok("(/ z (^ (+ -1 z) 2))".to_string())
+    );
+    assert_eq!(
+        eval(&["ztransform(n*2^n, n, z)"]),
+        Ok("(/ (* 2 z) (^ (+ -2 z) 2))".to_string())
+    );
+}
+
+#[test]
+fn interval_bounds_are_checked() {
+    assert!(fails(&["[1 .. nan]"], "not a number"));
+    assert!(fails(&["interval(0/0, 1)"], "not a number"));
+    assert!(fails(&["[2 .. 1]"], "reversed"));
+    assert!(fails(&["subs([a .. 2], a = 3)"], "reversed"));
+    assert_eq!(eval(&["[1 .. 2]"]), Ok("[1 .. 2]".to_string()));
+}
+
+#[test]
+fn odd_roots_of_negative_intervals() {
+    let bounds = |line: &str| match run(&[line]).map(|(result, _)| result.as_number()) {
+        Ok(Some(number)) => number.bounds(),
+        _ => panic!("`{}` is no interval", line),
+    };
+    let encloses = |line: &str, lower: f64, upper: f64| {
+        let (a, b) = bounds(line);
+        a <= lower && lower - a < 1e-12 && upper <= b && b - upper < 1e-12
+    };
+
+    assert!(encloses("[-8 .. -1]^(1/3)", -2.0, -1.0));
+    assert!(encloses("[-8 .. 8]^(1/3)", -2.0, 2.0));
+    assert!(encloses("[-8 .. -1]^(2/3)", 1.0, 4.0));
+    assert!(encloses("[-8 .. -1]^(-1/3)", -1.0, -0.5));
+    assert!(encloses("root([-27 .. 8], 3)", -3.0, 2.0));
+    assert!(encloses("sqrt([-4 .. 4])", 0.0, 2.0));
+
+    assert!(fails(&["sqrt([-4 .. -1])"], "no real values"));
+    assert!(fails(&["[-8 .. -1]^0.5"], "no real values"));
+    assert!(fails(&["ln([-2 .. -1])"], "no real values"));
+    assert!(fails(&["x + sqrt([-4 .. -1])"], "no real values"));
+}
+
+#[test]
+fn units_keep_the_built_in_n() {
+    assert_eq!(eval(&["load(units)", "N(π, 5)"]), Ok("3.1416".to_string()));
+    assert_eq!(
+        eval(&["load(units)", "2 kg m / s^2 to newton"]),
+        Ok("(* 2 newton)".to_string())
+    );
+    assert!(fails(&["N := 5"], "can not be redefined"));
+}
+
+#[test]
+fn units_convert_only_to_known_units() {
+    assert!(fails(&["load(units)", "5 kg to foo"], "no unit"));
+    assert!(fails(&["load(units)", "x to m"], "unknown dimension"));
+    assert!(fails(&["load(units)", "5 kg to m"], "can not convert"));
+    assert_eq!(
+        eval(&["load(units)", "1 kWh to J"]),
+        Ok("(* 3600000 J)".to_string())
+    );
+    assert_eq!(
+        eval(&["load(units)", "(3 m)/(2 s) to m/s"]),
+        Ok("(/ (* 3 m) (* 2 s))".to_string())
+    );
+}
+
+#[test]
+fn quantities_bind_their_units() {
+    assert_eq!(
+        eval(&["load(units)", "3 m / 2 s"]),
+        Ok("(/ (* 3 m) (* 2 s))".to_string())
+    );
+    assert_eq!(
+        eval(&["load(units)", "3 m / 2 s to km/hr"]),
+        Ok("(/ (* 27 km) (* 5 hr))".to_string())
+    );
+    assert_eq!(
+        eval(&["load(units)", "3 s^2"]),
+        Ok("(* 3 (^ s 2))".to_string())
+    );
+    assert_eq!(eval(&["load(units)", "1/2 x"]), Ok("(/ x 2)".to_string()));
+}
+
+#[test]
+fn functions_of_quantities_need_plain_numbers() {
+    assert!(fails(&["load(units)", "sin(3 m)"], "of dimension"));
+    assert!(fails(&["load(units)", "exp(2 s)"], "of dimension"));
+    assert_eq!(
+        eval(&["load(units)", "exp(2 s / s)"]),
+        Ok("(exp 2)".to_string())
+    );
+    assert_eq!(
+        eval(&["load(units)", "abs(-3 m)"]),
+        Ok("(abs (- (* 3 m)))".to_string())
+    );
+}
+
+#[test]
+fn to_and_in_are_names_without_units() {
+    assert_eq!(eval(&["in := 4", "in + 1"]), Ok("5".to_string()));
+    assert_eq!(eval(&["to := 2", "load(units)", "to"]), Ok("2".to_string()));
+    assert_eq!(
+        eval(&["load(units)", "2000 m in km"]),
+        Ok("(* 2 km)".to_string())
+    );
+}
+
+#[test]
+fn planck_is_h_and_the_hour_is_hr() {
+    assert_eq!(
+        eval(&["load(physics)", "1 d to hr"]),
+        Ok("(* 24 hr)".to_string())
+    );
+    assert_eq!(
+        eval(&["load(physics)", "2 hr to min"]),
+        Ok("(* 120 min)".to_string())
+    );
+    assert!(eval(&["load(physics)", "h"]).unwrap().contains("662607015"));
+    assert!(eval(&["load(physics)", "h to J s"])
+        .unwrap()
+        .contains("662607015"));
+    assert!(fails(&["load(physics)", "1 d to h"], "can not convert"));
+}
+
+#[test]
+fn uncertain_numbers_carry_their_errors() {
+    assert_eq!(approx(&["x := 3.2 ± 0.1", "x - x"]), 0.0);
+
+    let (result, _) = run(&["x := 3 ± 0.1", "y := 4 ± 0.2", "x * y"]).unwrap();
+    let deviation = match result {
+        Expr::Atom(Atom::Number(number)) => number.deviation(),
+        _ => panic!("expected a number"),
+    };
+    assert!((deviation - (0.4_f64.powi(2) + 0.6_f64.powi(2)).sqrt()).abs() < 1e-12);
+
+    // no registry of errors, that other threads or made up numbers would miss
+    let number = std::thread::spawn(|| Number::uncertain(1.0, 0.5).deviation())
+        .join()
+        .unwrap();
+    assert_eq!(number, 0.5);
+    let made_up = Number::Uncertain(1.0, std::rc::Rc::new([(999, 0.25)]));
+    assert_eq!((made_up.clone() + made_up).deviation(), 0.5);
+}
+
+#[test]
+fn radix_literals_end_at_their_last_digit() {
+    assert!(fails(&["0b102"], "invalid digit `2` in binary"));
+    assert!(fails(&["0o9"], "invalid digit `9` in octal"));
+    assert!(fails(&["0o17x"], "invalid digit `x` in octal"));
+    assert!(fails(&["0xFG"], "invalid digit `G` in hexadecimal"));
+    assert_eq!(eval(&["0b101 + 0o17 + 0xff"]), Ok("275".to_string()));
+    assert_eq!(eval(&["0b11 π"]), Ok("(* 3 π)".to_string()));
+}
+
+#[test]
+fn fixed_width_casts_saturate_at_the_bounds() {
+    assert_eq!(
+        eval(&["i64(1e30)"]),
+        Ok("9223372036854775807i64".to_string())
+    );
+    assert_eq!(
+        eval(&["i64(-1e30)"]),
+        Ok("-9223372036854775808i64".to_string())
+    );
+    assert_eq!(
+        eval(&["u64(1e30)"]),
+        Ok("18446744073709551615u64".to_string())
+    );
+    assert_eq!(eval(&["u64(-1e30)"]), Ok("0u64".to_string()));
+    assert_eq!(
+        eval(&["i64(9.3e18)"]),
+        Ok("9223372036854775807i64".to_string())
+    );
+    assert_eq!(eval(&["i64(-2.5)"]), Ok("-2i64".to_string()));
+
+    assert_eq!(
+        eval(&["x := saturating(i64(5))", "i64(x * 1e30)"]),
+        Ok("9223372036854775807i64".to_string())
+    );
+}
+
+#[test]
+fn fixed_width_division_by_zero_fails() {
+    assert!(fails(&["x := u64(5)", "x / u64(0)"], "zero"));
+    assert!(fails(&["x := i8(-5)", "x / 0"], "zero"));
+    assert!(fails(&["x := u8(5)", "x % u8(0)"], "zero"));
+    assert!(fails(&["u64(5 / u64(0))"], "zero"));
+    assert_eq!(eval(&["x := u8(7)", "x % u8(3)"]), Ok("1u8".to_string()));
+    assert_eq!(eval(&["x := i8(-7)", "x / i8(2)"]), Ok("-3i8".to_string()));
+}
+
+#[test]
+fn mixed_fixed_widths_are_promoted() {
+    assert_eq!(
+        eval(&["x := u8(200)", "x + i16(1000)"]),
+        Ok("1200i16".to_string())
+    );
+    assert_eq!(
+        eval(&["x := i16(1000)", "x + u8(200)"]),
+        Ok("1200i16".to_string())
+    );
+    assert_eq!(eval(&["x := u8(255)", "x + i8(1)"]), Ok("0i8".to_string()));
+    assert_eq!(
+        eval(&["x := saturating(u8(200))", "x + u16(100)"]),
+        Ok("saturating(300u16)".to_string())
+    );
+    assert_eq!(
+        eval(&["x := saturating(u8(200))", "x * u16(1000)"]),
+        Ok("saturating(65535u16)".to_string())
+    );
+    assert_eq!(eval(&["and(u8(255), i16(256))"]), Ok("0i16".to_string()));
+}
+
+#[test]
+fn gradients_without_closed_derivatives_are_numeric() {
+    let zeta = approx(&["gradient(zeta(x), x, 2)"]);
+    assert!((zeta + 0.937_548_254_315_843_8).abs() < 1e-8);
+
+    // digamma' = trigamma, which is π^2/6 - 1 at 2
+    let digamma = approx(&["gradient(digamma(x), x, 2)"]);
+    assert!((digamma - (std::f64::consts::PI.powi(2) / 6.0 - 1.0)).abs() < 1e-8);
+
+    let (result, _) = run(&["zeta(3 ± 0.1)"]).unwrap();
+    match result {
+        Expr::Atom(Atom::Number(number)) => assert!(number.deviation().is_finite()),
+        _ => panic!("expected a number"),
+    }
+}
+
+#[test]
+fn optima_do_not_run_off() {
+    assert!(fails(&["minimize(exp(x), x, 0)"], "has no minimum"));
+    assert!(fails(&["maximize(-exp(x), x, 5)"], "has no maximum"));
+    assert!(fails(&["minimize(exp(-x^2), x, 1)"], "has no minimum"));
+    assert!(fails(
+        &["minimize(exp(x) + y^2, (x; y), (0; 1))"],
+        "has no minimum"
+    ));
+
+    // flat minima still converge
+    assert!(approx_list(&["minimize(x^6, x, 1)"])[0].abs() < 0.1);
+    let minimum = approx_list(&["minimize(x * exp(x), x, 0)"]);
+    assert!((minimum[0] + 1.0).abs() < 1e-6);
+    assert!((approx_list(&["minimize((x - 3)^2, x, 0)"])[0] - 3.0).abs() < 1e-6);
+}
+
+#[test]
+fn unbounded_polynomials_have_local_optima() {
+    assert_eq!(eval(&["maximize(x^3 - 3x, x)"]), Ok("(; -1 2)".to_string()));
+    assert_eq!(eval(&["minimize(x^3 - 3x, x)"]), Ok("(; 1 -2)".to_string()));
+    assert_eq!(
+        eval(&["minimize(x^4 - 2x^2, x)"]),
+        Ok("(; (; 1 -1) -1)".to_string())
+    );
+
+    // the two local minima, each with its own value
+    let (result, _) = run(&["minimize(x^5 - 5x^3 + 4x, x)"]).unwrap();
+    let (points, mut env) = (result.list().remove(0).list(), Env::default());
+    let points: Vec<_> = points.iter().map(|x| float(x, &mut env)).collect();
+    assert_eq!(points.len(), 2);
+    assert!((points[0] - 1.644_432_868_158_268).abs() < 1e-12);
+    assert!((points[1] + 0.543_912_255_902_337_9).abs() < 1e-12);
+    assert!(fails(&["minimize(x^3, x)"], "has no minimum"));
+    assert!(fails(&["maximize(x^2, x)"], "has no maximum"));
+}